@@ -23,25 +23,66 @@ struct VrtAck {
     ok: bool,
 }
 
+/// Get the current time as a VRT (UTC seconds, picoseconds) timestamp pair.
+#[pyfunction]
+fn timestamp_now() -> (u32, u64) {
+    timestamp_from(Timestamp::now())
+}
+
+/// Split a time into whole UTC seconds and picoseconds past that second.
+fn timestamp_from(time: Timestamp) -> (u32, u64) {
+    let secs = time.as_second().try_into().unwrap();
+    let psecs = time.subsec_nanosecond() as u64 * 1000;
+    (secs, psecs)
+}
+
+/// Fill in a packet's UTC/real-time timestamps with the current time.
+fn set_timestamp_now(packet: &mut Vrt) {
+    let (secs, psecs) = timestamp_now();
+    packet.set_integer_timestamp(Some(secs), Tsi::Utc).unwrap();
+    packet
+        .set_fractional_timestamp(Some(psecs), Tsf::RealTimePs)
+        .unwrap();
+}
+
+/// Create a new VRT context packet describing the signal.
+fn create_context_message(
+    stream_id: Option<u32>,
+    bandwidth_hz: Option<f64>,
+    rf_ref_freq_hz: Option<f64>,
+    sample_rate_sps: Option<f64>,
+) -> Result<Vrt, VitaError> {
+    let mut context_packet = Vrt::new_context_packet();
+    context_packet.set_stream_id(stream_id);
+    set_timestamp_now(&mut context_packet);
+
+    let context = context_packet.payload_mut().context_mut().unwrap();
+    context.set_bandwidth_hz(bandwidth_hz)?;
+    context.set_rf_ref_freq_hz(rf_ref_freq_hz)?;
+    context.set_sample_rate_sps(sample_rate_sps)?;
+
+    context_packet.update_packet_size();
+    Ok(context_packet)
+}
+
+/// Create a new VRT signal data packet carrying the given samples.
+fn create_data_message(stream_id: Option<u32>, data: Vec<u8>) -> Result<Vrt, VitaError> {
+    let mut data_packet = Vrt::new_signal_data_packet();
+    data_packet.set_stream_id(stream_id);
+    set_timestamp_now(&mut data_packet);
+    data_packet.set_signal_payload(data)?;
+    Ok(data_packet)
+}
+
 /// Create a new VRT control packet based on input bandwidth and frequency.
 fn create_control_message(
     stream_id: Option<u32>,
     bandwidth_hz: Option<f64>,
     tune_freq_hz: Option<f64>,
-) -> Vrt {
-    // Get current timestamp to fill into the VRT packet.
-    let secs_since_epoch: u32 = Timestamp::now().as_second().try_into().unwrap();
-    let psecs_since_last_epoch_second =
-        Timestamp::now().as_nanosecond() - (secs_since_epoch as f64 * 1e12) as i128;
-
+) -> Result<Vrt, VitaError> {
     let mut control_packet = Vrt::new_control_packet();
     control_packet.set_stream_id(stream_id);
-    control_packet
-        .set_integer_timestamp(Some(secs_since_epoch), Tsi::Utc)
-        .unwrap();
-    control_packet
-        .set_fractional_timestamp(Some(psecs_since_last_epoch_second as u64), Tsf::RealTimePs)
-        .unwrap();
+    set_timestamp_now(&mut control_packet);
 
     // Set up the CAM field to execute the request and request ACKs.
     let mut cam = ControlAckMode::default();
@@ -57,11 +98,11 @@ fn create_control_message(
 
     // Set the data fields.
     let control = command.payload_mut().control_mut().unwrap();
-    control.set_rf_ref_freq_hz(tune_freq_hz);
-    control.set_bandwidth_hz(bandwidth_hz);
+    control.set_rf_ref_freq_hz(tune_freq_hz)?;
+    control.set_bandwidth_hz(bandwidth_hz)?;
 
     control_packet.update_packet_size();
-    control_packet
+    Ok(control_packet)
 }
 
 #[pymethods]
@@ -80,10 +121,11 @@ impl VrtClient {
     }
 
     fn send_cmd(&self, rf_ref_freq_hz: Option<f64>, bandwidth_hz: Option<f64>) -> PyResult<VrtAck> {
-        let command_packet = create_control_message(self.stream_id, rf_ref_freq_hz, bandwidth_hz);
-        self.socket
-            .send_to(&command_packet.to_bytes().unwrap(), &self.dest)
-            .map_err(|e| PyValueError::new_err(format!("failed to send packet: {e}")))?;
+        let command_packet = create_control_message(self.stream_id, rf_ref_freq_hz, bandwidth_hz)
+            .map_err(|e| {
+            PyValueError::new_err(format!("failed to build control packet: {e}"))
+        })?;
+        self.send(&command_packet)?;
         let mut response_buf = [0; 4096];
         match self.socket.recv_from(&mut response_buf) {
             Ok((bytes_read, _src)) => {
@@ -100,13 +142,82 @@ impl VrtClient {
             Err(e) => Err(PyValueError::new_err(format!("error: {e}"))),
         }
     }
+
+    #[pyo3(signature = (bandwidth_hz=None, rf_ref_freq_hz=None, sample_rate_sps=None))]
+    fn send_context(
+        &self,
+        bandwidth_hz: Option<f64>,
+        rf_ref_freq_hz: Option<f64>,
+        sample_rate_sps: Option<f64>,
+    ) -> PyResult<usize> {
+        let context_packet = create_context_message(
+            self.stream_id,
+            bandwidth_hz,
+            rf_ref_freq_hz,
+            sample_rate_sps,
+        )
+        .map_err(|e| PyValueError::new_err(format!("failed to build context packet: {e}")))?;
+        self.send(&context_packet)
+    }
+
+    fn send_data(&self, data: Vec<u8>) -> PyResult<usize> {
+        let data_packet = create_data_message(self.stream_id, data)
+            .map_err(|e| PyValueError::new_err(format!("failed to build data packet: {e}")))?;
+        self.send(&data_packet)
+    }
+}
+
+impl VrtClient {
+    /// Serialize and send a packet, returning the number of bytes sent.
+    fn send(&self, packet: &Vrt) -> PyResult<usize> {
+        let bytes = packet
+            .to_bytes()
+            .map_err(|e| PyValueError::new_err(format!("failed to serialize packet: {e}")))?;
+        self.socket
+            .send_to(&bytes, &self.dest)
+            .map_err(|e| PyValueError::new_err(format!("failed to send packet: {e}")))
+    }
 }
 
 /// A Python module implemented in Rust.
 #[pymodule]
 mod pyo3_demo {
+    #[pymodule_export]
+    use super::timestamp_now;
     #[pymodule_export]
     use super::VrtAck;
     #[pymodule_export]
     use super::VrtClient;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_message() {
+        let packet = create_context_message(Some(7), Some(8e6), Some(100e6), None).unwrap();
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        assert_eq!(parsed.stream_id(), Some(7));
+        assert_eq!(parsed.header().tsi(), Tsi::Utc);
+        let context = parsed.payload().context().unwrap();
+        assert_eq!(context.bandwidth_hz(), Some(8e6));
+        assert_eq!(context.rf_ref_freq_hz(), Some(100e6));
+        assert_eq!(context.sample_rate_sps(), None);
+    }
+
+    #[test]
+    fn data_message() {
+        let packet = create_data_message(None, vec![1, 2, 3, 4]).unwrap();
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        assert_eq!(parsed.stream_id(), None);
+        assert_eq!(parsed.signal_payload().unwrap(), &[1, 2, 3, 4]);
+        assert_eq!(parsed.header().tsf(), Tsf::RealTimePs);
+    }
+
+    #[test]
+    fn timestamp_split() {
+        let time = Timestamp::from_nanosecond(1_700_000_000_250_000_000).unwrap();
+        assert_eq!(timestamp_from(time), (1_700_000_000, 250_000_000_000));
+    }
+}
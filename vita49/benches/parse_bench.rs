@@ -9,12 +9,11 @@ use vita49::prelude::*;
 
 fn criterion_benchmark(c: &mut Criterion) {
     let data_json = include_str!("../tests/spectral_data_packet.json5");
-    let data_packet: Vrt =
-        serde_json5::from_str(&data_json).expect("failed to parse JSON into VRT");
+    let data_packet: Vrt = serde_json5::from_str(data_json).expect("failed to parse JSON into VRT");
     let data_vec = data_packet.to_bytes().unwrap();
     let context_json = include_str!("../tests/context_packet.json5");
     let context_packet: Vrt =
-        serde_json5::from_str(&context_json).expect("failed to parse JSON into VRT");
+        serde_json5::from_str(context_json).expect("failed to parse JSON into VRT");
     let context_vec = context_packet.to_bytes().unwrap();
 
     c.bench_function("Parse signal data", |p| {
@@ -23,6 +22,39 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("Parse context", |p| {
         p.iter(|| Vrt::try_from(black_box(&context_vec[..])).unwrap())
     });
+    c.bench_function("View signal data", |p| {
+        p.iter(|| {
+            let view = VrtView::new(black_box(&data_vec[..])).unwrap();
+            black_box(view.payload());
+        })
+    });
+    c.bench_function("Peek signal data header", |p| {
+        p.iter(|| PacketHeader::peek(black_box(&data_vec[..])).unwrap())
+    });
+    c.bench_function("Serialize context (to_bytes)", |p| {
+        p.iter(|| black_box(&context_packet).to_bytes().unwrap())
+    });
+    let mut buf = Vec::with_capacity(context_vec.len());
+    c.bench_function("Serialize context (write_into)", |p| {
+        p.iter(|| {
+            buf.clear();
+            black_box(&context_packet).write_into(&mut buf).unwrap()
+        })
+    });
+    let words: Vec<u32> = (0..16_384).collect();
+    let mut signal_data = SignalData::new();
+    c.bench_function("Set signal payload (bytes from u32)", |p| {
+        p.iter(|| {
+            let bytes: Vec<u8> = black_box(&words)
+                .iter()
+                .flat_map(|w| w.to_be_bytes())
+                .collect();
+            signal_data.set_payload(bytes).unwrap();
+        })
+    });
+    c.bench_function("Set signal payload (set_payload_u32)", |p| {
+        p.iter(|| signal_data.set_payload_u32(black_box(&words)).unwrap())
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
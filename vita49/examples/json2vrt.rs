@@ -35,6 +35,7 @@ fn main() {
     let file = File::options()
         .write(true)
         .create(true)
+        .truncate(true)
         .open(&path)
         .expect("failed to open VRT file");
     packet
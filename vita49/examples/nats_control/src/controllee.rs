@@ -138,8 +138,8 @@ impl Sdr {
             // If we're sending a query ACK, just get the current values from our
             // "SDR" and send them back to the controller.
             CommandPayload::QueryAck(a) => {
-                a.set_bandwidth_hz(Some(self.bandwidth_hz));
-                a.set_rf_ref_freq_hz(Some(self.tune_freq_hz));
+                a.set_bandwidth_hz(Some(self.bandwidth_hz)).unwrap();
+                a.set_rf_ref_freq_hz(Some(self.tune_freq_hz)).unwrap();
             }
             _ => unimplemented!(),
         }
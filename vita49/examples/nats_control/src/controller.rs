@@ -70,8 +70,8 @@ fn create_control_message(bandwidth_hz: Option<f64>, tune_freq_hz: Option<f64>)
 
     // Set the data fields.
     let control = command.payload_mut().control_mut().unwrap();
-    control.set_rf_ref_freq_hz(tune_freq_hz);
-    control.set_bandwidth_hz(bandwidth_hz);
+    control.set_rf_ref_freq_hz(tune_freq_hz).unwrap();
+    control.set_bandwidth_hz(bandwidth_hz).unwrap();
 
     control_packet.update_packet_size();
     control_packet
@@ -0,0 +1,66 @@
+// SPDX-FileCopyrightText: 2025 The vita49-rs Authors
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+use tokio::net::{TcpListener, UdpSocket};
+use vita49::{prelude::*, VrtStream};
+
+fn describe(packet: &Vrt) {
+    match packet.header().packet_type() {
+        // If it's a signal data packet, just print the payload length
+        PacketType::SignalData => {
+            println!(
+                "Got signal data packet with stream ID 0x{:X} and a payload of length {}",
+                &packet.stream_id().unwrap(),
+                &packet.payload().signal_data().unwrap().payload_size_bytes()
+            );
+        }
+        // If it's a context packet, print the fields (using the pre-
+        // implemented Display trait)
+        PacketType::Context => {
+            println!(
+                "Got context packet:\n{}",
+                &packet.payload().context().unwrap()
+            );
+        }
+        // Other packet types are not covered in this example
+        t => println!("Got unhandled packet type {t:?}"),
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), VitaError> {
+    // TCP is a byte stream, so packets are framed using the size
+    // field in each packet header.
+    let listener = TcpListener::bind("0.0.0.0:4991").await?;
+    tokio::spawn(async move {
+        loop {
+            let Ok((socket, src)) = listener.accept().await else {
+                continue;
+            };
+            tokio::spawn(async move {
+                let mut stream = VrtStream::new(socket);
+                loop {
+                    match stream.next_packet().await {
+                        Ok(Some(packet)) => describe(&packet),
+                        Ok(None) => break,
+                        Err(e) => {
+                            eprintln!("Dropping connection from {src}: {e}");
+                            break;
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    // UDP keeps one packet per datagram, so no framing is needed.
+    let socket = UdpSocket::bind("0.0.0.0:4991").await?;
+    let mut buf = [0; 40960];
+
+    println!("Entering receive loop...");
+    loop {
+        let (bytes_read, _src) = socket.recv_from(&mut buf).await?;
+        let packet = Vrt::try_from(&buf[..bytes_read])?;
+        describe(&packet);
+    }
+}
@@ -4,7 +4,7 @@
 use std::net::UdpSocket;
 use vita49::prelude::*;
 
-fn main() -> Result<(), std::io::Error> {
+fn main() -> Result<(), VitaError> {
     // Bind to a UDP socket
     let socket = UdpSocket::bind("0.0.0.0:4991")?;
     let mut buf = [0; 40960];
@@ -40,25 +40,18 @@ fn main() -> Result<(), std::io::Error> {
                     "Got command packet:\n{}",
                     &packet.payload().command().unwrap()
                 );
-                let command = packet.payload().command().unwrap();
-                // Create an ACK packet based on what the control packet
-                // requested (or just return here if no ACK is requested).
-                let mut reply = if command.cam().execution() {
-                    Vrt::new_exec_ack_packet()
-                } else if command.cam().validation() {
-                    Vrt::new_validation_ack_packet()
-                } else if command.cam().state() {
-                    Vrt::new_query_ack_packet()
-                } else {
+                // Create the ACK packets the control packet requested (or
+                // just return here if no ACK is requested). Each reply
+                // mirrors the stream ID and message ID so the controller
+                // knows which command we're replying to.
+                let Ok(replies) = packet.make_ack_replies() else {
                     continue;
                 };
-                // Mirror some of the header values from the command packet to make
-                // sure the controller knows which command we're replying to.
-                reply.set_stream_id(packet.stream_id());
-                reply.update_packet_size();
 
-                // Send a VITA 49.2 ACK back to the client
-                socket.send_to(&reply.to_bytes()?, src).unwrap();
+                // Send the VITA 49.2 ACKs back to the client
+                for reply in replies {
+                    socket.send_to(&reply.to_bytes()?, src).unwrap();
+                }
             }
             // Other packet types are not covered in this example
             _ => unimplemented!(),
@@ -4,7 +4,7 @@
 use std::net::UdpSocket;
 use vita49::prelude::*;
 
-fn main() -> Result<(), std::io::Error> {
+fn main() -> Result<(), VitaError> {
     // Bind to a UDP socket
     let socket = UdpSocket::bind("0.0.0.0:0")?;
 
@@ -13,8 +13,8 @@ fn main() -> Result<(), std::io::Error> {
     let mut packet = Vrt::new_context_packet();
     packet.set_stream_id(Some(0xDEADBEEF));
     let context = packet.payload_mut().context_mut().unwrap();
-    context.set_rf_ref_freq_hz(Some(100e6));
-    context.set_bandwidth_hz(Some(8e6));
+    context.set_rf_ref_freq_hz(Some(100e6))?;
+    context.set_bandwidth_hz(Some(8e6))?;
     packet.update_packet_size();
 
     // Send the packet
@@ -24,7 +24,7 @@ fn main() -> Result<(), std::io::Error> {
     let mut sig_packet = Vrt::new_signal_data_packet();
     sig_packet.set_stream_id(Some(0xDEADBEEF));
     sig_packet
-        .set_signal_payload(&[1, 2, 3, 4, 5, 6, 7, 8])
+        .set_signal_payload([1, 2, 3, 4, 5, 6, 7, 8])
         .unwrap();
 
     // Send the packet
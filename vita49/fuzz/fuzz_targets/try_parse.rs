@@ -0,0 +1,24 @@
+// SPDX-FileCopyrightText: 2025 The vita49-rs Authors
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vita49::prelude::*;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok((packet, len)) = Vrt::try_parse(data) {
+        assert_eq!(len, packet.packet_size_bytes());
+        // Anything that parses must also serialize and display.
+        let _ = packet.to_bytes();
+        match packet.payload() {
+            Payload::Context(c) => {
+                let _ = format!("{c}");
+            }
+            Payload::Command(c) => {
+                let _ = format!("{c}");
+            }
+            Payload::SignalData(_) | Payload::ExtensionContext(_) => (),
+        }
+    }
+});
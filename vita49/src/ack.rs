@@ -3,11 +3,13 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use crate::{
-    cif7::Cif7Opts, prelude::*, Cif0AckFields, Cif0AckManipulators, Cif1AckFields, Cif2AckFields,
-    Cif3AckFields, ControlAckMode,
+    cif7::Cif7Opts, prelude::*, AckResponse, Cif0AckFields, Cif0AckManipulators, Cif1AckFields,
+    Cif2AckFields, Cif3AckFields, Control, ControlAckMode,
 };
+use alloc::format;
+use alloc::vec::Vec;
+use core::fmt;
 use deku::prelude::*;
-use std::fmt;
 
 /// ACK level indicating if the ACK is a warning or error.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
@@ -104,7 +106,7 @@ pub struct Ack {
 
 impl Ack {
     /// Get the ACK size (in 32-bit words).
-    pub fn size_words(&self) -> u16 {
+    pub fn size_words(&self) -> usize {
         let mut ret = 0;
         if let Some(f) = &self.wif0_fields {
             ret += 1 + f.size_words();
@@ -132,6 +134,158 @@ impl Ack {
         }
         ret
     }
+
+    /// Builds the WIF/EIF fields of a validation (or execution) ACK
+    /// replying to `control`. Each entry of `results` names a field of the
+    /// control packet, as listed by [`Control::present_fields()`], with
+    /// the level and response to report for it; fields without an entry
+    /// were accepted and aren't flagged.
+    ///
+    /// The reply's CAM field must have its warning and/or error bits set
+    /// for the flagged fields to be included in the packet.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::InvalidValue`] if an entry names a field
+    /// that isn't included in `control`.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::{command_prelude::*, prelude::*};
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut control = Control::default();
+    /// control.set_bandwidth_hz(Some(8e6)).unwrap();
+    /// let mut response = AckResponse::default();
+    /// response.set_param_out_of_range();
+    /// let ack = Ack::from_control_validation(
+    ///     &control,
+    ///     &[("bandwidth", AckLevel::Error, response)],
+    /// )?;
+    /// assert_eq!(ack.errors(), [("bandwidth", response)]);
+    /// assert!(ack.warnings().is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_control_validation(
+        control: &Control,
+        results: &[(&str, AckLevel, AckResponse)],
+    ) -> Result<Ack, VitaError> {
+        let requested = control.present_fields();
+        let mut ack = Ack::default();
+        for &(name, level, response) in results {
+            if !requested.contains(&name) {
+                return Err(VitaError::InvalidValue(
+                    format!("{name} isn't included in the control packet").into(),
+                ));
+            }
+            if !ack.set_response_by_name(name, level, response) {
+                return Err(VitaError::InvalidValue(
+                    format!("{name} isn't a CIF field").into(),
+                ));
+            }
+        }
+        Ok(ack)
+    }
+
+    /// Flags the field called `name` in the WIF or EIF words with
+    /// `response`, enabling the CIF it belongs to if needed. Returns
+    /// false, leaving the ACK untouched, if there's no such field.
+    fn set_response_by_name(&mut self, name: &str, level: AckLevel, response: AckResponse) -> bool {
+        let (if0, if1, if2, if3, fields0, fields1, fields2, fields3) = match level {
+            AckLevel::Warning => (
+                &mut self.wif0,
+                &mut self.wif1,
+                &mut self.wif2,
+                &mut self.wif3,
+                &mut self.wif0_fields,
+                &mut self.wif1_fields,
+                &mut self.wif2_fields,
+                &mut self.wif3_fields,
+            ),
+            AckLevel::Error => (
+                &mut self.eif0,
+                &mut self.eif1,
+                &mut self.eif2,
+                &mut self.eif3,
+                &mut self.eif0_fields,
+                &mut self.eif1_fields,
+                &mut self.eif2_fields,
+                &mut self.eif3_fields,
+            ),
+        };
+        let mut word0 = if0.unwrap_or_default();
+        let mut fields = fields0.clone().unwrap_or_default();
+        if fields.set_response(&mut word0, name, response) {
+            *if0 = Some(word0);
+            *fields0 = Some(fields);
+            return true;
+        }
+        let mut word = if1.unwrap_or_default();
+        let mut fields = fields1.clone().unwrap_or_default();
+        if fields.set_response(&mut word, name, response) {
+            word0.set_cif1_enabled();
+            *if0 = Some(word0);
+            *if1 = Some(word);
+            *fields1 = Some(fields);
+            return true;
+        }
+        let mut word = if2.unwrap_or_default();
+        let mut fields = fields2.clone().unwrap_or_default();
+        if fields.set_response(&mut word, name, response) {
+            word0.set_cif2_enabled();
+            *if0 = Some(word0);
+            *if2 = Some(word);
+            *fields2 = Some(fields);
+            return true;
+        }
+        let mut word = if3.unwrap_or_default();
+        let mut fields = fields3.clone().unwrap_or_default();
+        if fields.set_response(&mut word, name, response) {
+            word0.set_cif3_enabled();
+            *if0 = Some(word0);
+            *if3 = Some(word);
+            *fields3 = Some(fields);
+            return true;
+        }
+        false
+    }
+
+    /// Get every field flagged in the WIF words along with its
+    /// warning response, in packet order.
+    pub fn warnings(&self) -> Vec<(&'static str, AckResponse)> {
+        let mut ret = Vec::new();
+        if let Some(f) = &self.wif0_fields {
+            ret.extend(f.responses());
+        }
+        if let Some(f) = &self.wif1_fields {
+            ret.extend(f.responses());
+        }
+        if let Some(f) = &self.wif2_fields {
+            ret.extend(f.responses());
+        }
+        if let Some(f) = &self.wif3_fields {
+            ret.extend(f.responses());
+        }
+        ret
+    }
+
+    /// Get every field flagged in the EIF words along with its
+    /// error response, in packet order.
+    pub fn errors(&self) -> Vec<(&'static str, AckResponse)> {
+        let mut ret = Vec::new();
+        if let Some(f) = &self.eif0_fields {
+            ret.extend(f.responses());
+        }
+        if let Some(f) = &self.eif1_fields {
+            ret.extend(f.responses());
+        }
+        if let Some(f) = &self.eif2_fields {
+            ret.extend(f.responses());
+        }
+        if let Some(f) = &self.eif3_fields {
+            ret.extend(f.responses());
+        }
+        ret
+    }
 }
 
 impl Cif0AckManipulators for Ack {
@@ -163,10 +317,179 @@ impl Cif0AckManipulators for Ack {
 }
 
 impl fmt::Display for Ack {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         writeln!(f, "ACK")?;
-        // TODO: improve printout
-        writeln!(f, "{self:#?}")?;
+        let levels = [
+            (
+                "WIF0",
+                AckLevel::Warning,
+                self.wif0_fields.as_ref().map(|x| x.responses()),
+            ),
+            (
+                "WIF1",
+                AckLevel::Warning,
+                self.wif1_fields.as_ref().map(|x| x.responses()),
+            ),
+            (
+                "WIF2",
+                AckLevel::Warning,
+                self.wif2_fields.as_ref().map(|x| x.responses()),
+            ),
+            (
+                "WIF3",
+                AckLevel::Warning,
+                self.wif3_fields.as_ref().map(|x| x.responses()),
+            ),
+            (
+                "EIF0",
+                AckLevel::Error,
+                self.eif0_fields.as_ref().map(|x| x.responses()),
+            ),
+            (
+                "EIF1",
+                AckLevel::Error,
+                self.eif1_fields.as_ref().map(|x| x.responses()),
+            ),
+            (
+                "EIF2",
+                AckLevel::Error,
+                self.eif2_fields.as_ref().map(|x| x.responses()),
+            ),
+            (
+                "EIF3",
+                AckLevel::Error,
+                self.eif3_fields.as_ref().map(|x| x.responses()),
+            ),
+        ];
+        for (name, level, responses) in levels {
+            let Some(responses) = responses else {
+                continue;
+            };
+            if responses.is_empty() {
+                continue;
+            }
+            writeln!(f, "{name}:")?;
+            for (field, response) in responses {
+                writeln!(f, "  {field}: {level:?}")?;
+                for line in format!("{response}").lines() {
+                    writeln!(f, "    {line}")?;
+                }
+            }
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_response_by_name() {
+        let response = AckResponse::default();
+        let mut ack = Ack::default();
+        assert!(!ack.set_response_by_name("no_such_field", AckLevel::Error, response));
+        assert_eq!(ack, Ack::default());
+
+        assert!(ack.set_response_by_name("aux_bandwidth", AckLevel::Warning, response));
+        assert!(ack.wif0.unwrap().cif1_enabled());
+        assert!(ack.wif1.unwrap().aux_bandwidth());
+        assert_eq!(ack.warnings(), [("aux_bandwidth", response)]);
+        assert!(ack.errors().is_empty());
+    }
+
+    #[test]
+    fn display_lists_set_fields() {
+        let mut ack = Ack::default();
+        let mut response = AckResponse::default();
+        response.set_param_out_of_range();
+        ack.set_bandwidth(AckLevel::Error, Some(response));
+        let mut response = AckResponse::default();
+        response.set_parameter_unsupported_precision();
+        ack.set_rf_ref_freq(AckLevel::Warning, Some(response));
+
+        let s = format!("{ack}");
+        assert!(
+            s.contains("WIF0:\n  rf_ref_freq: Warning\n    - Parameter with unsupported precision")
+        );
+        assert!(s.contains("EIF0:\n  bandwidth: Error\n    - Parameter out of range"));
+        assert!(!s.contains("sample_rate"));
+        assert!(!s.contains("WIF1"));
+    }
+
+    #[test]
+    fn warnings_and_errors() {
+        let mut ack = Ack::default();
+        let mut response = AckResponse::default();
+        response.set_param_out_of_range();
+        ack.set_bandwidth(AckLevel::Error, Some(response));
+
+        assert_eq!(ack.errors(), [("bandwidth", response)]);
+        assert!(ack.warnings().is_empty());
+    }
+
+    #[test]
+    fn from_control_validation() {
+        let mut packet = Vrt::new_control_packet();
+        let command = packet.payload_mut().command_mut().unwrap();
+        let mut cam = command.cam();
+        cam.set_validation();
+        cam.set_warnings_permitted();
+        command.set_cam(cam);
+        let control = command.payload_mut().control_mut().unwrap();
+        control.set_bandwidth_hz(Some(8e6)).unwrap();
+        control.set_sample_rate_sps(Some(10e6)).unwrap();
+        control.set_rf_ref_freq_hz(Some(100e6)).unwrap();
+
+        let mut out_of_range = AckResponse::default();
+        out_of_range.set_param_out_of_range();
+        let mut precision = AckResponse::default();
+        precision.set_parameter_unsupported_precision();
+        let control = packet
+            .payload()
+            .command()
+            .unwrap()
+            .payload()
+            .control()
+            .unwrap();
+        let ack = Ack::from_control_validation(
+            control,
+            &[
+                ("bandwidth", AckLevel::Error, out_of_range),
+                ("sample_rate", AckLevel::Warning, precision),
+            ],
+        )
+        .unwrap();
+
+        let mut reply = packet.make_ack_replies().unwrap().remove(0);
+        let command = reply.payload_mut().command_mut().unwrap();
+        let mut cam = command.cam();
+        cam.set_warnings_permitted();
+        cam.set_warning();
+        cam.set_error();
+        command.set_cam(cam);
+        *command.payload_mut().validation_ack_mut().unwrap() = ack;
+        reply.update_packet_size();
+
+        let parsed = Vrt::try_from(reply.to_bytes().unwrap().as_slice()).unwrap();
+        let ack = parsed
+            .payload()
+            .command()
+            .unwrap()
+            .payload()
+            .validation_ack()
+            .unwrap();
+        assert_eq!(ack.errors(), [("bandwidth", out_of_range)]);
+        assert_eq!(ack.warnings(), [("sample_rate", precision)]);
+
+        assert!(
+            Ack::from_control_validation(control, &[("gain", AckLevel::Error, out_of_range)])
+                .is_err()
+        );
+        assert!(Ack::from_control_validation(
+            control,
+            &[("not_a_field", AckLevel::Warning, precision)]
+        )
+        .is_err());
+    }
+}
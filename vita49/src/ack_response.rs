@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use std::fmt;
+use core::fmt;
 
 use deku::prelude::*;
 
@@ -224,8 +224,8 @@ impl AckResponse {
     // Bit 0 is reserved
 
     /// Returns the size of the ACK response field in 32-bit words.
-    pub fn size_words(&self) -> u16 {
-        (std::mem::size_of_val(self) / std::mem::size_of::<u32>()) as u16
+    pub fn size_words(&self) -> usize {
+        core::mem::size_of_val(self) / core::mem::size_of::<u32>()
     }
 
     /// Returns true if no fields in the response are set.
@@ -235,7 +235,7 @@ impl AckResponse {
 }
 
 impl fmt::Display for AckResponse {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         if self.field_not_executed() {
             writeln!(f, "- Field not executed")?
         }
@@ -3,8 +3,9 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use crate::prelude::*;
+use alloc::vec::Vec;
+use core::fmt;
 use deku::prelude::*;
-use std::fmt;
 
 /// Cancellation packet data structure. This is similar to a control packet, but does not include
 /// data fields for the set CIF fields. In other words, it only contains indicator fields for the
@@ -28,7 +29,7 @@ pub struct Cancellation {
 
 impl Cancellation {
     /// Get the cancellation size (in 32-bit words).
-    pub fn size_words(&self) -> u16 {
+    pub fn size_words(&self) -> usize {
         // Start with 1 32-bit word for the CIF0 field
         let mut ret = 1;
         if self.cif1.is_some() {
@@ -76,10 +77,35 @@ impl Cancellation {
     pub fn cif3_mut(&mut self) -> &mut Option<Cif3> {
         &mut self.cif3
     }
+
+    /// Returns the names of all fields being cancelled, in CIF0, CIF1,
+    /// CIF2, CIF3 order.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::{command_prelude::*, prelude::*};
+    /// let mut cancellation = Cancellation::default();
+    /// cancellation.cif0_mut().set_bandwidth();
+    /// cancellation.cif0_mut().set_rf_ref_freq();
+    /// assert_eq!(cancellation.cancelled_fields(), ["bandwidth", "rf_ref_freq"]);
+    /// ```
+    pub fn cancelled_fields(&self) -> Vec<&'static str> {
+        let mut ret = self.cif0.field_names();
+        if let Some(cif1) = &self.cif1 {
+            ret.extend(cif1.field_names());
+        }
+        if let Some(cif2) = &self.cif2 {
+            ret.extend(cif2.field_names());
+        }
+        if let Some(cif3) = &self.cif3 {
+            ret.extend(cif3.field_names());
+        }
+        ret
+    }
 }
 
 impl fmt::Display for Cancellation {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         writeln!(f, "Cancellation:")?;
         writeln!(f, "{}", self.cif0)?;
         if let Some(c) = self.cif1 {
@@ -90,3 +116,38 @@ impl fmt::Display for Cancellation {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancelled_fields() {
+        let mut packet = Vrt::new_cancellation_packet();
+        let cancellation = packet
+            .payload_mut()
+            .command_mut()
+            .unwrap()
+            .payload_mut()
+            .cancellation_mut()
+            .unwrap();
+        cancellation.cif0_mut().set_bandwidth();
+        cancellation.cif0_mut().set_sample_rate();
+        cancellation.cif0_mut().set_cif1_enabled();
+        let mut cif1 = Cif1::default();
+        cif1.set_spectrum();
+        *cancellation.cif1_mut() = Some(cif1);
+        assert_eq!(
+            cancellation.cancelled_fields(),
+            ["bandwidth", "sample_rate", "spectrum"]
+        );
+
+        packet.update_packet_size();
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        let parsed = parsed.payload().command().unwrap().payload();
+        assert_eq!(
+            parsed.cancellation().unwrap().cancelled_fields(),
+            ["bandwidth", "sample_rate", "spectrum"]
+        );
+    }
+}
@@ -6,20 +6,122 @@ Defines fields and methods related to CIF0 (ANSI/VITA-49.2-2017 9.1).
 Fields here are compatible with VITA 49.0 and later.
 */
 
+use alloc::vec::Vec;
 use core::fmt;
 
 use crate::ack::AckLevel;
 use crate::ack_response::AckResponse;
 use crate::device_id::DeviceId;
+#[cfg(feature = "jiff")]
+use crate::packet_header::Tsi;
+use crate::stream_id::StreamId;
 use crate::{
     cif7::Cif7Opts, context_association_lists::ContextAssociationLists,
     ecef_ephemeris::EcefEphemeris, formatted_gps::FormattedGps, gain::Gain, gps_ascii::GpsAscii,
+    payload_format::PayloadFormat, state_event_indicators::StateAndEventIndicators, VitaError,
 };
+use deku::ctx::Endian;
+use deku::no_std_io::{Read, Seek};
 use deku::prelude::*;
-use fixed::types::extra::{U20, U7};
+use fixed::types::extra::{U20, U6, U7};
 use fixed::{FixedI16, FixedI64, FixedU64};
 use vita49_macros::{ack_field, cif_basic, cif_field, cif_fields, cif_radix, cif_radix_masked};
 
+/// Serializes a single CIF field value as it's sent on the wire.
+pub(crate) fn field_to_bytes<T: DekuWriter<Endian>>(value: &T) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut cursor = deku::no_std_io::Cursor::new(&mut out);
+    let mut writer = Writer::new(&mut cursor);
+    value.to_writer(&mut writer, Endian::Big).ok()?;
+    writer.finalize().ok()?;
+    Some(out)
+}
+
+/// Generates the `sync_indicators()` function of an optional CIF
+/// (CIF1 to CIF3), which makes its indicator bits match the data fields
+/// that are present, dropping the indicator word (and its enable bit in
+/// CIF0) once nothing is left in it.
+macro_rules! optional_cif_sync {
+    ($manipulators:ident, $fields_type:ident, $cif:ident, $cif_mut:ident,
+     $fields:ident, $fields_mut:ident, $enable:ident, $disable:ident) => {
+        /// Makes the indicator bits of this CIF match the data fields
+        /// that are present.
+        pub(crate) fn sync_indicators<T: $manipulators>(t: &mut T) {
+            let mut cif = t.$cif().copied().unwrap_or_default();
+            match t.$fields() {
+                Some(fields) => fields.sync_indicators(&mut cif),
+                None => $fields_type::default().sync_indicators(&mut cif),
+            }
+            if t.$fields().is_some_and(|f| f.empty()) {
+                *t.$fields_mut() = None;
+            }
+            if cif.empty() {
+                *t.$cif_mut() = None;
+                t.cif0_mut().$disable();
+            } else {
+                *t.$cif_mut() = Some(cif);
+                t.cif0_mut().$enable();
+            }
+        }
+    };
+}
+pub(crate) use optional_cif_sync;
+
+/// Generates the `sync_indicators()` method shared by the payloads
+/// carrying CIF0 to CIF3 and CIF7 fields (context, control, and query
+/// ACK packets).
+macro_rules! sync_indicators_method {
+    () => {
+        /// Makes every CIF indicator bit match the presence of its data
+        /// field, so stale bits left by editing the fields directly don't
+        /// produce a malformed packet. Run by
+        /// [`Vrt::sync_indicators()`](crate::Vrt::sync_indicators()).
+        pub(crate) fn sync_indicators(&mut self) {
+            self.cif0_fields.sync_indicators(&mut self.cif0);
+            crate::cif1::sync_indicators(self);
+            crate::cif2::sync_indicators(self);
+            crate::cif3::sync_indicators(self);
+            if self.cif7.is_some() {
+                self.cif0.set_field_attributes_enabled();
+            } else {
+                self.cif0.unset_field_attributes_enabled();
+            }
+        }
+    };
+}
+pub(crate) use sync_indicators_method;
+
+/// Reads a CIF indicator word, returning an error instead of accepting
+/// indicator bits for fields this crate can't parse yet.
+pub(crate) fn read_indicator_word<R: Read + Seek>(
+    reader: &mut Reader<R>,
+    endian: Endian,
+    cif_num: u8,
+    unsupported_bits: u32,
+) -> Result<u32, DekuError> {
+    let word = u32::from_reader_with_ctx(reader, endian)?;
+    if word & unsupported_bits != 0 {
+        return Err(DekuError::Parse(
+            format!(
+                "CIF{cif_num} indicates unsupported fields (bits {:#010x})",
+                word & unsupported_bits
+            )
+            .into(),
+        ));
+    }
+    Ok(word)
+}
+
+/// Returns the names from a CIF field table whose indicator bits are
+/// set in `word`, in table order.
+pub(crate) fn set_field_names(word: u32, names: &[(u32, &'static str)]) -> Vec<&'static str> {
+    names
+        .iter()
+        .filter(|(bit, _)| word & (1 << bit) != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
 /// Base data structure for the CIF0 single-bit indicators.
 #[derive(
     Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, DekuRead, DekuWrite,
@@ -69,59 +171,135 @@ impl Cif0 {
     pub fn empty(&self) -> bool {
         self.0 == 0
     }
+
+    /// Indicator bit and name of each data field.
+    const FIELD_NAMES: &'static [(u32, &'static str)] = &[
+        (30, "reference_point_id"),
+        (29, "bandwidth"),
+        (28, "if_ref_freq"),
+        (27, "rf_ref_freq"),
+        (26, "rf_ref_freq_offset"),
+        (25, "if_band_offset"),
+        (24, "reference_level"),
+        (23, "gain"),
+        (22, "over_range_count"),
+        (21, "sample_rate"),
+        (20, "timestamp_adjustment"),
+        (19, "timestamp_cal_time"),
+        (18, "temperature"),
+        (17, "device_id"),
+        (16, "state_indicators"),
+        (15, "signal_data_payload_format"),
+        (14, "formatted_gps"),
+        (13, "formatted_ins"),
+        (12, "ecef_ephemeris"),
+        (11, "relative_ephemeris"),
+        (10, "ephemeris_ref_id"),
+        (9, "gps_ascii"),
+        (8, "context_association_lists"),
+    ];
+
+    /// Returns the names of the data fields whose indicator bits are set.
+    pub fn field_names(&self) -> Vec<&'static str> {
+        crate::cif0::set_field_names(self.0, Self::FIELD_NAMES)
+    }
 }
 
+// `serde_name` is the Wireshark VRT dissector's short name for the field.
+// See the serde section of the README for the naming rule.
 #[cif_fields(cif0)]
 pub struct Cif0Fields {
+    #[serde_name = "refptid"]
     reference_point_id: u32,
+    #[serde_name = "bw"]
     bandwidth: u64,
+    #[serde_name = "iffreq"]
     if_ref_freq: i64,
+    #[serde_name = "rffreq"]
     rf_ref_freq: u64,
+    #[serde_name = "rffreqoffset"]
     rf_ref_freq_offset: i64,
+    #[serde_name = "ifbandoffset"]
     if_band_offset: i64,
+    #[serde_name = "reflevel"]
     reference_level: i32,
     gain: Gain,
+    #[serde_name = "overrangecount"]
     over_range_count: u32,
+    #[serde_name = "samplerate"]
     sample_rate: u64,
+    #[serde_name = "timestampadjust"]
     timestamp_adjustment: u64,
+    #[serde_name = "timestampcal"]
     timestamp_cal_time: u32,
     temperature: i32,
+    #[serde_name = "deviceid"]
     device_id: DeviceId,
-    state_indicators: u32,
-    signal_data_payload_format: u64,
+    #[serde_name = "stateevent"]
+    state_indicators: StateAndEventIndicators,
+    #[serde_name = "fmt"]
+    signal_data_payload_format: PayloadFormat,
+    #[serde_name = "gps"]
     formatted_gps: FormattedGps,
+    #[serde_name = "ins"]
     formatted_ins: FormattedGps,
+    #[serde_name = "ecefephem"]
     ecef_ephemeris: EcefEphemeris,
+    #[serde_name = "relephem"]
     relative_ephemeris: EcefEphemeris,
+    #[serde_name = "ephemrefid"]
     ephemeris_ref_id: u32,
+    #[serde_name = "gpsascii"]
     gps_ascii: GpsAscii,
+    #[serde_name = "cntxassoclists"]
     context_association_lists: ContextAssociationLists,
 }
 
-#[cif_fields(cif0)]
+#[cif_fields(cif0, ack)]
 pub struct Cif0AckFields {
+    #[serde_name = "refptid"]
     reference_point_id: AckResponse,
+    #[serde_name = "bw"]
     bandwidth: AckResponse,
+    #[serde_name = "iffreq"]
     if_ref_freq: AckResponse,
+    #[serde_name = "rffreq"]
     rf_ref_freq: AckResponse,
+    #[serde_name = "rffreqoffset"]
     rf_ref_freq_offset: AckResponse,
+    #[serde_name = "ifbandoffset"]
     if_band_offset: AckResponse,
+    #[serde_name = "reflevel"]
     reference_level: AckResponse,
     gain: AckResponse,
+    #[serde_name = "overrangecount"]
     over_range_count: AckResponse,
+    #[serde_name = "samplerate"]
     sample_rate: AckResponse,
+    #[serde_name = "timestampadjust"]
     timestamp_adjustment: AckResponse,
+    #[serde_name = "timestampcal"]
     timestamp_cal_time: AckResponse,
     temperature: AckResponse,
+    #[serde_name = "deviceid"]
     device_id: AckResponse,
+    #[serde_name = "stateevent"]
     state_indicators: AckResponse,
+    #[serde_name = "fmt"]
     signal_data_payload_format: AckResponse,
+    #[serde_name = "gps"]
     formatted_gps: AckResponse,
+    #[serde_name = "ins"]
     formatted_ins: AckResponse,
+    #[serde_name = "ecefephem"]
     ecef_ephemeris: AckResponse,
+    #[serde_name = "relephem"]
     relative_ephemeris: AckResponse,
+    #[serde_name = "ephemrefid"]
     ephemeris_ref_id: AckResponse,
+    #[serde_name = "gpsascii"]
     gps_ascii: AckResponse,
+    #[serde_name = "cntxassoclists"]
     context_association_lists: AckResponse,
 }
 
@@ -138,33 +316,173 @@ pub trait Cif0Manipulators {
     /// Get a mutable reference to the packet's CIF0 data fields
     fn cif0_fields_mut(&mut self) -> &mut Cif0Fields;
 
+    /// Get the whole CIF0 indicator word. CIF0 is always included, so
+    /// unlike the other CIFs this isn't optional.
+    fn cif0_word(&self) -> u32 {
+        self.cif0().as_u32()
+    }
+
     cif_basic!(cif0, reference_point_id, reference_point_id, u32);
     cif_radix!(cif0, bandwidth, bandwidth_hz, f64, FixedU64::<U20>);
+    // The spec (9.5.5) allows a negative IF reference frequency, e.g. for
+    // a spectrally inverted or complex baseband IF.
     cif_radix!(cif0, if_ref_freq, if_ref_freq_hz, f64, FixedI64::<U20>);
+    // The spec (9.5.10) also defines the RF reference frequency as two's
+    // complement, but a negative RF frequency has no physical meaning, so
+    // it's kept unsigned: this doubles the usable range, and a word with
+    // the sign bit set reads back as a frequency above 8.79 THz rather
+    // than a negative one.
     cif_radix!(cif0, rf_ref_freq, rf_ref_freq_hz, f64, FixedU64::<U20>);
     cif_radix!(cif0, rf_ref_freq_offset, rf_ref_freq_offset_hz, f64, FixedI64::<U20>);
     cif_radix!(cif0, if_band_offset, if_band_offset_hz, f64, FixedI64::<U20>);
+    // Q8.7 in the lower 16 bits: -256 to +255.9921875 dB in 1/128 dB
+    // steps. Out-of-range values clamp to those limits.
     cif_radix_masked!(cif0, reference_level, reference_level_db, f32, FixedI16::<U7>, i32, i16);
     cif_basic!(cif0, gain, gain, Gain);
     cif_basic!(cif0, over_range_count, over_range_count, u32);
     cif_radix!(cif0, sample_rate, sample_rate_sps, f64, FixedU64::<U20>);
     // TODO: add full support
     cif_basic!(cif0, timestamp_adjustment, timestamp_adjustment, u64);
-    // TODO: add full support
     cif_basic!(cif0, timestamp_cal_time, timestamp_cal_time, u32);
-    // TODO: add full support
     cif_basic!(cif0, temperature, temperature, i32);
+    cif_radix_masked!(cif0, temperature, temperature_c, f32, FixedI16::<U6>, i32, i16);
     cif_basic!(cif0, device_id, device_id, DeviceId);
-    // TODO: add full support
-    cif_basic!(cif0, state_indicators, state_indicators, u32);
-    // TODO: add full support
-    cif_basic!(cif0, signal_data_payload_format, signal_data_payload_format, u64);
+    cif_basic!(cif0, state_indicators, state_indicators, StateAndEventIndicators);
+    cif_basic!(cif0, signal_data_payload_format, signal_data_payload_format, PayloadFormat);
     cif_basic!(cif0, formatted_gps, formatted_gps, FormattedGps);
     cif_basic!(cif0, formatted_ins, formatted_ins, FormattedGps);
     cif_basic!(cif0, ecef_ephemeris, ecef_ephemeris, EcefEphemeris);
     cif_basic!(cif0, relative_ephemeris, relative_ephemeris, EcefEphemeris);
+    cif_basic!(cif0, ephemeris_ref_id, ephemeris_ref_id, u32);
     cif_basic!(cif0, gps_ascii, gps_ascii, GpsAscii);
     cif_basic!(cif0, context_association_lists, context_association_lists, ContextAssociationLists);
+
+    /// Gets the timestamp calibration time field in seconds. Per the
+    /// spec (9.7.3.3), the epoch is given by the packet header's TSI
+    /// field.
+    fn timestamp_cal_time_secs(&self) -> Option<u32> {
+        self.timestamp_cal_time().copied()
+    }
+
+    /// Sets the timestamp calibration time field in seconds since the
+    /// epoch given by the packet header's TSI field, or clears it with
+    /// `None`.
+    fn set_timestamp_cal_time_secs(&mut self, secs: Option<u32>) {
+        self.set_timestamp_cal_time(secs);
+    }
+
+    /// Gets the timestamp calibration time field as a wall-clock time,
+    /// interpreting it with the packet header's `tsi` mode. Returns `None`
+    /// if the field isn't present or `tsi` isn't [`Tsi::Utc`] or
+    /// [`Tsi::Gps`].
+    ///
+    /// GPS time is converted with [`gps_to_utc()`](crate::gps_to_utc)
+    /// using the given GPS-UTC `leap_seconds` offset (usually
+    /// [`GPS_LEAP_SECONDS`](crate::GPS_LEAP_SECONDS)); it's ignored for
+    /// UTC time.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::{prelude::*, GPS_LEAP_SECONDS};
+    /// let mut context = Context::new();
+    /// context.set_timestamp_cal_time_secs(Some(1_700_000_000));
+    /// let time = context
+    ///     .timestamp_cal_time_jiff(Tsi::Utc, GPS_LEAP_SECONDS)
+    ///     .unwrap();
+    /// assert_eq!(time.to_string(), "2023-11-14T22:13:20Z");
+    /// assert_eq!(context.timestamp_cal_time_jiff(Tsi::Other, GPS_LEAP_SECONDS), None);
+    /// ```
+    #[cfg(feature = "jiff")]
+    fn timestamp_cal_time_jiff(&self, tsi: Tsi, leap_seconds: i64) -> Option<jiff::Timestamp> {
+        let secs = self.timestamp_cal_time_secs()?;
+        let unix_secs = crate::gps_time::tsi_to_unix_secs(secs, tsi, leap_seconds)?;
+        jiff::Timestamp::from_second(unix_secs).ok()
+    }
+
+    /// Sets the timestamp calibration time field from a wall-clock time,
+    /// encoded for the packet header's `tsi` mode with the given GPS-UTC
+    /// `leap_seconds` offset. See
+    /// [`Cif0Manipulators::timestamp_cal_time_jiff()`].
+    ///
+    /// # Errors
+    /// Returns [`VitaError::TimestampModeMismatch`] if `tsi` isn't
+    /// [`Tsi::Utc`] or [`Tsi::Gps`], or [`VitaError::OutOfRange`] if the
+    /// time can't be represented from that epoch.
+    #[cfg(feature = "jiff")]
+    fn set_timestamp_cal_time_jiff(
+        &mut self,
+        time: jiff::Timestamp,
+        tsi: Tsi,
+        leap_seconds: i64,
+    ) -> Result<(), VitaError> {
+        let secs = crate::gps_time::unix_secs_to_tsi(time.as_second(), tsi, leap_seconds)?;
+        self.set_timestamp_cal_time_secs(Some(secs));
+        Ok(())
+    }
+
+    /// Gets the reference point ID field as a stream ID. Per the spec
+    /// (9.2), this field holds the Stream ID of the point in the system
+    /// where the context applies.
+    fn reference_point(&self) -> Option<StreamId> {
+        self.reference_point_id().map(|id| StreamId::from(*id))
+    }
+
+    /// Sets the reference point ID field to the stream ID of a
+    /// reference point, or clears it with `None`.
+    fn set_reference_point(&mut self, stream_id: Option<StreamId>) {
+        self.set_reference_point_id(stream_id.map(u32::from));
+    }
+
+    /// Checks that the reference point ID field, if set, holds the
+    /// stream ID of a reference point the caller knows about, as decided
+    /// by `is_known`.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::InvalidValue`] if the reference point ID
+    /// doesn't name a known stream.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::{prelude::*, StreamId};
+    /// let known = [StreamId::new(0x10), StreamId::new(0x20)];
+    /// let mut context = Context::new();
+    /// assert!(context.check_reference_point(|id| known.contains(&id)).is_ok());
+    /// context.set_reference_point(Some(StreamId::new(0x20)));
+    /// assert!(context.check_reference_point(|id| known.contains(&id)).is_ok());
+    /// context.set_reference_point(Some(StreamId::new(0x30)));
+    /// assert!(context.check_reference_point(|id| known.contains(&id)).is_err());
+    /// ```
+    fn check_reference_point<F: Fn(StreamId) -> bool>(&self, is_known: F) -> Result<(), VitaError> {
+        match self.reference_point() {
+            Some(id) if !is_known(id) => Err(VitaError::InvalidValue(
+                format!("reference point ID {id} isn't a known stream ID").into(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Adds `count` to the over-range count field, saturating at
+    /// `u32::MAX`. The field is included (starting from 0) if it wasn't
+    /// already.
+    ///
+    /// The count covers the data packets sent since the previous context
+    /// packet for the stream. Each of those data packets also flags its
+    /// own over-range event with the trailer's over-range indicator (see
+    /// [`Trailer::set_over_range_indicator()`](crate::Trailer::set_over_range_indicator)),
+    /// so an emitter typically adds one per flagged packet.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut context = Context::new();
+    /// context.increment_over_range_count(3);
+    /// context.increment_over_range_count(u32::MAX);
+    /// assert_eq!(context.over_range_count(), Some(&u32::MAX));
+    /// ```
+    fn increment_over_range_count(&mut self, count: u32) {
+        let total = self.over_range_count().copied().unwrap_or(0).saturating_add(count);
+        self.set_over_range_count(Some(total));
+    }
 }
 
 /// Shared trait for manipulating CIF0 ACK fields.
@@ -214,7 +532,7 @@ pub trait Cif0AckManipulators {
 
 impl fmt::Display for Cif0 {
     #[rustfmt::skip]
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         writeln!(f, "CIF0:")?;
         writeln!(f, "  Context field change indicator: {}", self.context_field_changed())?;
         writeln!(f, "  Reference point identifier: {}", self.reference_point_id())?;
@@ -6,6 +6,7 @@ Defines fields and methods related to CIF1 (ANSI/VITA-49.2-2017 9.1).
 Fields here are compatible with VITA 49.2 and later.
 */
 
+use alloc::vec::Vec;
 use core::fmt;
 
 use crate::{
@@ -27,9 +28,18 @@ use vita49_macros::{
 )]
 #[deku(endian = "endian", ctx = "endian: deku::ctx::Endian")]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct Cif1(u32);
+pub struct Cif1(
+    #[deku(
+        reader = "crate::cif0::read_indicator_word(deku::reader, endian, 1, Self::UNSUPPORTED_BITS)"
+    )]
+    u32,
+);
 
 impl Cif1 {
+    /// Indicator bits for fields that aren't supported yet
+    /// (three_d_pointing_vector_struct, array_of_cifs, sector_scan, index_list).
+    const UNSUPPORTED_BITS: u32 = (1 << 28) | (1 << 11) | (1 << 9) | (1 << 7);
+
     cif_field!(phase_offset, 31);
     cif_field!(polarization, 30);
     cif_field!(three_d_pointing_vector, 29);
@@ -61,9 +71,49 @@ impl Cif1 {
     cif_field!(buffer_size, 1);
     // Bit 0 is reserved
 
+    /// Get the CIF field as a raw u32.
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
+
     fn empty(&self) -> bool {
         self.0 == 0
     }
+
+    /// Indicator bit and name of each data field.
+    const FIELD_NAMES: &'static [(u32, &'static str)] = &[
+        (31, "phase_offset"),
+        (30, "polarization"),
+        (29, "three_d_pointing_vector"),
+        (28, "three_d_pointing_vector_struct"),
+        (27, "spatial_scan_type"),
+        (26, "spatial_ref_type"),
+        (25, "beam_widths"),
+        (24, "range"),
+        (20, "eb_over_no_and_ber"),
+        (19, "threshold"),
+        (18, "compression_point"),
+        (17, "second_and_third_order_intercept_points"),
+        (16, "snr_figure"),
+        (15, "aux_freq"),
+        (14, "aux_gain"),
+        (13, "aux_bandwidth"),
+        (11, "array_of_cifs"),
+        (10, "spectrum"),
+        (9, "sector_scan"),
+        (7, "index_list"),
+        (6, "discrete_io_32"),
+        (5, "discrete_io_64"),
+        (4, "health_status"),
+        (3, "v49_spec_compliance"),
+        (2, "version_and_build_code"),
+        (1, "buffer_size"),
+    ];
+
+    /// Returns the names of the data fields whose indicator bits are set.
+    pub fn field_names(&self) -> Vec<&'static str> {
+        crate::cif0::set_field_names(self.0, Self::FIELD_NAMES)
+    }
 }
 
 #[cif_fields(cif1)]
@@ -113,7 +163,7 @@ pub struct Cif1Fields {
     buffer_size: u64,
 }
 
-#[cif_fields(cif1)]
+#[cif_fields(cif1, ack)]
 pub struct Cif1AckFields {
     phase_offset: AckResponse,
     polarization: AckResponse,
@@ -143,6 +193,17 @@ pub struct Cif1AckFields {
     buffer_size: AckResponse,
 }
 
+crate::cif0::optional_cif_sync!(
+    Cif1Manipulators,
+    Cif1Fields,
+    cif1,
+    cif1_mut,
+    cif1_fields,
+    cif1_fields_mut,
+    set_cif1_enabled,
+    unset_cif1_enabled
+);
+
 /// Trait for common CIF1 manipulation methods. Used by Context and
 /// Command packets.
 #[rustfmt::skip]
@@ -160,6 +221,12 @@ pub trait Cif1Manipulators {
     /// Get a mutable reference to the packet's CIF1 data fields
     fn cif1_fields_mut(&mut self) -> &mut Option<Cif1Fields>;
 
+    /// Get the whole CIF1 indicator word, or `None` if CIF1 isn't
+    /// included.
+    fn cif1_word(&self) -> Option<u32> {
+        self.cif1().map(|c| c.as_u32())
+    }
+
     cif_radix_masked!(cif1, phase_offset, phase_offset_radians, f32, FixedI16::<U7>, i32, i16);
     // TODO: add full support
     cif_basic!(cif1, polarization, polarization, i32);
@@ -272,7 +339,7 @@ pub trait Cif1AckManipulators {
 
 impl fmt::Display for Cif1 {
     #[rustfmt::skip]
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         writeln!(f, "CIF1:")?;
         writeln!(f, "  Phase offset: {}", self.phase_offset())?;
         writeln!(f, "  Polarization: {}", self.polarization())?;
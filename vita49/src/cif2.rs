@@ -6,6 +6,8 @@ Defines fields and methods related to CIF2 (ANSI/VITA-49.2-2017 9.1).
 Fields here are compatible with VITA 49.2 and later.
 */
 
+use alloc::vec::Vec;
+
 use crate::command_prelude::*;
 use crate::{ack_response::AckResponse, cif0::Cif0, cif7::Cif7Opts, Cif0AckFields};
 use deku::prelude::*;
@@ -51,9 +53,52 @@ impl Cif2 {
     cif_field!(rf_footprint_range, 3);
     // Bits 0-2 are reserved
 
+    /// Get the CIF field as a raw u32.
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
+
     fn empty(&self) -> bool {
         self.0 == 0
     }
+
+    /// Indicator bit and name of each data field.
+    const FIELD_NAMES: &'static [(u32, &'static str)] = &[
+        (31, "bind"),
+        (30, "cited_sid"),
+        (29, "sibling_sid"),
+        (28, "parent_sid"),
+        (27, "child_sid"),
+        (26, "cited_message_id"),
+        (25, "controllee_id"),
+        (24, "controllee_uuid"),
+        (23, "controller_id"),
+        (22, "controller_uuid"),
+        (21, "info_source_id"),
+        (20, "track_id"),
+        (19, "country_code"),
+        (18, "operator"),
+        (17, "platform_class"),
+        (16, "platform_instance"),
+        (15, "platform_display"),
+        (14, "ems_device_class"),
+        (13, "ems_device_type"),
+        (12, "ems_device_instance"),
+        (11, "modulation_class"),
+        (10, "modulation_type"),
+        (9, "function_id"),
+        (8, "mode_id"),
+        (7, "event_id"),
+        (6, "function_priority_id"),
+        (5, "comms_priority_id"),
+        (4, "rf_footprint"),
+        (3, "rf_footprint_range"),
+    ];
+
+    /// Returns the names of the data fields whose indicator bits are set.
+    pub fn field_names(&self) -> Vec<&'static str> {
+        crate::cif0::set_field_names(self.0, Self::FIELD_NAMES)
+    }
 }
 
 #[cif_fields(cif2)]
@@ -90,7 +135,7 @@ pub struct Cif2Fields {
     rf_footprint_range: u32,
 }
 
-#[cif_fields(cif2)]
+#[cif_fields(cif2, ack)]
 pub struct Cif2AckFields {
     bind: AckResponse,
     cited_sid: AckResponse,
@@ -123,6 +168,17 @@ pub struct Cif2AckFields {
     rf_footprint_range: AckResponse,
 }
 
+crate::cif0::optional_cif_sync!(
+    Cif2Manipulators,
+    Cif2Fields,
+    cif2,
+    cif2_mut,
+    cif2_fields,
+    cif2_fields_mut,
+    set_cif2_enabled,
+    unset_cif2_enabled
+);
+
 /// Trait for common CIF2 manipulation methods. Used by Context and
 /// Command packets.
 #[rustfmt::skip]
@@ -140,15 +196,37 @@ pub trait Cif2Manipulators {
     /// Get a mutable reference to the packet's CIF2 data fields
     fn cif2_fields_mut(&mut self) -> &mut Option<Cif2Fields>;
 
-    cif_basic!(cif2, bind, bind, u32);
-    cif_basic!(cif2, cited_sid, cited_sid, u32);
+    /// Get the whole CIF2 indicator word, or `None` if CIF2 isn't
+    /// included.
+    fn cif2_word(&self) -> Option<u32> {
+        self.cif2().map(|c| c.as_u32())
+    }
+
+    cif_basic!(
+        /// The bind field identifies the stream a set of streams is bound
+        /// to (e.g. to group streams from the same device).
+        cif2, bind, bind, u32
+    );
+    cif_basic!(
+        /// The CITED SID field holds the stream ID of the stream this
+        /// packet refers to.
+        cif2, cited_sid, cited_sid, u32
+    );
     cif_basic!(cif2, sibling_sid, sibling_sid, u32);
     cif_basic!(cif2, parent_sid, parent_sid, u32);
     cif_basic!(cif2, child_sid, child_sid, u32);
     cif_basic!(cif2, cited_message_id, cited_message_id, u32);
-    cif_basic!(cif2, controllee_id, controllee_id, u32);
+    cif_basic!(
+        /// The controllee ID field holds the 32-bit identifier of the
+        /// controllable entity the packet refers to.
+        cif2, controllee_id, controllee_id, u32
+    );
     cif_basic!(cif2, controllee_uuid, controllee_uuid, u128);
-    cif_basic!(cif2, controller_id, controller_id, u32);
+    cif_basic!(
+        /// The controller ID field holds the 32-bit identifier of the
+        /// controlling entity the packet refers to.
+        cif2, controller_id, controller_id, u32
+    );
     cif_basic!(cif2, controller_uuid, controller_uuid, u128);
     cif_basic!(cif2, info_source_id, info_source_id, u32);
     cif_basic!(cif2, track_id, track_id, u32);
@@ -277,3 +355,39 @@ impl Cif2Fields {
             .map(|ems_class| (ems_class & (1 << 12)) > 0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn identification_fields_round_trip() {
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_cited_sid(Some(0xCAFE));
+        context.set_bind(Some(3));
+        context.set_controllee_id(Some(0x1234));
+        context.set_controller_id(Some(0x5678));
+        assert!(Cif2Manipulators::cif0(context).cif2_enabled());
+        let cif2 = context.cif2().unwrap();
+        assert!(cif2.cited_sid() && cif2.bind());
+        assert!(cif2.controllee_id() && cif2.controller_id());
+        packet.update_packet_size();
+
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        assert_eq!(parsed, packet);
+        let context = parsed.payload().context().unwrap();
+        assert_eq!(context.cited_sid(), Some(&0xCAFE));
+        assert_eq!(context.bind(), Some(&3));
+        assert_eq!(context.controllee_id(), Some(&0x1234));
+        assert_eq!(context.controller_id(), Some(&0x5678));
+
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_cited_sid(None);
+        context.set_bind(None);
+        context.set_controllee_id(None);
+        context.set_controller_id(None);
+        assert!(!Cif2Manipulators::cif0(context).cif2_enabled());
+        assert!(context.cif2().is_none());
+    }
+}
@@ -6,8 +6,10 @@ Defines fields and methods related to CIF3 (ANSI/VITA-49.2-2017 9.1).
 Fields here are compatible with VITA 49.2 and later.
 */
 
+use alloc::vec::Vec;
+
 use crate::command_prelude::*;
-use crate::{ack_response::AckResponse, cif0::Cif0, cif7::Cif7Opts};
+use crate::{ack_response::AckResponse, cif0::Cif0, cif7::Cif7Opts, TimestampDetails};
 use deku::prelude::*;
 use fixed::{types::extra::U6, FixedI16};
 use vita49_macros::{
@@ -20,9 +22,18 @@ use vita49_macros::{
 )]
 #[deku(endian = "endian", ctx = "endian: deku::ctx::Endian")]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct Cif3(u32);
+pub struct Cif3(
+    #[deku(
+        reader = "crate::cif0::read_indicator_word(deku::reader, endian, 3, Self::UNSUPPORTED_BITS)"
+    )]
+    u32,
+);
 
 impl Cif3 {
+    /// Indicator bits for fields that aren't supported yet
+    /// (age, shelf_life).
+    const UNSUPPORTED_BITS: u32 = (1 << 17) | (1 << 16);
+
     cif_field!(timestamp_details, 31);
     cif_field!(timestamp_skew, 30);
     // Bits 28-29 are reserved
@@ -47,15 +58,47 @@ impl Cif3 {
     cif_field!(network_id, 1);
     // Bit 0 is reserved
 
+    /// Get the CIF field as a raw u32.
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
+
     fn empty(&self) -> bool {
         self.0 == 0
     }
+
+    /// Indicator bit and name of each data field.
+    const FIELD_NAMES: &'static [(u32, &'static str)] = &[
+        (31, "timestamp_details"),
+        (30, "timestamp_skew"),
+        (27, "rise_time"),
+        (26, "fall_time"),
+        (25, "offset_time"),
+        (24, "pulse_width"),
+        (23, "period"),
+        (22, "duration"),
+        (21, "dwell"),
+        (20, "jitter"),
+        (17, "age"),
+        (16, "shelf_life"),
+        (7, "air_temp"),
+        (6, "ground_temp"),
+        (5, "humidity"),
+        (4, "barometric_pressure"),
+        (3, "sea_and_swell_state"),
+        (2, "tropospheric_state"),
+        (1, "network_id"),
+    ];
+
+    /// Returns the names of the data fields whose indicator bits are set.
+    pub fn field_names(&self) -> Vec<&'static str> {
+        crate::cif0::set_field_names(self.0, Self::FIELD_NAMES)
+    }
 }
 
 #[cif_fields(cif3)]
 pub struct Cif3Fields {
-    // TODO: add full support
-    timestamp_details: u64,
+    timestamp_details: TimestampDetails,
     timestamp_skew: i64,
     rise_time: i64,
     fall_time: i64,
@@ -78,7 +121,7 @@ pub struct Cif3Fields {
     network_id: u32,
 }
 
-#[cif_fields(cif3)]
+#[cif_fields(cif3, ack)]
 pub struct Cif3AckFields {
     timestamp_details: AckResponse,
     timestamp_skew: AckResponse,
@@ -101,6 +144,17 @@ pub struct Cif3AckFields {
     network_id: AckResponse,
 }
 
+crate::cif0::optional_cif_sync!(
+    Cif3Manipulators,
+    Cif3Fields,
+    cif3,
+    cif3_mut,
+    cif3_fields,
+    cif3_fields_mut,
+    set_cif3_enabled,
+    unset_cif3_enabled
+);
+
 /// Trait for common CIF3 manipulation methods. Used by Context and
 /// Command packets.
 #[rustfmt::skip]
@@ -118,9 +172,36 @@ pub trait Cif3Manipulators {
     /// Get a mutable reference to the packet's CIF3 data fields
     fn cif3_fields_mut(&mut self) -> &mut Option<Cif3Fields>;
 
-    // TODO: add full support
-    cif_basic!(cif3, timestamp_details, timestamp_details, u64);
-    cif_basic!(cif3, timestamp_skew, timestamp_skew, i64);
+    /// Get the whole CIF3 indicator word, or `None` if CIF3 isn't
+    /// included.
+    fn cif3_word(&self) -> Option<u32> {
+        self.cif3().map(|c| c.as_u32())
+    }
+
+    cif_basic!(
+        /// The timestamp details field (ANSI/VITA-49.2-2017 section 9.7.3.1)
+        /// is a 64-bit field describing the timestamps in the packet. See
+        /// [`TimestampDetails`] for its sub-fields.
+        ///
+        /// # Example
+        /// ```
+        /// use vita49::{prelude::*, TimestampDetails};
+        /// let mut packet = Vrt::new_context_packet();
+        /// let context = packet.payload_mut().context_mut().unwrap();
+        /// let mut details = TimestampDetails::default();
+        /// details.set_global(true);
+        /// details.set_timestamp_offset(1000);
+        /// context.set_timestamp_details(Some(details));
+        /// assert!(context.cif3().unwrap().timestamp_details());
+        /// assert!(context.timestamp_details().unwrap().global());
+        /// assert_eq!(context.timestamp_details().unwrap().timestamp_offset(), 1000);
+        /// ```
+        cif3, timestamp_details, timestamp_details, TimestampDetails
+    );
+    cif_basic!(
+        /// The timestamp skew field is covered in ANSI/VITA-49.2-2017 section 9.7.3.2.
+        cif3, timestamp_skew, timestamp_skew, i64
+    );
     cif_basic!(cif3, rise_time, rise_time, i64);
     cif_basic!(cif3, fall_time, fall_time, i64);
     cif_basic!(cif3, offset_time, offset_time, i64);
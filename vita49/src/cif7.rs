@@ -17,11 +17,14 @@ Instead, each CIF field has an equivalent `*_attributes` field which is a vector
 of the main CIF field type.
 
 So, a user wishing to use CIF7 would need to do some additional work to correlate
-the vector of values with the statistical fields in CIF7.
+the vector of values with the statistical fields in CIF7. [`Cif7::attribute_names()`]
+`Context::label_attributes()` and `Context::label_attribute_values()`
+help with this.
 
 See ANSI/VITA-49.2-2017 section 9.12 for additional details.
 */
 
+use alloc::vec::Vec;
 use deku::prelude::*;
 use vita49_macros::cif_field;
 
@@ -55,6 +58,40 @@ impl Cif7 {
     pub fn num_set(&self) -> usize {
         u32::count_ones(self.0) as usize
     }
+
+    /// Returns the names of the attributes carried in each field's
+    /// `*_attributes` vector, in order. These are the set bits other than
+    /// `current`, which is sent as the main field value, from the most
+    /// significant bit down.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut cif7 = Cif7::default();
+    /// cif7.set_current();
+    /// cif7.set_max();
+    /// cif7.set_average();
+    /// assert_eq!(cif7.attribute_names(), ["average", "max"]);
+    /// ```
+    pub fn attribute_names(&self) -> Vec<&'static str> {
+        [
+            (self.average(), "average"),
+            (self.median(), "median"),
+            (self.std_dev(), "std_dev"),
+            (self.max(), "max"),
+            (self.min(), "min"),
+            (self.precision(), "precision"),
+            (self.accuracy(), "accuracy"),
+            (self.first_derivative(), "first_derivative"),
+            (self.second_derivative(), "second_derivative"),
+            (self.third_derivative(), "third_derivative"),
+            (self.probability(), "probability"),
+            (self.belief(), "belief"),
+        ]
+        .into_iter()
+        .filter_map(|(set, name)| set.then_some(name))
+        .collect()
+    }
 }
 
 /// Structure representing the state of CI7.
@@ -15,11 +15,45 @@ use deku::prelude::*;
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClassIdentifier {
     word_1: u32,
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "icc", alias = "information_class_code")
+    )]
     information_class_code: u16,
+    #[cfg_attr(feature = "serde", serde(rename = "pcc", alias = "packet_class_code"))]
     packet_class_code: u16,
 }
 
 impl ClassIdentifier {
+    /// Creates a class identifier from an OUI, information class code
+    /// and packet class code, with no padding bits.
+    ///
+    /// Note: only the least significant 24 bits of `oui` are used.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let class_id = ClassIdentifier::new(0xFF5654, 0x1, 0x2);
+    /// assert_eq!(class_id.oui(), 0xFF5654);
+    /// assert_eq!(class_id.information_class_code(), 0x1);
+    /// assert_eq!(class_id.packet_class_code(), 0x2);
+    /// ```
+    pub fn new(oui: u32, information_class_code: u16, packet_class_code: u16) -> ClassIdentifier {
+        ClassIdentifier {
+            word_1: oui & 0xFF_FFFF,
+            information_class_code,
+            packet_class_code,
+        }
+    }
+
+    /// Builds a class identifier from its two raw 32-bit words.
+    pub(crate) fn from_words(word_1: u32, word_2: u32) -> ClassIdentifier {
+        ClassIdentifier {
+            word_1,
+            information_class_code: (word_2 >> 16) as u16,
+            packet_class_code: word_2 as u16,
+        }
+    }
     /// Gets the number of padding bits.
     /// # Example
     /// ```
@@ -55,7 +89,7 @@ impl ClassIdentifier {
     /// Note: while this API takes a 32-bit integer, only the least
     /// significant 24 bits are used.
     pub fn set_oui(&mut self, oui: u32) {
-        self.word_1 = self.word_1 & !(0xFF_FFFF) | oui;
+        self.word_1 = self.word_1 & !(0xFF_FFFF) | (oui & 0xFF_FFFF);
     }
 
     /// Gets the information class code.
@@ -76,3 +110,43 @@ impl ClassIdentifier {
         self.packet_class_code = code;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn class_id_round_trip() {
+        let mut packet = Vrt::new_signal_data_packet();
+        let mut class_id = ClassIdentifier::new(0x12_3456, 0xABCD, 0x1357);
+        class_id.set_pad_bit_count(8);
+        packet.set_class_id(Some(class_id));
+        assert!(packet.header().class_id_included());
+        packet.update_packet_size();
+
+        let bytes = packet.to_bytes().unwrap();
+        assert_eq!(
+            &bytes[8..16],
+            &[0x40, 0x12, 0x34, 0x56, 0xAB, 0xCD, 0x13, 0x57]
+        );
+        let parsed = Vrt::try_from(&bytes[..]).unwrap();
+        let class_id = parsed.class_id().unwrap();
+        assert_eq!(class_id.oui(), 0x12_3456);
+        assert_eq!(class_id.information_class_code(), 0xABCD);
+        assert_eq!(class_id.packet_class_code(), 0x1357);
+        assert_eq!(class_id.pad_bit_count(), 8);
+
+        let mut packet = parsed;
+        packet.set_class_id(None);
+        assert!(!packet.header().class_id_included());
+    }
+
+    #[test]
+    fn oui_is_masked() {
+        let mut class_id = ClassIdentifier::default();
+        class_id.set_pad_bit_count(3);
+        class_id.set_oui(0xFFFF_FFFF);
+        assert_eq!(class_id.oui(), 0xFF_FFFF);
+        assert_eq!(class_id.pad_bit_count(), 3);
+    }
+}
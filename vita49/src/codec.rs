@@ -0,0 +1,145 @@
+// SPDX-FileCopyrightText: 2025 The vita49-rs Authors
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+A [`tokio_util::codec`] codec for VRT packets on byte streams
+(e.g. a `TcpStream`). Only available with the `codec` feature.
+*/
+
+use bytes::{Buf, BufMut, BytesMut};
+use std::io::{Error, ErrorKind};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::prelude::*;
+
+/// Codec that frames VRT packets using the packet size field of each
+/// header, for use with [`tokio_util::codec::Framed`] and friends.
+///
+/// The decoder waits until a full header word is buffered, then until
+/// the whole packet it describes is buffered, before parsing it.
+///
+/// # Example
+/// ```no_run
+/// use tokio::net::TcpStream;
+/// use tokio_util::codec::Framed;
+/// use vita49::VrtCodec;
+/// # async fn run() -> std::io::Result<()> {
+/// let socket = TcpStream::connect("127.0.0.1:4991").await?;
+/// let framed = Framed::new(socket, VrtCodec::new());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct VrtCodec;
+
+impl VrtCodec {
+    /// Creates a new codec.
+    pub fn new() -> VrtCodec {
+        VrtCodec
+    }
+}
+
+impl Decoder for VrtCodec {
+    type Item = Vrt;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Vrt>, Error> {
+        if src.len() < 4 {
+            return Ok(None);
+        }
+        let header = PacketHeader::from_u32(u32::from_be_bytes([src[0], src[1], src[2], src[3]]));
+        let packet_len = header.packet_size_bytes();
+        if packet_len < 4 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                VitaError::InvalidPacketSize,
+            ));
+        }
+        if src.len() < packet_len {
+            src.reserve(packet_len - src.len());
+            return Ok(None);
+        }
+        let packet = Vrt::try_from(&src[..packet_len]);
+        src.advance(packet_len);
+        Ok(Some(packet?))
+    }
+}
+
+impl Encoder<Vrt> for VrtCodec {
+    type Error = Error;
+
+    fn encode(&mut self, packet: Vrt, dst: &mut BytesMut) -> Result<(), Error> {
+        self.encode(&packet, dst)
+    }
+}
+
+impl Encoder<&Vrt> for VrtCodec {
+    type Error = Error;
+
+    fn encode(&mut self, packet: &Vrt, dst: &mut BytesMut) -> Result<(), Error> {
+        let bytes = packet.to_bytes()?;
+        dst.reserve(bytes.len());
+        dst.put_slice(&bytes);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_packets() -> (Vrt, Vrt) {
+        let mut data = Vrt::new_signal_data_packet();
+        data.set_stream_id(Some(0x1234));
+        data.set_signal_payload([1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        let mut context = Vrt::new_context_packet();
+        context
+            .payload_mut()
+            .context_mut()
+            .unwrap()
+            .set_bandwidth_hz(Some(8e6))
+            .unwrap();
+        context.update_packet_size();
+        (data, context)
+    }
+
+    #[test]
+    fn decode_byte_by_byte() {
+        let (data, context) = test_packets();
+        let mut codec = VrtCodec::new();
+        let mut wire = BytesMut::new();
+        codec.encode(&data, &mut wire).unwrap();
+        codec.encode(context.clone(), &mut wire).unwrap();
+
+        let mut src = BytesMut::new();
+        let mut decoded = Vec::new();
+        for byte in wire {
+            src.put_u8(byte);
+            if let Some(packet) = codec.decode(&mut src).unwrap() {
+                decoded.push(packet);
+            }
+        }
+        assert_eq!(decoded, [data, context]);
+        assert!(src.is_empty());
+        assert!(codec.decode_eof(&mut src).unwrap().is_none());
+    }
+
+    #[test]
+    fn partial_packet_at_eof() {
+        let (data, _) = test_packets();
+        let mut codec = VrtCodec::new();
+        let mut src = BytesMut::new();
+        codec.encode(&data, &mut src).unwrap();
+        src.truncate(6);
+        assert!(codec.decode(&mut src).unwrap().is_none());
+        assert!(codec.decode_eof(&mut src).is_err());
+    }
+
+    #[test]
+    fn invalid_size() {
+        let mut codec = VrtCodec::new();
+        let mut src = BytesMut::from(&[0x10, 0x00, 0x00, 0x00][..]);
+        let err = codec.decode(&mut src).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
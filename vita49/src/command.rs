@@ -13,6 +13,19 @@ use crate::{
 };
 use deku::prelude::*;
 
+/// Controllee or controller identity. The 32-bit ID and 128-bit UUID
+/// forms are mutually exclusive (ANSI/VITA-49.2-2017 section 8.2.1).
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum Identity {
+    /// 32-bit identifier.
+    Id(u32),
+    /// 128-bit UUID.
+    Uuid(u128),
+    /// No identity included.
+    #[default]
+    None,
+}
+
 /// Main command payload structure.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, DekuRead, DekuWrite)]
 #[deku(
@@ -201,6 +214,60 @@ impl Command {
         Ok(())
     }
 
+    /// Gets the controllee identity from whichever of the ID or UUID
+    /// is set.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::{command_prelude::*, prelude::*};
+    /// let mut packet = Vrt::new_control_packet();
+    /// let command = packet.payload_mut().command_mut().unwrap();
+    /// assert_eq!(command.controllee(), Identity::None);
+    /// command.set_controllee_uuid(Some(0xABCD)).unwrap();
+    /// assert_eq!(command.controllee(), Identity::Uuid(0xABCD));
+    /// ```
+    pub fn controllee(&self) -> Identity {
+        Identity::join(self.controllee_id, self.controllee_uuid)
+    }
+
+    /// Gets the controller identity from whichever of the ID or UUID
+    /// is set.
+    pub fn controller(&self) -> Identity {
+        Identity::join(self.controller_id, self.controller_uuid)
+    }
+
+    /// Sets the controllee identity, replacing whichever of the ID or UUID
+    /// was set before.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::{command_prelude::*, prelude::*};
+    /// let mut packet = Vrt::new_control_packet();
+    /// let command = packet.payload_mut().command_mut().unwrap();
+    /// command.set_controllee(Identity::Id(7));
+    /// command.set_controllee(Identity::Uuid(0xABCD));
+    /// assert_eq!(command.controllee_id(), None);
+    /// assert_eq!(command.controllee_uuid(), Some(0xABCD));
+    /// ```
+    pub fn set_controllee(&mut self, identity: Identity) {
+        let (id, uuid) = identity.split();
+        self.controllee_id = None;
+        self.controllee_uuid = None;
+        // Neither can fail now that both fields are cleared.
+        let _ = self.set_controllee_id(id);
+        let _ = self.set_controllee_uuid(uuid);
+    }
+
+    /// Sets the controller identity, replacing whichever of the ID or UUID
+    /// was set before.
+    pub fn set_controller(&mut self, identity: Identity) {
+        let (id, uuid) = identity.split();
+        self.controller_id = None;
+        self.controller_uuid = None;
+        let _ = self.set_controller_id(id);
+        let _ = self.set_controller_uuid(uuid);
+    }
+
     /// Get the controllee UUID.
     pub fn controllee_uuid(&self) -> Option<u128> {
         self.controllee_uuid
@@ -259,8 +326,56 @@ impl Command {
         &mut self.command_payload
     }
 
+    /// Checks that the CAM field agrees with the payload type. For ACK
+    /// payloads, the CAM must select exactly one of the validation,
+    /// execution, or query ACK types, and it must be the one stored.
+    ///
+    /// Serialization doesn't check this, and a mismatched packet won't
+    /// parse back into the same payload, so call this (or
+    /// [`Vrt::checked_to_bytes()`]) before sending a hand-built packet.
+    ///
+    /// The CAM field itself is also checked with
+    /// [`ControlAckMode::validate()`].
+    ///
+    /// # Errors
+    /// Returns [`VitaError::InconsistentCam`] if the CAM field combines
+    /// bits that aren't allowed together,
+    /// [`VitaError::AmbiguousAckCam`] if an ACK payload's CAM
+    /// selects zero or several ACK types, or
+    /// [`VitaError::CommandPayloadMismatch`] if it selects a different one.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut packet = Vrt::new_exec_ack_packet();
+    /// let command = packet.payload_mut().command_mut().unwrap();
+    /// assert!(command.validate().is_ok());
+    /// let mut cam = command.cam();
+    /// cam.set_validation();
+    /// command.set_cam(cam);
+    /// assert!(matches!(command.validate(), Err(VitaError::AmbiguousAckCam)));
+    /// ```
+    pub fn validate(&self) -> Result<(), VitaError> {
+        self.cam.validate()?;
+        match self.command_payload {
+            CommandPayload::Control(_) | CommandPayload::Cancellation(_) => Ok(()),
+            _ => self.check_payload_type(&PacketHeader::new_ack_header()),
+        }
+    }
+
+    /// Checks that the payload type is the one the parser would pick
+    /// for this CAM field and the given header.
+    pub(crate) fn check_payload_type(&self, header: &PacketHeader) -> Result<(), VitaError> {
+        let expected = CommandPayload::derive_type(&self.cam, header)?.type_name();
+        let payload = self.command_payload.type_name();
+        if expected != payload {
+            return Err(VitaError::CommandPayloadMismatch { expected, payload });
+        }
+        Ok(())
+    }
+
     /// Get the size of the command packet (in 32-bit words).
-    pub fn size_words(&self) -> u16 {
+    pub fn size_words(&self) -> usize {
         let mut ret = self.cam.size_words();
         ret += 1; // message_id
         if self.controllee_id.is_some() {
@@ -276,6 +391,33 @@ impl Command {
         ret += self.command_payload.size_words();
         ret
     }
+
+    /// Gets the size of the command payload (everything after the packet
+    /// prologue) in bytes.
+    pub fn payload_size_bytes(&self) -> usize {
+        self.size_words() * 4
+    }
+}
+
+impl Identity {
+    /// Splits the identity into the optional ID and UUID fields.
+    fn split(self) -> (Option<u32>, Option<u128>) {
+        match self {
+            Identity::Id(id) => (Some(id), None),
+            Identity::Uuid(uuid) => (None, Some(uuid)),
+            Identity::None => (None, None),
+        }
+    }
+
+    /// Builds the identity from the optional ID and UUID fields, which
+    /// the setters keep mutually exclusive.
+    fn join(id: Option<u32>, uuid: Option<u128>) -> Identity {
+        match (id, uuid) {
+            (Some(id), _) => Identity::Id(id),
+            (None, Some(uuid)) => Identity::Uuid(uuid),
+            (None, None) => Identity::None,
+        }
+    }
 }
 
 impl TryFrom<Payload> for Command {
@@ -289,8 +431,45 @@ impl TryFrom<Payload> for Command {
     }
 }
 
+/// Parses a standalone command payload: the CAM field and everything
+/// after it, without the packet header, stream ID, or timestamps.
+///
+/// The payload is parsed as if it came from a control packet (ACK and
+/// cancellation header indicators clear) whose size matches `bytes`, so
+/// ACK and cancellation payloads can't be read this way. Any bytes past
+/// the end of the payload are ignored.
+///
+/// # Example
+/// ```
+/// use vita49::{command_prelude::*, prelude::*};
+/// # fn main() -> Result<(), VitaError> {
+/// let mut packet = Vrt::new_control_packet();
+/// let command = packet.payload_mut().command_mut()?;
+/// command.set_message_id(42);
+/// packet.update_packet_size();
+/// // Skip the header and stream ID.
+/// let bytes = packet.to_bytes()?;
+/// let command = Command::try_from(&bytes[8..])?;
+/// assert_eq!(command.message_id(), 42);
+/// # Ok(())
+/// # }
+/// ```
+impl TryFrom<&[u8]> for Command {
+    type Error = VitaError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let header = PacketHeader::new_control_header().with_payload_len(bytes.len())?;
+        let mut cursor = deku::no_std_io::Cursor::new(bytes);
+        let mut reader = deku::reader::Reader::new(&mut cursor);
+        Ok(Command::from_reader_with_ctx(
+            &mut reader,
+            (deku::ctx::Endian::Big, &header),
+        )?)
+    }
+}
+
 impl fmt::Display for Command {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.cam)?;
         writeln!(f, "Message ID: {:x}", self.message_id)?;
         if let Some(cid) = self.controllee_id {
@@ -319,7 +498,7 @@ impl fmt::Display for Command {
 #[cfg(test)]
 mod tests {
     use crate::prelude::*;
-    use crate::{ActionMode, ControlAckMode, IdFormat, Tsf, Tsi};
+    use crate::{ActionMode, Command, ControlAckMode, IdFormat, Tsf, Tsi};
 
     #[test]
     fn create_control_packet() {
@@ -346,4 +525,173 @@ mod tests {
         command.controllee_id = Some(123);
         command.controller_uuid = Some(321);
     }
+
+    #[test]
+    fn validate_ack_payload_type() {
+        for mut packet in [
+            Vrt::new_control_packet(),
+            Vrt::new_cancellation_packet(),
+            Vrt::new_validation_ack_packet(),
+            Vrt::new_exec_ack_packet(),
+            Vrt::new_query_ack_packet(),
+        ] {
+            assert!(packet.payload().command().unwrap().validate().is_ok());
+            assert!(packet.checked_to_bytes().is_ok());
+
+            // No ACK type selected.
+            let command = packet.payload_mut().command_mut().unwrap();
+            let mut cam = command.cam();
+            cam.unset_validation();
+            cam.unset_execution();
+            cam.unset_state();
+            command.set_cam(cam);
+            if packet.header().is_ack_packet().unwrap() {
+                assert!(matches!(
+                    packet.checked_to_bytes(),
+                    Err(VitaError::AmbiguousAckCam)
+                ));
+            } else {
+                assert!(packet.checked_to_bytes().is_ok());
+            }
+        }
+
+        // CAM selects a different ACK type than the payload holds.
+        let mut packet = Vrt::new_validation_ack_packet();
+        let command = packet.payload_mut().command_mut().unwrap();
+        let mut cam = command.cam();
+        cam.unset_validation();
+        cam.set_execution();
+        command.set_cam(cam);
+        assert!(matches!(
+            command.validate(),
+            Err(VitaError::CommandPayloadMismatch {
+                expected: "execution ACK",
+                payload: "validation ACK"
+            })
+        ));
+
+        // Header says ACK but the payload is a control payload.
+        let mut packet = Vrt::new_control_packet();
+        *packet.header_mut() = PacketHeader::new_ack_header();
+        packet.update_packet_size();
+        assert!(packet.payload().command().unwrap().validate().is_ok());
+        assert!(packet.checked_to_bytes().is_err());
+
+        // Size mismatch.
+        let mut packet = Vrt::new_control_packet();
+        packet.header_mut().set_packet_size(1);
+        assert!(matches!(
+            packet.checked_to_bytes(),
+            Err(VitaError::PacketSizeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn validate_cam_warnings() {
+        let mut packet = Vrt::new_control_packet();
+        let command = packet.payload_mut().command_mut().unwrap();
+        let mut cam = command.cam();
+        cam.set_warning();
+        command.set_cam(cam);
+        assert!(matches!(
+            command.validate(),
+            Err(VitaError::InconsistentCam(_))
+        ));
+
+        cam.set_warnings_permitted();
+        command.set_cam(cam);
+        assert!(command.validate().is_ok());
+    }
+
+    #[test]
+    fn get_identity() {
+        use crate::Identity;
+        let mut packet = Vrt::new_control_packet();
+        let command = packet.payload_mut().command_mut().unwrap();
+        assert_eq!(command.controllee(), Identity::None);
+        assert_eq!(command.controller(), Identity::None);
+
+        command.set_controllee_id(Some(1)).unwrap();
+        command.set_controller_uuid(Some(2)).unwrap();
+        packet.update_packet_size();
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        let command = parsed.payload().command().unwrap();
+        assert_eq!(command.controllee(), Identity::Id(1));
+        assert_eq!(command.controller(), Identity::Uuid(2));
+
+        let command = packet.payload_mut().command_mut().unwrap();
+        command.set_controllee(Identity::Uuid(3));
+        command.set_controller(Identity::Id(4));
+        assert_eq!(command.controllee(), Identity::Uuid(3));
+        assert_eq!(command.controller(), Identity::Id(4));
+    }
+
+    #[test]
+    fn switch_identity() {
+        use crate::Identity;
+        let mut packet = Vrt::new_control_packet();
+        let command = packet.payload_mut().command_mut().unwrap();
+
+        command.set_controllee(Identity::Id(1));
+        command.set_controller(Identity::Uuid(2));
+        assert_eq!(command.controllee_id(), Some(1));
+        assert_eq!(command.controller_uuid(), Some(2));
+
+        // ID -> UUID and UUID -> ID without errors.
+        command.set_controllee(Identity::Uuid(3));
+        command.set_controller(Identity::Id(4));
+        assert_eq!(command.controllee_id(), None);
+        assert_eq!(command.controllee_uuid(), Some(3));
+        assert_eq!(command.controller_id(), Some(4));
+        assert_eq!(command.controller_uuid(), None);
+        assert!(command.cam().controllee_enabled());
+        assert_eq!(command.cam().controllee_id_format(), IdFormat::Uuid128bit);
+        assert_eq!(command.cam().controller_id_format(), IdFormat::Id32bit);
+        packet.update_packet_size();
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        assert_eq!(parsed, packet);
+
+        // And back.
+        let command = packet.payload_mut().command_mut().unwrap();
+        command.set_controllee(Identity::Id(5));
+        assert_eq!(command.controllee_id(), Some(5));
+        assert_eq!(command.controllee_uuid(), None);
+        assert_eq!(command.cam().controllee_id_format(), IdFormat::Id32bit);
+
+        command.set_controllee(Identity::None);
+        command.set_controller(Identity::None);
+        assert_eq!(command.controllee_id(), None);
+        assert_eq!(command.controller_id(), None);
+        assert!(!command.cam().controllee_enabled());
+        assert!(!command.cam().controller_enabled());
+    }
+
+    #[test]
+    fn parse_raw_control_payload() {
+        let mut packet = Vrt::new_control_packet();
+        packet.set_stream_id(Some(0xDEADBEEF));
+        packet.set_integer_timestamp(Some(0), Tsi::Utc).unwrap();
+        let command = packet.payload_mut().command_mut().unwrap();
+        command.set_message_id(123);
+        command.set_controllee_id(Some(0x42)).unwrap();
+        let control = command.payload_mut().control_mut().unwrap();
+        control.set_bandwidth_hz(Some(8e6)).unwrap();
+        control.set_rf_ref_freq_hz(Some(100e6)).unwrap();
+        control.set_sample_rate_sps(Some(10e6)).unwrap();
+        packet.update_packet_size();
+
+        let bytes = packet.to_bytes().unwrap();
+        // Header, stream ID and integer timestamp.
+        let raw = &bytes[12..];
+        let command = Command::try_from(raw).unwrap();
+        assert_eq!(&command, packet.payload().command().unwrap());
+        let control = command.payload().control().unwrap();
+        assert_eq!(control.bandwidth_hz(), Some(8e6));
+        assert_eq!(control.rf_ref_freq_hz(), Some(100e6));
+        assert_eq!(control.sample_rate_sps(), Some(10e6));
+
+        // Truncated payloads are rejected.
+        assert!(Command::try_from(&raw[..raw.len() - 4]).is_err());
+        assert!(Command::try_from(&[][..]).is_err());
+    }
 }
@@ -30,7 +30,8 @@ use deku::prelude::*;
 /// For the actual packet types, here are some attributes:
 /// 1. Control Packet
 ///    - Includes all CIF indicators
-///    - In Action Mode 0, will NOT include CIF fields
+///    - In Action Mode 0, is expected NOT to include CIF fields (not
+///      enforced; see [`ControlAckMode::will_include_cif_fields()`])
 ///    - In other Action Modes, WILL include CIF fields
 /// 2. Cancellation Packet
 ///    - Only includes CIF indicator fields (no real data fields)
@@ -44,7 +45,7 @@ use deku::prelude::*;
 #[deku(
     endian = "endian",
     ctx = "endian: deku::ctx::Endian, cam: &ControlAckMode, packet_header: &PacketHeader",
-    id = "CommandPayload::derive_type(cam, packet_header)"
+    id = "CommandPayload::derive_type(cam, packet_header)?"
 )]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CommandPayload {
@@ -67,34 +68,58 @@ pub enum CommandPayload {
 
 impl CommandPayload {
     /// Determine the type of command payload based on CAM field and VRT packet header.
-    fn derive_type(cam: &ControlAckMode, packet_header: &PacketHeader) -> CommandPayload {
-        if packet_header.is_ack_packet().unwrap() {
+    ///
+    /// # Errors
+    /// Returns [`VitaError::AmbiguousAckCam`] if the header marks an ACK packet
+    /// but the CAM field doesn't select exactly one of the validation, execution,
+    /// or query ACK types, or [`VitaError::CommandOnly`] if the header isn't a
+    /// command header (which the parser never passes in).
+    ///
+    /// If both the ACK and cancellation indicators are set, the ACK
+    /// indicator takes precedence.
+    pub(crate) fn derive_type(
+        cam: &ControlAckMode,
+        packet_header: &PacketHeader,
+    ) -> Result<CommandPayload, VitaError> {
+        let Indicators::Command(indicators) = packet_header.indicators() else {
+            return Err(VitaError::CommandOnly);
+        };
+        if indicators.ack_packet {
             if [cam.validation(), cam.execution(), cam.state()]
                 .iter()
                 .filter(|&x| *x)
                 .count()
                 != 1
             {
-                panic!("CAM field in ACK packet does not exclusively select one of Validation, Exec, or Query");
+                return Err(VitaError::AmbiguousAckCam);
             }
             if cam.validation() {
-                CommandPayload::ValidationAck(Ack::default())
+                Ok(CommandPayload::ValidationAck(Ack::default()))
             } else if cam.execution() {
-                CommandPayload::ExecAck(Ack::default())
-            } else if cam.state() {
-                CommandPayload::QueryAck(QueryAck::default())
+                Ok(CommandPayload::ExecAck(Ack::default()))
             } else {
-                unreachable!()
+                Ok(CommandPayload::QueryAck(QueryAck::default()))
             }
-        } else if packet_header.is_cancellation_packet().unwrap() {
-            CommandPayload::Cancellation(Cancellation::default())
+        } else if indicators.cancellation_packet {
+            Ok(CommandPayload::Cancellation(Cancellation::default()))
         } else {
-            CommandPayload::Control(Control::default())
+            Ok(CommandPayload::Control(Control::default()))
+        }
+    }
+
+    /// Gets a short name for the payload type, for error messages.
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            CommandPayload::Control(_) => "control",
+            CommandPayload::Cancellation(_) => "cancellation",
+            CommandPayload::ValidationAck(_) => "validation ACK",
+            CommandPayload::ExecAck(_) => "execution ACK",
+            CommandPayload::QueryAck(_) => "query ACK",
         }
     }
 
     /// Get the size of the command payload (in 32-bit words).
-    pub fn size_words(&self) -> u16 {
+    pub fn size_words(&self) -> usize {
         match self {
             CommandPayload::Control(p) => p.size_words(),
             CommandPayload::Cancellation(p) => p.size_words(),
@@ -139,7 +164,7 @@ impl CommandPayload {
     /// let mut packet = Vrt::new_control_packet();
     /// let mut command = packet.payload_mut().command_mut().unwrap();
     /// let mut control = command.payload_mut().control_mut().unwrap();
-    /// control.set_bandwidth_hz(Some(64e6));
+    /// control.set_bandwidth_hz(Some(64e6)).unwrap();
     /// assert_eq!(control.bandwidth_hz(), Some(64e6));
     /// ```
     pub fn control_mut(&mut self) -> Result<&mut Control, VitaError> {
@@ -329,7 +354,7 @@ impl CommandPayload {
     /// let mut packet = Vrt::new_query_ack_packet();
     /// let command = packet.payload_mut().command_mut().unwrap();
     /// let ack = command.payload_mut().query_ack_mut().unwrap();
-    /// ack.set_bandwidth_hz(Some(100e6));
+    /// ack.set_bandwidth_hz(Some(100e6)).unwrap();
     /// assert!(ack.bandwidth_hz().is_some())
     /// ```
     pub fn query_ack_mut(&mut self) -> Result<&mut QueryAck, VitaError> {
@@ -339,3 +364,87 @@ impl CommandPayload {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command_header(ack_packet: bool, cancellation_packet: bool) -> PacketHeader {
+        let mut header = PacketHeader::new_control_header();
+        header.set_indicators(Indicators::Command(CommandIndicators {
+            ack_packet,
+            cancellation_packet,
+        }));
+        header
+    }
+
+    #[test]
+    fn derive_type_indicator_combinations() {
+        let mut cam = ControlAckMode::default();
+        cam.set_validation();
+        for (ack, cancel, expected) in [
+            (false, false, "control"),
+            (false, true, "cancellation"),
+            (true, false, "validation ACK"),
+            (true, true, "validation ACK"),
+        ] {
+            let header = command_header(ack, cancel);
+            assert_eq!(header.is_ack_packet().ok(), Some(ack));
+            assert_eq!(header.is_cancellation_packet().ok(), Some(cancel));
+            let payload = CommandPayload::derive_type(&cam, &header).unwrap();
+            assert_eq!(payload.type_name(), expected);
+        }
+        assert!(matches!(
+            CommandPayload::derive_type(&cam, &PacketHeader::new_context_header()),
+            Err(VitaError::CommandOnly)
+        ));
+    }
+
+    #[test]
+    fn parse_indicator_combinations() {
+        use alloc::string::ToString;
+        let ambiguous_cam = VitaError::AmbiguousAckCam.to_string();
+        for (packet, expected) in [
+            (
+                Vrt::new_control_packet(),
+                ["control", "cancellation", "ambiguous CAM", "ambiguous CAM"],
+            ),
+            (
+                Vrt::new_cancellation_packet(),
+                ["control", "cancellation", "ambiguous CAM", "ambiguous CAM"],
+            ),
+            // An ACK's payload is too short to be read as a control packet.
+            (
+                Vrt::new_validation_ack_packet(),
+                [
+                    "incomplete",
+                    "incomplete",
+                    "validation ACK",
+                    "validation ACK",
+                ],
+            ),
+        ] {
+            let bytes = packet.to_bytes().unwrap();
+            for (indicator_bits, expected) in [0x00, 0x01, 0x04, 0x05].into_iter().zip(expected) {
+                let mut bytes = bytes.clone();
+                // ACK is bit 26 and cancellation bit 24 of the header.
+                bytes[0] = (bytes[0] & !0x05) | indicator_bits;
+                let outcome = match Vrt::try_from(&bytes[..]) {
+                    Ok(parsed) => {
+                        let header = parsed.header();
+                        assert_eq!(header.is_ack_packet().ok(), Some(indicator_bits & 0x04 > 0));
+                        assert_eq!(
+                            header.is_cancellation_packet().ok(),
+                            Some(indicator_bits & 0x01 > 0)
+                        );
+                        parsed.payload().command().unwrap().payload().type_name()
+                    }
+                    Err(DekuError::Parse(msg)) if msg == ambiguous_cam => "ambiguous CAM",
+                    Err(DekuError::Incomplete(_)) => "incomplete",
+                    Err(e) => panic!("unexpected error for {indicator_bits:#x}: {e}"),
+                };
+                assert_eq!(outcome, expected, "indicator bits {indicator_bits:#x}");
+            }
+        }
+    }
+}
@@ -2,6 +2,8 @@
 //
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use alloc::string::String;
+use alloc::vec::Vec;
 use core::fmt;
 
 use deku::prelude::*;
@@ -11,7 +13,21 @@ use crate::cif1::{Cif1, Cif1Fields, Cif1Manipulators};
 use crate::cif2::{Cif2, Cif2Fields, Cif2Manipulators};
 use crate::cif3::{Cif3, Cif3Fields, Cif3Manipulators};
 use crate::cif7::{Cif7, Cif7Opts};
+use crate::ecef_ephemeris::EcefEphemeris;
 use crate::payload::Payload;
+use crate::VitaError;
+
+/// A field that differs between two context payloads. See
+/// [`Context::diff()`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct FieldDiff {
+    /// Field name, as listed by [`Context::present_fields()`].
+    pub field: &'static str,
+    /// Raw value in the first context (`Debug` formatted).
+    pub left: String,
+    /// Raw value in the second context (`Debug` formatted).
+    pub right: String,
+}
 
 /// Context packet payload. Includes all CIFs and optional fields.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, DekuRead, DekuWrite)]
@@ -68,7 +84,22 @@ impl Context {
         self.cif0.context_field_changed()
     }
 
-    /// Set the context field change indicator bit.
+    /// Sets or clears the context field change indicator (CIF0 bit 31).
+    ///
+    /// Per ANSI/VITA-49.2, an emitter must set this bit whenever any
+    /// field in the packet has changed since the last context packet
+    /// for the stream, and clear it when the packet only repeats
+    /// previously sent values.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut packet = Vrt::new_context_packet();
+    /// let context = packet.payload_mut().context_mut().unwrap();
+    /// context.set_bandwidth_hz(Some(8e6)).unwrap();
+    /// context.set_context_changed(true);
+    /// assert!(context.context_changed());
+    /// ```
     pub fn set_context_changed(&mut self, changed: bool) {
         if changed {
             self.cif0.set_context_field_changed()
@@ -77,8 +108,41 @@ impl Context {
         }
     }
 
+    /// Removes CIF7 from the context: clears the CIF7 indicator word and
+    /// enable bit along with every field's `*_attributes` values, leaving
+    /// only the current values. Useful when forwarding to peers that
+    /// don't support CIF7.
+    ///
+    /// Nothing else in the context is touched. If CIF7 didn't include the
+    /// "current" value, fields are left without a value and their
+    /// indicator bits are cleared by
+    /// [`sync_indicators()`](Vrt::sync_indicators()).
+    ///
+    /// [`sync_indicators()`](Vrt::sync_indicators()) and
+    /// [`update_packet_size()`](Vrt::update_packet_size()) should be
+    /// executed after running this method.
+    pub fn clear_cif7(&mut self) {
+        self.cif7 = None;
+        self.cif0.unset_field_attributes_enabled();
+        #[cfg(feature = "cif7")]
+        {
+            self.cif0_fields.clear_attributes();
+            if let Some(f) = &mut self.cif1_fields {
+                f.clear_attributes();
+            }
+            if let Some(f) = &mut self.cif2_fields {
+                f.clear_attributes();
+            }
+            if let Some(f) = &mut self.cif3_fields {
+                f.clear_attributes();
+            }
+        }
+    }
+
+    crate::cif0::sync_indicators_method!();
+
     /// Returns the size of the context payload in 32-bit words.
-    pub fn size_words(&self) -> u16 {
+    pub fn size_words(&self) -> usize {
         // Start with 1 32-bit word for the CIF0 field
         let mut ret = 1 + self.cif0_fields.size_words();
         if let Some(f) = &self.cif1_fields {
@@ -95,6 +159,400 @@ impl Context {
         }
         ret
     }
+
+    /// Returns the size of the context payload in bytes.
+    pub fn payload_size_bytes(&self) -> usize {
+        self.size_words() * 4
+    }
+
+    /// Lists the CIF0-CIF3 data fields (and the context field change
+    /// indicator) that differ between `self` and `other`. Values are
+    /// shown in their raw encoding, e.g. `Some(6291456000000)` for a
+    /// 6 MHz bandwidth.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut a = Context::new();
+    /// a.set_bandwidth_hz(Some(6e6)).unwrap();
+    /// let mut b = a.clone();
+    /// b.set_bandwidth_hz(Some(8e6)).unwrap();
+    /// let diff = a.diff(&b);
+    /// assert_eq!(diff.len(), 1);
+    /// assert_eq!(diff[0].field, "bandwidth");
+    /// ```
+    pub fn diff(&self, other: &Context) -> Vec<FieldDiff> {
+        let mut ret = Vec::new();
+        if self.context_changed() != other.context_changed() {
+            ret.push(FieldDiff {
+                field: "context_changed",
+                left: format!("{}", self.context_changed()),
+                right: format!("{}", other.context_changed()),
+            });
+        }
+        ret.extend(self.cif0_fields.diff(&other.cif0_fields));
+        // A missing CIF compares like one with no fields set.
+        let (cif1, cif2, cif3) = (
+            Cif1Fields::default(),
+            Cif2Fields::default(),
+            Cif3Fields::default(),
+        );
+        let l = self.cif1_fields.as_ref().unwrap_or(&cif1);
+        ret.extend(l.diff(other.cif1_fields.as_ref().unwrap_or(&cif1)));
+        let l = self.cif2_fields.as_ref().unwrap_or(&cif2);
+        ret.extend(l.diff(other.cif2_fields.as_ref().unwrap_or(&cif2)));
+        let l = self.cif3_fields.as_ref().unwrap_or(&cif3);
+        ret.extend(l.diff(other.cif3_fields.as_ref().unwrap_or(&cif3)));
+        ret
+    }
+
+    /// Overlays a newer context onto this one ("sticky" context): every
+    /// field included in `newer` is copied into `self`, while fields
+    /// `newer` doesn't include keep their current value. The context
+    /// field change indicator is taken from `newer`.
+    ///
+    /// Both contexts are expected to use the same CIF7 attributes, if
+    /// any.
+    ///
+    /// [`update_packet_size()`](Vrt::update_packet_size()) should be
+    /// executed after running this method.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut last_known = Context::new();
+    /// last_known.set_bandwidth_hz(Some(6e6)).unwrap();
+    /// last_known.set_sample_rate_sps(Some(8e6)).unwrap();
+    /// let mut update = Context::new();
+    /// update.set_bandwidth_hz(Some(8e6)).unwrap();
+    /// last_known.overlay(&update);
+    /// assert_eq!(last_known.bandwidth_hz(), Some(8e6));
+    /// assert_eq!(last_known.sample_rate_sps(), Some(8e6));
+    /// ```
+    pub fn overlay(&mut self, newer: &Context) {
+        self.set_context_changed(newer.context_changed());
+        self.cif0_fields.overlay(&newer.cif0_fields);
+        if let Some(fields) = &newer.cif1_fields {
+            self.cif1_fields
+                .get_or_insert_with(Cif1Fields::default)
+                .overlay(fields);
+        }
+        if let Some(fields) = &newer.cif2_fields {
+            self.cif2_fields
+                .get_or_insert_with(Cif2Fields::default)
+                .overlay(fields);
+        }
+        if let Some(fields) = &newer.cif3_fields {
+            self.cif3_fields
+                .get_or_insert_with(Cif3Fields::default)
+                .overlay(fields);
+        }
+        if newer.cif7.is_some() {
+            self.cif7 = newer.cif7;
+        }
+        self.sync_indicators();
+    }
+
+    /// Returns the names of the fields included in this context payload,
+    /// in CIF0, CIF1, CIF2, CIF3 order.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut context = Context::new();
+    /// context.set_bandwidth_hz(Some(8e6)).unwrap();
+    /// context.set_sample_rate_sps(Some(16e6)).unwrap();
+    /// assert_eq!(context.present_fields(), ["bandwidth", "sample_rate"]);
+    /// ```
+    pub fn present_fields(&self) -> Vec<&'static str> {
+        let mut names = self.cif0.field_names();
+        for cif in [
+            self.cif1.map(|c| c.field_names()),
+            self.cif2.map(|c| c.field_names()),
+            self.cif3.map(|c| c.field_names()),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            names.extend(cif);
+        }
+        names
+    }
+
+    /// Returns the on-wire (big-endian) bytes of the field called `name`,
+    /// as listed by [`present_fields()`](Context::present_fields()), or
+    /// `None` if the field isn't set. Handy for comparing a single field
+    /// against another implementation's encoding.
+    ///
+    /// Only the field's current value is returned, not its CIF7
+    /// attributes.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut context = Context::new();
+    /// context.set_sample_rate_sps(Some(1.0)).unwrap();
+    /// assert_eq!(
+    ///     context.field_bytes("sample_rate"),
+    ///     Some(vec![0, 0, 0, 0, 0, 0x10, 0, 0])
+    /// );
+    /// assert_eq!(context.field_bytes("bandwidth"), None);
+    /// ```
+    pub fn field_bytes(&self, name: &str) -> Option<Vec<u8>> {
+        self.cif0_fields
+            .field_bytes(name)
+            .or_else(|| self.cif1_fields.as_ref()?.field_bytes(name))
+            .or_else(|| self.cif2_fields.as_ref()?.field_bytes(name))
+            .or_else(|| self.cif3_fields.as_ref()?.field_bytes(name))
+    }
+
+    /// Labels the CIF7 attribute values of every field included in this
+    /// context payload. Each entry holds a field name, as listed by
+    /// [`present_fields()`](Context::present_fields()), and the field's
+    /// attributes as (attribute name, on-wire bytes) pairs, with names
+    /// from [`Cif7::attribute_names()`]. Fields carrying no attributes get
+    /// an empty list.
+    ///
+    /// Use [`label_attribute_values()`](Context::label_attribute_values())
+    /// to get a single field's attributes as typed values instead.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut context = Context::new();
+    /// let mut cif7 = Cif7::default();
+    /// cif7.set_current();
+    /// cif7.set_average();
+    /// context.cif7 = Some(cif7);
+    /// context.set_over_range_count(Some(1));
+    /// context.set_over_range_count_attributes(Some(vec![3]));
+    /// context.set_temperature(Some(2));
+    /// assert_eq!(
+    ///     context.label_attributes(),
+    ///     [
+    ///         ("over_range_count", vec![("average", vec![0, 0, 0, 3])]),
+    ///         ("temperature", vec![]),
+    ///     ]
+    /// );
+    /// ```
+    #[cfg(feature = "cif7")]
+    #[allow(clippy::type_complexity)]
+    pub fn label_attributes(&self) -> Vec<(&'static str, Vec<(&'static str, Vec<u8>)>)> {
+        let names = self.cif7.map(|c| c.attribute_names()).unwrap_or_default();
+        self.present_fields()
+            .into_iter()
+            .map(|field| {
+                let values = self
+                    .cif0_fields
+                    .attribute_bytes(field)
+                    .or_else(|| self.cif1_fields.as_ref()?.attribute_bytes(field))
+                    .or_else(|| self.cif2_fields.as_ref()?.attribute_bytes(field))
+                    .or_else(|| self.cif3_fields.as_ref()?.attribute_bytes(field))
+                    .unwrap_or_default();
+                (field, names.iter().copied().zip(values).collect())
+            })
+            .collect()
+    }
+
+    /// Pairs the CIF7 attribute values of a single field, as returned by
+    /// its `*_attributes()` getter, with the name of the attribute each
+    /// one holds (see [`Cif7::attribute_names()`]).
+    ///
+    /// Returns an empty list if CIF7 isn't enabled.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut context = Context::new();
+    /// let mut cif7 = Cif7::default();
+    /// cif7.set_current();
+    /// cif7.set_average();
+    /// cif7.set_max();
+    /// context.cif7 = Some(cif7);
+    /// context.set_bandwidth_hz(Some(8e6)).unwrap();
+    /// context.set_bandwidth_hz_attributes(Some(vec![7e6, 9e6])).unwrap();
+    /// assert_eq!(
+    ///     context.label_attribute_values(context.bandwidth_hz_attributes()),
+    ///     [("average", 7e6), ("max", 9e6)]
+    /// );
+    /// ```
+    #[cfg(feature = "cif7")]
+    pub fn label_attribute_values<T>(&self, values: Vec<T>) -> Vec<(&'static str, T)> {
+        let names = self.cif7.map(|c| c.attribute_names()).unwrap_or_default();
+        names.into_iter().zip(values).collect()
+    }
+
+    /// Returns the center frequency of the band described by this
+    /// context, combining the frequency fields per the tuning model of
+    /// ANSI/VITA-49.2-2017 sections 9.5.4 through 9.5.6.
+    ///
+    /// If the RF reference frequency is present, the RF center frequency
+    /// is returned:
+    ///
+    /// ```text
+    /// center = rf_ref_freq + rf_ref_freq_offset + if_band_offset
+    /// ```
+    ///
+    /// Otherwise, if the IF reference frequency is present, the IF center
+    /// frequency is returned:
+    ///
+    /// ```text
+    /// center = if_ref_freq + if_band_offset
+    /// ```
+    ///
+    /// Absent offsets count as zero. Spectral inversion isn't accounted
+    /// for. Returns `None` if neither reference frequency is present.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut context = Context::new();
+    /// context.set_if_ref_freq_hz(Some(70e6)).unwrap();
+    /// context.set_if_band_offset_hz(Some(-2e6)).unwrap();
+    /// assert_eq!(context.center_freq_hz(), Some(68e6));
+    /// context.set_rf_ref_freq_hz(Some(2.4e9)).unwrap();
+    /// assert_eq!(context.center_freq_hz(), Some(2.398e9));
+    /// ```
+    pub fn center_freq_hz(&self) -> Option<f64> {
+        let if_band_offset = self.if_band_offset_hz().unwrap_or(0.0);
+        if let Some(rf_ref_freq) = self.rf_ref_freq_hz() {
+            let rf_ref_freq_offset = self.rf_ref_freq_offset_hz().unwrap_or(0.0);
+            Some(rf_ref_freq + rf_ref_freq_offset + if_band_offset)
+        } else {
+            self.if_ref_freq_hz().map(|f| f + if_band_offset)
+        }
+    }
+
+    /// Looks up the stream the reference point ID field points to in a
+    /// caller-maintained map of known streams, keyed by stream ID.
+    ///
+    /// This is named differently from
+    /// [`Cif0Manipulators::reference_point()`], which returns the raw
+    /// stream ID.
+    ///
+    /// Returns `None` if the reference point ID isn't set or the stream
+    /// isn't in `streams`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    /// use vita49::prelude::*;
+    /// let streams = HashMap::from([(0x10, "antenna"), (0x20, "digitizer")]);
+    /// let mut context = Context::new();
+    /// context.set_reference_point_id(Some(0x20));
+    /// assert_eq!(context.resolve_reference_point(&streams), Some(&"digitizer"));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn resolve_reference_point<'a, T>(
+        &self,
+        streams: &'a std::collections::HashMap<u32, T>,
+    ) -> Option<&'a T> {
+        streams.get(self.reference_point_id()?)
+    }
+
+    /// Returns the relative ephemeris together with the ephemeris
+    /// reference ID identifying the stream that defines its reference
+    /// frame (ANSI/VITA-49.2-2017 section 9.4.10).
+    ///
+    /// Returns `Ok(None)` if no relative ephemeris is set.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::MissingEphemerisRefId`] if the relative
+    /// ephemeris is set but the ephemeris reference ID isn't, since the
+    /// ephemeris can't be interpreted without it.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::{prelude::*, EcefEphemeris};
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut context = Context::new();
+    /// assert!(context.relative_ephemeris_with_ref()?.is_none());
+    /// context.set_relative_ephemeris(Some(EcefEphemeris::default()));
+    /// assert!(context.relative_ephemeris_with_ref().is_err());
+    /// context.set_ephemeris_ref_id(Some(0x1234));
+    /// let (_, ref_id) = context.relative_ephemeris_with_ref()?.unwrap();
+    /// assert_eq!(ref_id, 0x1234);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn relative_ephemeris_with_ref(&self) -> Result<Option<(&EcefEphemeris, u32)>, VitaError> {
+        let Some(ephemeris) = self.relative_ephemeris() else {
+            return Ok(None);
+        };
+        let ref_id = self
+            .ephemeris_ref_id()
+            .ok_or(VitaError::MissingEphemerisRefId)?;
+        Ok(Some((ephemeris, *ref_id)))
+    }
+
+    /// Checks that the bandwidth doesn't exceed the sample rate, which
+    /// would mean the described signal is aliased. Some downstream tools
+    /// reject such packets; this lets emitters catch them first.
+    ///
+    /// Passes if either field is absent.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::BandwidthExceedsSampleRate`] if both fields
+    /// are present and the bandwidth is larger than the sample rate.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut context = Context::new();
+    /// context.set_bandwidth_hz(Some(8e6)).unwrap();
+    /// assert!(context.check_nyquist().is_ok());
+    /// context.set_sample_rate_sps(Some(6e6)).unwrap();
+    /// assert!(matches!(
+    ///     context.check_nyquist(),
+    ///     Err(VitaError::BandwidthExceedsSampleRate { .. })
+    /// ));
+    /// ```
+    pub fn check_nyquist(&self) -> Result<(), VitaError> {
+        if let (Some(bandwidth_hz), Some(sample_rate_sps)) =
+            (self.bandwidth_hz(), self.sample_rate_sps())
+        {
+            if bandwidth_hz > sample_rate_sps {
+                return Err(VitaError::BandwidthExceedsSampleRate {
+                    bandwidth_hz,
+                    sample_rate_sps,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses a standalone context payload: the CIF words and fields,
+/// without the packet header, stream ID, or timestamps. Context payloads
+/// don't depend on the header, so no assumptions are made about it. Any
+/// bytes past the end of the payload are ignored.
+///
+/// # Example
+/// ```
+/// use vita49::prelude::*;
+/// # fn main() -> Result<(), VitaError> {
+/// let mut packet = Vrt::new_context_packet();
+/// let context = packet.payload_mut().context_mut()?;
+/// context.set_bandwidth_hz(Some(8e6)).unwrap();
+/// packet.update_packet_size();
+/// // Skip the header and stream ID.
+/// let bytes = packet.to_bytes()?;
+/// let context = Context::try_from(&bytes[8..])?;
+/// assert_eq!(context.bandwidth_hz(), Some(8e6));
+/// # Ok(())
+/// # }
+/// ```
+impl TryFrom<&[u8]> for Context {
+    type Error = VitaError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let mut cursor = deku::no_std_io::Cursor::new(bytes);
+        let mut reader = deku::reader::Reader::new(&mut cursor);
+        Ok(Context::from_reader_with_ctx(
+            &mut reader,
+            deku::ctx::Endian::Big,
+        )?)
+    }
 }
 
 impl TryFrom<Payload> for Context {
@@ -187,7 +645,7 @@ impl Cif3Manipulators for Context {
 }
 
 impl fmt::Display for Context {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.cif0)?;
         if let Some(cif1) = self.cif1 {
             write!(f, "{cif1}")?;
@@ -202,7 +660,16 @@ impl fmt::Display for Context {
             writeln!(f, "Sample rate: {samp_rate} sps")?;
         }
         if let Some(device_id) = &self.device_id() {
-            write!(f, "{device_id}")?;
+            writeln!(f, "Device ID: {device_id}")?;
+        }
+        if let Some(indicators) = &self.state_indicators() {
+            writeln!(f, "State/event indicators: {indicators}")?;
+        }
+        if let Some(format) = self.signal_data_payload_format() {
+            write!(f, "{format}")?;
+        }
+        if let Some(gps) = self.formatted_gps() {
+            write!(f, "{gps}")?;
         }
         if let Some(spectrum) = self.spectrum() {
             write!(f, "{spectrum}")?;
@@ -240,9 +707,699 @@ mod tests {
         assert_eq!(context.sample_rate_sps(), Some(8e6));
         assert!(context.cif0.cif1_enabled());
         assert!(context.cif1.is_some());
-        assert!(context.cif1.clone().unwrap().spectrum());
+        assert!(context.cif1.unwrap().spectrum());
         assert_eq!(context.spectrum().unwrap().spectrum_type_as_u32(), 0x101);
         assert_eq!(context.spectrum().unwrap().num_transform_points(), 1280);
         assert_eq!(context.spectrum().unwrap().f1_index(), -640);
+
+        let fields = context.present_fields();
+        for name in ["bandwidth", "rf_ref_freq", "sample_rate", "spectrum"] {
+            assert!(fields.contains(&name), "{name} missing from {fields:?}");
+        }
+        assert!(!fields.contains(&"reference_point_id"));
+    }
+
+    #[test]
+    fn timestamp_details_round_trip() {
+        use crate::prelude::*;
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        let mut details = crate::TimestampDetails::from_u64(0x0102_0304_0506_0708);
+        details.set_global(true);
+        context.set_timestamp_details(Some(details));
+        packet.update_packet_size();
+
+        let bytes = packet.to_bytes().unwrap();
+        let parsed = Vrt::try_from(&bytes[..]).unwrap();
+        assert_eq!(parsed, packet);
+        let context = parsed.payload().context().unwrap();
+        assert!(context.cif0.cif3_enabled());
+        assert!(context.cif3().unwrap().timestamp_details());
+        assert_eq!(context.timestamp_details(), Some(&details));
+        assert_eq!(
+            context.timestamp_details().unwrap().as_u64(),
+            0x0112_0304_0506_0708
+        );
+        assert_eq!(
+            context.timestamp_details().unwrap().timestamp_offset(),
+            0x0506_0708
+        );
+
+        let mut packet = parsed;
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_timestamp_details(None);
+        assert!(!context.cif0.cif3_enabled());
+        assert!(context.cif3().is_none());
+    }
+
+    #[test]
+    fn temperature_fixed_point() {
+        use crate::prelude::*;
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_temperature_c(Some(25.5)).unwrap();
+        assert_eq!(context.temperature(), Some(&0x660));
+        packet.update_packet_size();
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        assert_eq!(
+            parsed.payload().context().unwrap().temperature_c(),
+            Some(25.5)
+        );
+
+        // Negative values must not spill into the reserved upper 16 bits.
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_temperature_c(Some(-10.25)).unwrap();
+        assert_eq!(context.temperature(), Some(&0xFD70));
+        assert_eq!(context.temperature_c(), Some(-10.25));
+        context.set_temperature_c(None).unwrap();
+        assert!(!context.cif0.temperature());
+    }
+
+    #[test]
+    fn over_range_count_saturates() {
+        use crate::prelude::*;
+        let mut context = Context::new();
+        assert_eq!(context.over_range_count(), None);
+        context.increment_over_range_count(1);
+        assert!(context.cif0.over_range_count());
+        assert_eq!(context.over_range_count(), Some(&1));
+        context.increment_over_range_count(u32::MAX - 2);
+        assert_eq!(context.over_range_count(), Some(&(u32::MAX - 1)));
+        context.increment_over_range_count(5);
+        assert_eq!(context.over_range_count(), Some(&u32::MAX));
+        context.increment_over_range_count(1);
+        assert_eq!(context.over_range_count(), Some(&u32::MAX));
+    }
+
+    #[test]
+    fn reference_point_round_trip() {
+        use crate::prelude::*;
+        use crate::StreamId;
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_reference_point(Some(StreamId::new(0x1234)));
+        assert!(context.cif0.reference_point_id());
+        packet.update_packet_size();
+
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        let context = parsed.payload().context().unwrap();
+        assert_eq!(context.reference_point(), Some(StreamId::new(0x1234)));
+        assert_eq!(context.reference_point_id(), Some(&0x1234));
+        assert!(context
+            .check_reference_point(|id| id == StreamId::new(0x1234))
+            .is_ok());
+        assert!(matches!(
+            context.check_reference_point(|id| id == StreamId::new(0x1)),
+            Err(VitaError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn negative_if_ref_freq_round_trip() {
+        use crate::prelude::*;
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_if_ref_freq_hz(Some(-1.5e6)).unwrap();
+        packet.update_packet_size();
+
+        let bytes = packet.to_bytes().unwrap();
+        // Header, stream ID and CIF0, then the IF reference frequency
+        let raw = -1_500_000i64 << 20;
+        assert_eq!(&bytes[12..20], &raw.to_be_bytes());
+        let parsed = Vrt::try_from(&bytes[..]).unwrap();
+        let context = parsed.payload().context().unwrap();
+        assert_eq!(context.if_ref_freq_hz(), Some(-1.5e6));
+        assert_eq!(context.if_ref_freq_raw(), Some(raw));
+    }
+
+    #[test]
+    fn signed_offsets_round_trip() {
+        use crate::prelude::*;
+        // Includes the most negative value and the smallest negative
+        // value (all bits set) for a 64-bit/20-bit radix field.
+        for offset_hz in [-2.5e9, 2.5e9, -(2f64.powi(43)), -1.0 / 2f64.powi(20)] {
+            let mut packet = Vrt::new_context_packet();
+            let context = packet.payload_mut().context_mut().unwrap();
+            context.set_if_band_offset_hz(Some(offset_hz)).unwrap();
+            context.set_rf_ref_freq_offset_hz(Some(offset_hz)).unwrap();
+            packet.update_packet_size();
+
+            let bytes = packet.to_bytes().unwrap();
+            let raw = ((offset_hz * 2f64.powi(20)) as i64).to_be_bytes();
+            // Header, stream ID and CIF0, then RF offset and IF band offset
+            assert_eq!(&bytes[12..20], &raw);
+            assert_eq!(&bytes[20..28], &raw);
+
+            let parsed = Vrt::try_from(&bytes[..]).unwrap();
+            let context = parsed.payload().context().unwrap();
+            assert_eq!(context.if_band_offset_hz(), Some(offset_hz));
+            assert_eq!(context.rf_ref_freq_offset_hz(), Some(offset_hz));
+        }
+    }
+
+    #[test]
+    fn cif1_radix_setter_leaves_cif7_off() {
+        use crate::prelude::*;
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_range_m(Some(10.0)).unwrap();
+        assert!(!context.cif0.field_attributes_enabled());
+        packet.update_packet_size();
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        assert_eq!(parsed.payload().context().unwrap().range_m(), Some(10.0));
+
+        // Only the attribute setter turns CIF7 on.
+        #[cfg(feature = "cif7")]
+        {
+            let context = packet.payload_mut().context_mut().unwrap();
+            context.set_range_m_attributes(Some(vec![9.0])).unwrap();
+            assert!(context.cif0.field_attributes_enabled());
+        }
+    }
+
+    #[cfg(feature = "cif7")]
+    #[test]
+    fn clear_cif7() {
+        use crate::prelude::*;
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        let mut cif7 = Cif7::default();
+        cif7.set_current();
+        cif7.set_average();
+        context.cif7 = Some(cif7);
+        context.set_bandwidth_hz(Some(8e6)).unwrap();
+        context
+            .set_bandwidth_hz_attributes(Some(vec![7e6]))
+            .unwrap();
+        context.set_range_m(Some(10.0)).unwrap();
+        context.set_range_m_attributes(Some(vec![9.0])).unwrap();
+        packet.update_packet_size();
+        let bytes = packet.to_bytes().unwrap();
+        assert_eq!(Vrt::try_from(&bytes[..]).unwrap(), packet);
+
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.clear_cif7();
+        assert!(context.cif7.is_none());
+        assert!(!context.cif0.field_attributes_enabled());
+        assert!(context.bandwidth_hz_attributes().is_empty());
+        assert!(context.range_m_attributes().is_empty());
+        packet.update_packet_size();
+
+        let bytes = packet.to_bytes().unwrap();
+        // Header, stream ID, CIF0, CIF1, bandwidth, range
+        assert_eq!(bytes.len(), 4 * (1 + 1 + 1 + 1 + 2 + 1));
+        // CIF7 enable is bit 7 of CIF0
+        assert_eq!(bytes[11] & 0x80, 0);
+        let parsed = Vrt::try_from(&bytes[..]).unwrap();
+        let context = parsed.payload().context().unwrap();
+        assert_eq!(context.bandwidth_hz(), Some(8e6));
+        assert_eq!(context.range_m(), Some(10.0));
+
+        // Without "current", only the attribute-only fields drop out.
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        let mut cif7 = Cif7::default();
+        cif7.set_average();
+        context.cif7 = Some(cif7);
+        context.set_context_changed(true);
+        context
+            .set_bandwidth_hz_attributes(Some(vec![7e6]))
+            .unwrap();
+        packet.update_packet_size();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.clear_cif7();
+        assert!(context.context_changed());
+        assert!(context.cif0.bandwidth());
+        packet.sync_indicators();
+        packet.update_packet_size();
+        let context = packet.payload().context().unwrap();
+        assert!(context.context_changed());
+        assert!(!context.cif0.bandwidth());
+        let bytes = packet.to_bytes().unwrap();
+        assert_eq!(Vrt::try_from(&bytes[..]).unwrap(), packet);
+    }
+
+    #[test]
+    fn raw_fixed_point_values() {
+        use crate::prelude::*;
+        let mut context = Context::new();
+        assert_eq!(context.bandwidth_raw(), None);
+        context.set_bandwidth_hz(Some(8e6)).unwrap();
+        context.set_if_band_offset_hz(Some(-0.5)).unwrap();
+        context.set_range_m(Some(1.25)).unwrap();
+        // 20 fractional bits for frequencies, 6 for range
+        assert_eq!(context.bandwidth_raw(), Some(8_000_000 << 20));
+        assert_eq!(context.if_band_offset_raw(), Some(-(1 << 19)));
+        assert_eq!(context.range_raw(), Some(80));
+    }
+
+    #[test]
+    fn indicator_words() {
+        use crate::prelude::*;
+        let mut context = Context::new();
+        context.set_bandwidth_hz(Some(8e6)).unwrap();
+        context.set_range_m(Some(10.0)).unwrap();
+        assert_eq!(context.cif0_word(), (1 << 29) | (1 << 1));
+        assert_eq!(context.cif1_word(), Some(1 << 24));
+        assert_eq!(context.cif2_word(), None);
+        assert_eq!(context.cif3_word(), None);
+
+        let mut packet = Vrt::new_control_packet();
+        let control = packet.payload_mut().command_mut().unwrap();
+        let control = control.payload_mut().control_mut().unwrap();
+        control.set_rf_ref_freq_hz(Some(100e6)).unwrap();
+        assert_eq!(control.cif0_word(), 1 << 27);
+        assert_eq!(control.cif1_word(), None);
+    }
+
+    #[test]
+    fn ephemeris_ref_id_round_trip() {
+        use crate::prelude::*;
+        let mut packet = Vrt::new_context_packet();
+        let base_size = packet.header().packet_size();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_ephemeris_ref_id(Some(0xABCD));
+        assert!(context.cif0.ephemeris_ref_id());
+        packet.update_packet_size();
+        assert_eq!(packet.header().packet_size(), base_size + 1);
+
+        let mut parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        let context = parsed.payload_mut().context_mut().unwrap();
+        assert_eq!(context.ephemeris_ref_id(), Some(&0xABCD));
+        context.set_ephemeris_ref_id(None);
+        assert!(!context.cif0.ephemeris_ref_id());
+        parsed.update_packet_size();
+        assert_eq!(parsed.header().packet_size(), base_size);
+    }
+
+    #[test]
+    fn timestamp_cal_time_round_trip() {
+        use crate::prelude::*;
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_timestamp_cal_time_secs(Some(1_735_689_600));
+        assert!(context.cif0.timestamp_cal_time());
+        packet.update_packet_size();
+
+        let mut parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        let context = parsed.payload_mut().context_mut().unwrap();
+        assert_eq!(context.timestamp_cal_time_secs(), Some(1_735_689_600));
+        context.set_timestamp_cal_time_secs(None);
+        assert!(!context.cif0.timestamp_cal_time());
+    }
+
+    #[cfg(feature = "jiff")]
+    #[test]
+    fn timestamp_cal_time_jiff() {
+        use crate::{prelude::*, GPS_LEAP_SECONDS};
+        let time: jiff::Timestamp = "2025-01-01T00:00:00Z".parse().unwrap();
+        let mut context = Context::new();
+
+        context
+            .set_timestamp_cal_time_jiff(time, Tsi::Utc, GPS_LEAP_SECONDS)
+            .unwrap();
+        assert_eq!(context.timestamp_cal_time_secs(), Some(1_735_689_600));
+        assert_eq!(
+            context.timestamp_cal_time_jiff(Tsi::Utc, GPS_LEAP_SECONDS),
+            Some(time)
+        );
+
+        // Seconds since 1980-01-06, plus 18 leap seconds.
+        context
+            .set_timestamp_cal_time_jiff(time, Tsi::Gps, GPS_LEAP_SECONDS)
+            .unwrap();
+        assert_eq!(context.timestamp_cal_time_secs(), Some(1_419_724_818));
+        assert_eq!(
+            context.timestamp_cal_time_jiff(Tsi::Gps, GPS_LEAP_SECONDS),
+            Some(time)
+        );
+
+        // An older leap-second offset gives a different encoding.
+        context
+            .set_timestamp_cal_time_jiff(time, Tsi::Gps, 17)
+            .unwrap();
+        assert_eq!(context.timestamp_cal_time_secs(), Some(1_419_724_817));
+        assert_eq!(context.timestamp_cal_time_jiff(Tsi::Gps, 17), Some(time));
+
+        assert!(matches!(
+            context.set_timestamp_cal_time_jiff(time, Tsi::Other, GPS_LEAP_SECONDS),
+            Err(VitaError::TimestampModeMismatch)
+        ));
+        let before_gps: jiff::Timestamp = "1970-01-02T00:00:00Z".parse().unwrap();
+        assert!(matches!(
+            context.set_timestamp_cal_time_jiff(before_gps, Tsi::Gps, GPS_LEAP_SECONDS),
+            Err(VitaError::OutOfRange)
+        ));
+    }
+
+    #[test]
+    fn diff_bandwidth_only() {
+        use super::FieldDiff;
+        use crate::prelude::*;
+        use crate::Spectrum;
+        let mut a = Context::new();
+        a.set_bandwidth_hz(Some(6e6)).unwrap();
+        a.set_sample_rate_sps(Some(8e6)).unwrap();
+        a.set_spectrum(Some(Spectrum::new()));
+        let mut b = a.clone();
+        assert!(a.diff(&b).is_empty());
+
+        b.set_bandwidth_hz(Some(8e6)).unwrap();
+        let diff = a.diff(&b);
+        assert_eq!(
+            diff,
+            [FieldDiff {
+                field: "bandwidth",
+                left: format!("{:?}", Some(6_000_000u64 << 20)),
+                right: format!("{:?}", Some(8_000_000u64 << 20)),
+            }]
+        );
+        let diff = b.diff(&Context::new());
+        let fields: alloc::vec::Vec<_> = diff.iter().map(|d| d.field).collect();
+        assert_eq!(fields, ["bandwidth", "sample_rate", "spectrum"]);
+    }
+
+    #[test]
+    fn overlay_bandwidth_update() {
+        use crate::prelude::*;
+        let mut full = Vrt::new_context_packet();
+        let context = full.payload_mut().context_mut().unwrap();
+        context.set_bandwidth_hz(Some(6e6)).unwrap();
+        context.set_rf_ref_freq_hz(Some(100e6)).unwrap();
+        context.set_sample_rate_sps(Some(8e6)).unwrap();
+        context.set_range_m(Some(10.0)).unwrap();
+        full.update_packet_size();
+
+        let mut update = Vrt::new_context_packet();
+        let context = update.payload_mut().context_mut().unwrap();
+        context.set_context_changed(true);
+        context.set_bandwidth_hz(Some(8e6)).unwrap();
+        update.update_packet_size();
+
+        let update = update.payload().context().unwrap();
+        full.payload_mut().context_mut().unwrap().overlay(update);
+        full.update_packet_size();
+        let parsed = Vrt::try_from(&full.to_bytes().unwrap()[..]).unwrap();
+        let context = parsed.payload().context().unwrap();
+        assert!(context.context_changed());
+        assert_eq!(context.bandwidth_hz(), Some(8e6));
+        assert_eq!(context.rf_ref_freq_hz(), Some(100e6));
+        assert_eq!(context.sample_rate_sps(), Some(8e6));
+        assert_eq!(context.range_m(), Some(10.0));
+
+        // Overlaying onto an empty context copies the CIF1 fields too.
+        let mut empty = Context::new();
+        empty.overlay(context);
+        assert_eq!(&empty, context);
+    }
+
+    #[test]
+    fn reference_level_clamps() {
+        use crate::prelude::*;
+        // Largest Q8.7 value, 255.9921875
+        let max = f32::from(i16::MAX) / 128.0;
+        let mut context = Context::new();
+        for (level, expected) in [
+            (255.5, 255.5),
+            (-256.0, -256.0),
+            (-0.5, -0.5),
+            (max, max),
+            (300.0, max),
+            (1e9, max),
+            (-256.5, -256.0),
+            (-1e9, -256.0),
+        ] {
+            context.set_reference_level_db(Some(level)).unwrap();
+            assert_eq!(context.reference_level_db(), Some(expected), "{level}");
+        }
+        // The upper 16 bits stay reserved (zero) after clamping.
+        context.set_reference_level_db(Some(-300.0)).unwrap();
+        assert_eq!(context.cif0_fields().reference_level, Some(0x8000));
+
+        // NaN is rejected and the field is left alone.
+        assert!(context.set_reference_level_db(Some(f32::NAN)).is_err());
+        assert_eq!(context.cif0_fields().reference_level, Some(0x8000));
+    }
+
+    #[test]
+    fn radix_frequencies_clamp() {
+        use crate::prelude::*;
+        let mut context = Context::new();
+        // Largest f64 below 2^44 Hz, exactly representable in 44.20.
+        let max = 2f64.powi(44) - 2f64.powi(-9);
+        context.set_rf_ref_freq_hz(Some(max)).unwrap();
+        assert_eq!(context.rf_ref_freq_hz(), Some(max));
+        assert_eq!(context.rf_ref_freq_raw(), Some(u64::MAX - (1 << 11) + 1));
+
+        // Just beyond the range clamps instead of wrapping.
+        for freq in [2f64.powi(44), 1e14, f64::INFINITY] {
+            context.set_rf_ref_freq_hz(Some(freq)).unwrap();
+            assert_eq!(context.rf_ref_freq_raw(), Some(u64::MAX), "{freq}");
+        }
+        context.set_rf_ref_freq_hz(Some(-1.0)).unwrap();
+        assert_eq!(context.rf_ref_freq_raw(), Some(0));
+
+        // Signed fields clamp at both ends.
+        context.set_if_ref_freq_hz(Some(1e14)).unwrap();
+        assert_eq!(context.if_ref_freq_raw(), Some(i64::MAX));
+        context.set_if_ref_freq_hz(Some(-1e14)).unwrap();
+        assert_eq!(context.if_ref_freq_raw(), Some(i64::MIN));
+        context.set_if_ref_freq_hz(Some(-(2f64.powi(43)))).unwrap();
+        assert_eq!(context.if_ref_freq_raw(), Some(i64::MIN));
+        assert_eq!(context.if_ref_freq_hz(), Some(-(2f64.powi(43))));
+
+        // CIF1+ fields go through the same clamping.
+        context.set_aux_freq_hz(Some(1e14)).unwrap();
+        assert_eq!(context.aux_freq_raw(), Some(u64::MAX));
+
+        // NaN is rejected and the field is left alone.
+        context.set_rf_ref_freq_hz(Some(100e6)).unwrap();
+        assert!(matches!(
+            context.set_rf_ref_freq_hz(Some(f64::NAN)),
+            Err(VitaError::OutOfRange)
+        ));
+        assert_eq!(context.rf_ref_freq_hz(), Some(100e6));
+        assert!(context.set_aux_freq_hz(Some(f64::NAN)).is_err());
+        assert_eq!(context.aux_freq_raw(), Some(u64::MAX));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn resolve_reference_point() {
+        use crate::prelude::*;
+        use crate::StreamId;
+        use std::collections::HashMap;
+
+        #[derive(Debug, PartialEq)]
+        struct StreamInfo {
+            name: &'static str,
+            center_freq_hz: f64,
+        }
+
+        let streams = HashMap::from([
+            (
+                0x100,
+                StreamInfo {
+                    name: "rf_input",
+                    center_freq_hz: 2.4e9,
+                },
+            ),
+            (
+                0x200,
+                StreamInfo {
+                    name: "if_output",
+                    center_freq_hz: 70e6,
+                },
+            ),
+        ]);
+
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        assert_eq!(context.resolve_reference_point(&streams), None);
+        context.set_reference_point(Some(StreamId::from(0x200)));
+        packet.update_packet_size();
+
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        let context = parsed.payload().context().unwrap();
+        let info = context.resolve_reference_point(&streams).unwrap();
+        assert_eq!(info.name, "if_output");
+        assert_eq!(info.center_freq_hz, 70e6);
+
+        // Unknown streams don't resolve.
+        let mut context = Context::new();
+        context.set_reference_point_id(Some(0x300));
+        assert_eq!(context.resolve_reference_point(&streams), None);
+    }
+
+    #[test]
+    fn relative_ephemeris_with_ref() {
+        use crate::prelude::*;
+        use crate::EcefEphemeris;
+        let mut ephemeris = EcefEphemeris::default();
+        ephemeris.set_position_x_m(Some(10.0)).unwrap();
+        ephemeris.set_position_y_m(Some(-20.0)).unwrap();
+        ephemeris.set_position_z_m(Some(5.5)).unwrap();
+
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_relative_ephemeris(Some(ephemeris));
+        assert!(matches!(
+            context.relative_ephemeris_with_ref(),
+            Err(VitaError::MissingEphemerisRefId)
+        ));
+        context.set_ephemeris_ref_id(Some(0xCAFE));
+        // An absolute ephemeris doesn't get in the way.
+        context.set_ecef_ephemeris(Some(EcefEphemeris::default()));
+        packet.update_packet_size();
+
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        let context = parsed.payload().context().unwrap();
+        let (relative, ref_id) = context.relative_ephemeris_with_ref().unwrap().unwrap();
+        assert_eq!(relative, &ephemeris);
+        assert_eq!(relative.position_y_m(), Some(-20.0));
+        assert_eq!(ref_id, 0xCAFE);
+
+        // The reference ID alone isn't enough.
+        let mut context = Context::new();
+        context.set_ephemeris_ref_id(Some(0xCAFE));
+        assert_eq!(context.relative_ephemeris_with_ref().unwrap(), None);
+    }
+
+    #[test]
+    fn display_with_device_id() {
+        use crate::prelude::*;
+        use crate::DeviceId;
+        let mut context = Context::new();
+        context.set_device_id(Some(DeviceId::new(0xFF5654, 0x1234).unwrap()));
+        context.set_state_indicators(Some(Default::default()));
+        let s = format!("{context}");
+        assert!(s.contains("Device ID: OUI: FF:56:54, Device code: 0x1234\n"));
+        // The following field starts on its own line.
+        assert!(s.contains("\nState/event indicators: "));
+    }
+
+    #[test]
+    fn toggle_context_changed() {
+        use crate::prelude::*;
+        let mut context = Context::new();
+        assert_eq!(context.cif0.as_u32() & (1 << 31), 0);
+        context.set_context_changed(true);
+        assert!(context.context_changed());
+        assert_eq!(context.cif0.as_u32() & (1 << 31), 1 << 31);
+        context.set_context_changed(false);
+        assert!(!context.context_changed());
+        assert_eq!(context.cif0.as_u32(), 0);
+    }
+
+    #[test]
+    fn check_nyquist() {
+        use crate::prelude::*;
+        let mut context = Context::new();
+        assert!(context.check_nyquist().is_ok());
+        context.set_sample_rate_sps(Some(8e6)).unwrap();
+        assert!(context.check_nyquist().is_ok());
+        context.set_bandwidth_hz(Some(8e6)).unwrap();
+        assert!(context.check_nyquist().is_ok());
+        context.set_bandwidth_hz(Some(6e6)).unwrap();
+        assert!(context.check_nyquist().is_ok());
+
+        context.set_bandwidth_hz(Some(10e6)).unwrap();
+        match context.check_nyquist() {
+            Err(VitaError::BandwidthExceedsSampleRate {
+                bandwidth_hz,
+                sample_rate_sps,
+            }) => {
+                assert_eq!(bandwidth_hz, 10e6);
+                assert_eq!(sample_rate_sps, 8e6);
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn center_freq_hz() {
+        use crate::prelude::*;
+        let mut context = Context::new();
+        assert_eq!(context.center_freq_hz(), None);
+        // An offset alone doesn't give a center frequency.
+        context.set_if_band_offset_hz(Some(1.5e6)).unwrap();
+        assert_eq!(context.center_freq_hz(), None);
+
+        context.set_if_ref_freq_hz(Some(70e6)).unwrap();
+        assert_eq!(context.center_freq_hz(), Some(71.5e6));
+
+        context.set_rf_ref_freq_hz(Some(915e6)).unwrap();
+        assert_eq!(context.center_freq_hz(), Some(916.5e6));
+        context.set_rf_ref_freq_offset_hz(Some(-250e3)).unwrap();
+        assert_eq!(context.center_freq_hz(), Some(916.25e6));
+
+        // Negative band offsets survive a round trip.
+        context.set_if_band_offset_hz(Some(-3e6)).unwrap();
+        let mut packet = Vrt::new_context_packet();
+        *packet.payload_mut().context_mut().unwrap() = context;
+        packet.update_packet_size();
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        let context = parsed.payload().context().unwrap();
+        assert_eq!(context.if_band_offset_hz(), Some(-3e6));
+        assert_eq!(context.center_freq_hz(), Some(911.75e6));
+    }
+
+    #[test]
+    fn sync_indicators() {
+        use crate::prelude::*;
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_bandwidth_hz(Some(8e6)).unwrap();
+        context.set_sample_rate_sps(Some(10e6)).unwrap();
+        context.set_bandwidth_hz(None).unwrap();
+        packet.update_packet_size();
+        let bytes = packet.to_bytes().unwrap();
+        let cif0 = u32::from_be_bytes(bytes[8..12].try_into().unwrap());
+        assert_eq!(cif0 & (1 << 29), 0);
+        assert_ne!(cif0 & (1 << 21), 0);
+
+        // Clearing fields directly leaves stale bits until the indicators
+        // are synced.
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.cif0_fields_mut().sample_rate = None;
+        context.set_spectrum(Some(crate::Spectrum::default()));
+        context.cif1_fields_mut().as_mut().unwrap().spectrum = None;
+        context.cif0_fields_mut().bandwidth = Some(0);
+        assert!(!context.cif0.bandwidth());
+        packet.update_packet_size();
+        assert!(!packet.payload().context().unwrap().cif0.bandwidth());
+        packet.sync_indicators();
+        packet.update_packet_size();
+        let bytes = packet.to_bytes().unwrap();
+        let cif0 = u32::from_be_bytes(bytes[8..12].try_into().unwrap());
+        assert_eq!(cif0, 1 << 29);
+        let parsed = Vrt::try_from(&bytes[..]).unwrap();
+        let context = parsed.payload().context().unwrap();
+        assert_eq!(context.present_fields(), ["bandwidth"]);
+        assert_eq!(context.cif1, None);
+    }
+
+    #[test]
+    fn field_bytes() {
+        use crate::prelude::*;
+        let mut context = Context::new();
+        context.set_bandwidth_hz(Some(8e6)).unwrap();
+        context.set_reference_level_db(Some(-10.0)).unwrap();
+        // 8 MHz as a 64-bit 44.20 fixed-point number.
+        let bandwidth = (8_000_000u64 << 20).to_be_bytes();
+        assert_eq!(context.field_bytes("bandwidth"), Some(bandwidth.to_vec()));
+        assert_eq!(
+            context.field_bytes("reference_level"),
+            Some(vec![0, 0, 0xFB, 0])
+        );
+        assert_eq!(context.field_bytes("sample_rate"), None);
+        assert_eq!(context.field_bytes("not_a_field"), None);
+
+        // Matches the bytes of the serialized packet.
+        let mut packet = Vrt::new_context_packet();
+        *packet.payload_mut().context_mut().unwrap() = context;
+        packet.update_packet_size();
+        let bytes = packet.to_bytes().unwrap();
+        assert_eq!(&bytes[12..20], &bandwidth);
     }
 }
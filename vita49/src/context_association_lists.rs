@@ -6,8 +6,20 @@ Data structures and methods related to context association lists
 (ANSI/VITA-49.2-2017 section 9.13.2).
 */
 
+use alloc::vec::Vec;
 use deku::prelude::*;
 
+use crate::VitaError;
+
+/// Maximum number of entries in the source and system lists.
+const MAX_SOURCE_SYSTEM_LEN: usize = 0x1FF;
+/// Maximum number of entries in the vector-component list.
+const MAX_VECTOR_COMPONENT_LEN: usize = 0xFFFF;
+/// Maximum number of entries in the asynchronous-channel list.
+const MAX_ASYNC_CHANNEL_LEN: usize = 0x7FFF;
+/// Bit in the second word indicating the asynchronous-channel tag list is present.
+const ASYNC_TAG_LIST_BIT: u32 = 1 << 15;
+
 /// Base context association lists structure.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, DekuRead, DekuWrite)]
 #[deku(endian = "endian", ctx = "endian: deku::ctx::Endian")]
@@ -15,21 +27,21 @@ use deku::prelude::*;
 pub struct ContextAssociationLists {
     w1: u32,
     w2: u32,
-    #[deku(count = "((w1 >> 16) & 0x3FF)")]
+    #[deku(count = "((w1 >> 16) & 0x1FF)")]
     source_list: Vec<u32>,
-    #[deku(count = "(w1 & 0x3FF)")]
+    #[deku(count = "(w1 & 0x1FF)")]
     system_list: Vec<u32>,
     #[deku(count = "(w2 >> 16)")]
     vector_component_list: Vec<u32>,
-    #[deku(count = "(w2 & 0x1FF)")]
+    #[deku(count = "(w2 & 0x7FFF)")]
     async_channel_list: Vec<u32>,
-    #[deku(cond = "(w2 & (1 << 15) > 1)", count = "(w2 & 0x1FF)")]
+    #[deku(cond = "(w2 & (1 << 15) > 1)", count = "(w2 & 0x7FFF)")]
     async_channel_tag_list: Vec<u32>,
 }
 
 impl ContextAssociationLists {
     /// Get the size of the lists in 32-bit words.
-    pub fn size_words(&self) -> u16 {
+    pub fn size_words(&self) -> usize {
         // Start with the 2 top words
         let mut ret = 2;
         ret += self.source_list.len();
@@ -37,6 +49,230 @@ impl ContextAssociationLists {
         ret += self.vector_component_list.len();
         ret += self.async_channel_list.len();
         ret += self.async_channel_tag_list.len();
-        ret as u16
+        ret
+    }
+
+    /// Gets the source context association list (stream IDs).
+    pub fn source_list(&self) -> &[u32] {
+        &self.source_list
+    }
+
+    /// Sets the source context association list (stream IDs).
+    ///
+    /// # Errors
+    /// Returns an error if the list has more than 511 entries.
+    pub fn set_source_list(&mut self, list: Vec<u32>) -> Result<(), VitaError> {
+        if list.len() > MAX_SOURCE_SYSTEM_LEN {
+            return Err(VitaError::OutOfRange);
+        }
+        self.w1 = (self.w1 & !(0x1FF << 16)) | ((list.len() as u32) << 16);
+        self.source_list = list;
+        Ok(())
+    }
+
+    /// Gets the system context association list (stream IDs).
+    pub fn system_list(&self) -> &[u32] {
+        &self.system_list
+    }
+
+    /// Sets the system context association list (stream IDs).
+    ///
+    /// # Errors
+    /// Returns an error if the list has more than 511 entries.
+    pub fn set_system_list(&mut self, list: Vec<u32>) -> Result<(), VitaError> {
+        if list.len() > MAX_SOURCE_SYSTEM_LEN {
+            return Err(VitaError::OutOfRange);
+        }
+        self.w1 = (self.w1 & !0x1FF) | list.len() as u32;
+        self.system_list = list;
+        Ok(())
+    }
+
+    /// Gets the vector-component context association list (stream IDs).
+    pub fn vector_component_list(&self) -> &[u32] {
+        &self.vector_component_list
+    }
+
+    /// Sets the vector-component context association list (stream IDs).
+    ///
+    /// # Errors
+    /// Returns an error if the list has more than 65535 entries.
+    pub fn set_vector_component_list(&mut self, list: Vec<u32>) -> Result<(), VitaError> {
+        if list.len() > MAX_VECTOR_COMPONENT_LEN {
+            return Err(VitaError::OutOfRange);
+        }
+        self.w2 = (self.w2 & 0xFFFF) | ((list.len() as u32) << 16);
+        self.vector_component_list = list;
+        Ok(())
+    }
+
+    /// Gets the asynchronous-channel context association list (stream IDs).
+    pub fn async_channel_list(&self) -> &[u32] {
+        &self.async_channel_list
+    }
+
+    /// Gets the asynchronous-channel tag list. If `None` is returned, the
+    /// tag list is not included.
+    pub fn async_channel_tag_list(&self) -> Option<&[u32]> {
+        if self.w2 & ASYNC_TAG_LIST_BIT > 0 {
+            Some(&self.async_channel_tag_list)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the asynchronous-channel context association list (stream IDs)
+    /// and, optionally, a tag for each entry. If `None` is passed for the
+    /// tags, the tag list is not included.
+    ///
+    /// # Errors
+    /// Returns an error if the list has more than 32767 entries or if a
+    /// tag list is given with a different length than the channel list.
+    pub fn set_async_channel_list(
+        &mut self,
+        list: Vec<u32>,
+        tags: Option<Vec<u32>>,
+    ) -> Result<(), VitaError> {
+        if list.len() > MAX_ASYNC_CHANNEL_LEN {
+            return Err(VitaError::OutOfRange);
+        }
+        if tags.as_ref().is_some_and(|t| t.len() != list.len()) {
+            return Err(VitaError::OutOfRange);
+        }
+        self.w2 &= 0xFFFF_0000;
+        self.w2 |= list.len() as u32;
+        if tags.is_some() {
+            self.w2 |= ASYNC_TAG_LIST_BIT;
+        }
+        self.async_channel_list = list;
+        self.async_channel_tag_list = tags.unwrap_or_default();
+        Ok(())
+    }
+
+    /// Sets or (with `None`) removes the asynchronous-channel tag list,
+    /// keeping the current channel list. This sets or clears the tag
+    /// list indicator bit, so the lists' size changes accordingly.
+    ///
+    /// # Errors
+    /// Returns an error if a tag list is given with a different length
+    /// than the channel list.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::ContextAssociationLists;
+    /// let mut lists = ContextAssociationLists::default();
+    /// lists.set_async_channel_list(vec![4, 5], None).unwrap();
+    /// lists.set_async_channel_tag_list(Some(vec![6, 7])).unwrap();
+    /// assert_eq!(lists.async_channel_tag_list(), Some(&[6, 7][..]));
+    /// assert_eq!(lists.size_words(), 2 + 2 + 2);
+    /// ```
+    pub fn set_async_channel_tag_list(&mut self, tags: Option<Vec<u32>>) -> Result<(), VitaError> {
+        self.set_async_channel_list(self.async_channel_list.clone(), tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn source_list_round_trip() {
+        let mut lists = ContextAssociationLists::default();
+        lists.set_source_list(vec![1, 2, 3]).unwrap();
+        lists
+            .set_async_channel_list(vec![4, 5], Some(vec![6, 7]))
+            .unwrap();
+        assert_eq!(lists.size_words(), 2 + 3 + 2 + 2);
+
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_context_association_lists(Some(lists.clone()));
+        packet.update_packet_size();
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        let parsed = parsed
+            .payload()
+            .context()
+            .unwrap()
+            .context_association_lists()
+            .unwrap();
+        assert_eq!(parsed, &lists);
+        assert_eq!(parsed.source_list(), &[1, 2, 3]);
+        assert!(parsed.system_list().is_empty());
+        assert!(parsed.vector_component_list().is_empty());
+        assert_eq!(parsed.async_channel_list(), &[4, 5]);
+        assert_eq!(parsed.async_channel_tag_list(), Some(&[6, 7][..]));
+
+        assert!(lists.set_source_list(vec![0; 512]).is_err());
+        assert!(lists.set_async_channel_list(vec![1], Some(vec![])).is_err());
+        lists.set_async_channel_list(vec![1], None).unwrap();
+        assert_eq!(lists.async_channel_tag_list(), None);
+        assert_eq!(lists.size_words(), 2 + 3 + 1);
+    }
+
+    #[test]
+    fn async_channel_tags() {
+        let mut lists = ContextAssociationLists::default();
+        lists.set_async_channel_list(vec![4, 5], None).unwrap();
+
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_context_association_lists(Some(lists.clone()));
+        packet.update_packet_size();
+        let untagged_size = packet.header().packet_size();
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        let parsed_lists = parsed
+            .payload()
+            .context()
+            .unwrap()
+            .context_association_lists()
+            .unwrap();
+        assert_eq!(parsed_lists.async_channel_tag_list(), None);
+
+        assert!(lists.set_async_channel_tag_list(Some(vec![1])).is_err());
+        lists.set_async_channel_tag_list(Some(vec![6, 7])).unwrap();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_context_association_lists(Some(lists.clone()));
+        packet.update_packet_size();
+        assert_eq!(packet.header().packet_size(), untagged_size + 2);
+        let bytes = packet.to_bytes().unwrap();
+        let parsed = Vrt::try_from(&bytes[..]).unwrap();
+        let parsed_lists = parsed
+            .payload()
+            .context()
+            .unwrap()
+            .context_association_lists()
+            .unwrap();
+        assert_eq!(parsed_lists.async_channel_list(), &[4, 5]);
+        assert_eq!(parsed_lists.async_channel_tag_list(), Some(&[6, 7][..]));
+
+        lists.set_async_channel_tag_list(None).unwrap();
+        assert_eq!(lists.async_channel_tag_list(), None);
+        assert_eq!(lists.async_channel_list(), &[4, 5]);
+        assert_eq!(lists.size_words(), 2 + 2);
+    }
+
+    #[test]
+    fn oversized_context_packet() {
+        let mut lists = ContextAssociationLists::default();
+        lists
+            .set_vector_component_list(vec![0; MAX_VECTOR_COMPONENT_LEN])
+            .unwrap();
+        assert_eq!(lists.size_words(), 65537);
+
+        let mut packet = Vrt::new_context_packet();
+        packet.update_packet_size();
+        let size = packet.header().packet_size();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_context_association_lists(Some(lists));
+        // Header, stream ID, CIF0 and the lists.
+        assert!(matches!(
+            packet.computed_size_words(),
+            Err(VitaError::PayloadTooLarge { words: 65540 })
+        ));
+        assert!(packet.try_update_packet_size().is_err());
+        assert_eq!(packet.header().packet_size(), size);
+        assert_eq!(packet.len_bytes(), 65540 * 4);
+        assert!(packet.checked_to_bytes().is_err());
     }
 }
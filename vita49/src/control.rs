@@ -3,8 +3,9 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use crate::{cif7::Cif7Opts, prelude::*};
+use alloc::vec::Vec;
+use core::fmt;
 use deku::prelude::*;
-use std::fmt;
 
 /// Data structure for control packets. Very similar to [`Context`], but reversed. All the same
 /// fields are used, but processed by a controllee to *set* fields rather than report the current
@@ -52,8 +53,27 @@ pub struct Control {
 }
 
 impl Control {
+    /// Returns the names of the fields included in this control payload,
+    /// in CIF0, CIF1, CIF2, CIF3 order. See [`Context::present_fields()`].
+    pub fn present_fields(&self) -> Vec<&'static str> {
+        let mut names = self.cif0.field_names();
+        for cif in [
+            self.cif1.map(|c| c.field_names()),
+            self.cif2.map(|c| c.field_names()),
+            self.cif3.map(|c| c.field_names()),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            names.extend(cif);
+        }
+        names
+    }
+
+    crate::cif0::sync_indicators_method!();
+
     /// Get the size of the control structure (in 32-bit words).
-    pub fn size_words(&self) -> u16 {
+    pub fn size_words(&self) -> usize {
         // Start with 1 32-bit word for the CIF0 field
         let mut ret = 1 + self.cif0_fields.size_words();
         if let Some(f) = &self.cif1_fields {
@@ -151,7 +171,7 @@ impl Cif3Manipulators for Control {
 }
 
 impl fmt::Display for Control {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         writeln!(f, "Control:")?;
         if let Some(bw) = &self.bandwidth_hz() {
             writeln!(f, "  Bandwidth: {bw} Hz")?;
@@ -163,7 +183,7 @@ impl fmt::Display for Control {
             writeln!(f, "  Sample rate: {samp_rate} sps")?;
         }
         if let Some(device_id) = &self.device_id() {
-            write!(f, "{device_id}")?;
+            writeln!(f, "Device ID: {device_id}")?;
         }
         if let Some(spectrum) = self.spectrum() {
             write!(f, "{spectrum}")?;
@@ -171,3 +191,25 @@ impl fmt::Display for Control {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ActionMode;
+
+    #[test]
+    fn indicator_only_control_keeps_its_bits() {
+        let mut packet = Vrt::new_control_packet();
+        let command = packet.payload_mut().command_mut().unwrap();
+        assert_eq!(command.cam().action_mode(), ActionMode::NoAction);
+        let control = command.payload_mut().control_mut().unwrap();
+        control.cif0.set_bandwidth();
+        control.cif0.set_sample_rate();
+        packet.sync_indicators();
+        packet.update_packet_size();
+        let control = packet.payload().command().unwrap().payload().control();
+        let cif0 = control.unwrap().cif0;
+        assert!(cif0.bandwidth());
+        assert!(cif0.sample_rate());
+    }
+}
@@ -8,15 +8,18 @@ Data structures and methods related to the Control Acknowledgement Mode
 
 use core::fmt;
 
+use deku::ctx::Endian;
+use deku::no_std_io::{Read, Seek};
 use deku::prelude::*;
 
+use crate::VitaError;
+
 /// Base CAM field data structure.
 #[derive(
     Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, DekuRead, DekuWrite,
 )]
 #[deku(endian = "endian", ctx = "endian: deku::ctx::Endian")]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct ControlAckMode(u32);
+pub struct ControlAckMode(#[deku(reader = "Self::read_word(deku::reader, endian)")] u32);
 
 /// Identification format (128-bit UUID or 32-bit ID).
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
@@ -59,14 +62,103 @@ pub enum TimingControlMode {
 }
 
 impl ControlAckMode {
+    /// Largest timing control value defined by the standard; higher
+    /// values are reserved.
+    const MAX_TIMING_CONTROL: u32 = 0b100;
+
+    /// Reads the CAM word, rejecting reserved timing control modes so
+    /// [`ControlAckMode::timing_control()`] can't fail on parsed packets.
+    fn read_word<R: Read + Seek>(reader: &mut Reader<R>, endian: Endian) -> Result<u32, DekuError> {
+        let word = u32::from_reader_with_ctx(reader, endian)?;
+        Ok(Self::from_u32(word)?.0)
+    }
+
+    /// Builds a CAM field from its raw 32-bit value, e.g. to replay a
+    /// captured CAM word exactly. The bits map to the accessors as follows:
+    ///
+    /// | Bits  | Accessor                                          |
+    /// |-------|---------------------------------------------------|
+    /// | 31    | [`controllee_enabled()`](Self::controllee_enabled) |
+    /// | 30    | controllee ID format (1 = UUID)                   |
+    /// | 29    | [`controller_enabled()`](Self::controller_enabled) |
+    /// | 28    | controller ID format (1 = UUID)                   |
+    /// | 27    | [`partial_packet_impl_permitted()`](Self::partial_packet_impl_permitted) |
+    /// | 26    | [`warnings_permitted()`](Self::warnings_permitted) |
+    /// | 25    | [`errors_permitted()`](Self::errors_permitted)     |
+    /// | 24-23 | [`action_mode()`](Self::action_mode)               |
+    /// | 22    | [`nack_only()`](Self::nack_only)                   |
+    /// | 20    | [`validation()`](Self::validation)                 |
+    /// | 19    | [`execution()`](Self::execution)                   |
+    /// | 18    | [`state()`](Self::state)                           |
+    /// | 17    | [`warning()`](Self::warning)                       |
+    /// | 16    | [`error()`](Self::error)                           |
+    /// | 14-12 | [`timing_control()`](Self::timing_control)         |
+    /// | 11    | [`partial_action_taken()`](Self::partial_action_taken) |
+    /// | 10    | [`action_scheduled_or_executed()`](Self::action_scheduled_or_executed) |
+    ///
+    /// The remaining bits are reserved and kept as given.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::ReservedField`] if the timing control bits
+    /// hold a reserved value.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::{ActionMode, ControlAckMode};
+    /// let cam = ControlAckMode::from_u32(0x8100_0000).unwrap();
+    /// assert!(cam.controllee_enabled());
+    /// assert_eq!(cam.action_mode(), ActionMode::Execute);
+    /// assert_eq!(cam.as_u32(), 0x8100_0000);
+    /// ```
+    pub fn from_u32(word: u32) -> Result<ControlAckMode, VitaError> {
+        if (word >> 12) & 0b111 > Self::MAX_TIMING_CONTROL {
+            return Err(VitaError::ReservedField);
+        }
+        Ok(ControlAckMode(word))
+    }
+
+    /// Gets the raw 32-bit value of the CAM field.
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
+
     /// Generate a new Control Ack Mode field that's zeroed out.
     pub fn new(&self) -> ControlAckMode {
         ControlAckMode::default()
     }
 
+    /// Checks the CAM field for combinations the standard doesn't allow:
+    /// the warning bit set without warnings being permitted, or the
+    /// reserved action mode.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::InconsistentCam`] describing the first
+    /// problem found.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::{prelude::*, ControlAckMode};
+    /// let mut cam = ControlAckMode::default();
+    /// cam.set_warning();
+    /// assert!(matches!(cam.validate(), Err(VitaError::InconsistentCam(_))));
+    /// cam.set_warnings_permitted();
+    /// assert!(cam.validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> Result<(), VitaError> {
+        if self.warning() && !self.warnings_permitted() {
+            return Err(VitaError::InconsistentCam(
+                "warning bit set without warnings permitted",
+            ));
+        }
+        if self.action_mode() == ActionMode::Reserved {
+            return Err(VitaError::InconsistentCam("reserved action mode"));
+        }
+        Ok(())
+    }
+
     /// Returns the size of the CAM field in 32-bit words.
-    pub fn size_words(&self) -> u16 {
-        (std::mem::size_of_val(self) / std::mem::size_of::<u32>()) as u16
+    pub fn size_words(&self) -> usize {
+        core::mem::size_of_val(self) / core::mem::size_of::<u32>()
     }
 
     /// Returns true if a bit in the field is set, false if not.
@@ -188,7 +280,7 @@ impl ControlAckMode {
             0b00 => ActionMode::NoAction,
             0b01 => ActionMode::DryRun,
             0b10 => ActionMode::Execute,
-            _ => panic!("invalid action mode"),
+            _ => ActionMode::Reserved,
         }
     }
 
@@ -214,6 +306,35 @@ impl ControlAckMode {
         self.0 = (self.0 & !(0b11 << 23)) | (val << 23);
     }
 
+    /// Returns true if a control packet with this CAM field is expected
+    /// to carry CIF data fields: Action Mode 0 ([`ActionMode::NoAction`])
+    /// packets hold only the CIF indicator words, while any other action
+    /// mode includes the data fields.
+    ///
+    /// This isn't enforced when serializing, which writes whatever fields
+    /// are set. [`Vrt::sync_indicators()`](crate::Vrt::sync_indicators())
+    /// follows it, leaving the indicator bits of Action Mode 0 control
+    /// packets alone. Check it before setting fields to keep a packet in
+    /// line with its action mode.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::{prelude::*, ActionMode, ControlAckMode};
+    /// let mut packet = Vrt::new_control_packet();
+    /// let command = packet.payload_mut().command_mut().unwrap();
+    /// let mut cam = ControlAckMode::default();
+    /// assert!(!cam.will_include_cif_fields());
+    /// cam.set_action_mode(ActionMode::Execute);
+    /// command.set_cam(cam);
+    /// if command.cam().will_include_cif_fields() {
+    ///     let control = command.payload_mut().control_mut().unwrap();
+    ///     control.set_bandwidth_hz(Some(8e6)).unwrap();
+    /// }
+    /// ```
+    pub fn will_include_cif_fields(&self) -> bool {
+        self.action_mode() != ActionMode::NoAction
+    }
+
     /// Return NACK-only mode.
     /// When true: Provide AckV and/or AckX variants of
     /// Acknowledge packet ONLY when Warnings or Errors
@@ -272,6 +393,22 @@ impl ControlAckMode {
         self.unset_bit(18);
     }
 
+    /// Returns true if any acknowledgement (validation, execution or
+    /// query-state) is requested, false if the controllee shouldn't
+    /// reply at all.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::ControlAckMode;
+    /// let mut cam = ControlAckMode::default();
+    /// assert!(!cam.any_ack_requested());
+    /// cam.set_execution();
+    /// assert!(cam.any_ack_requested());
+    /// ```
+    pub fn any_ack_requested(&self) -> bool {
+        self.validation() || self.execution() || self.state()
+    }
+
     /// For ACK packets, returns true if warnings are reported in the ACK, false if not.
     ///
     /// For Control packets, returns true if warnings are requested in the ACK, false if not.
@@ -347,9 +484,138 @@ impl ControlAckMode {
     }
 }
 
+/// Readable serde representation of the CAM field, so JSON fixtures
+/// can say `"action_mode": "Execute"` instead of carrying a raw word.
+/// Missing entries default to unset. Reserved bits are carried as-is
+/// in `reserved` so nothing is lost on a round trip.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct CamFields {
+    controllee_enabled: bool,
+    controllee_id_format: IdFormat,
+    controller_enabled: bool,
+    controller_id_format: IdFormat,
+    partial_packet_impl_permitted: bool,
+    warnings_permitted: bool,
+    errors_permitted: bool,
+    action_mode: ActionMode,
+    nack_only: bool,
+    validation: bool,
+    execution: bool,
+    state: bool,
+    warning: bool,
+    error: bool,
+    timing_control: TimingControlMode,
+    partial_action_taken: bool,
+    action_scheduled_or_executed: bool,
+    reserved: u32,
+}
+
+#[cfg(feature = "serde")]
+impl CamFields {
+    /// CAM bits with no defined meaning (21, 15, and 9 through 0).
+    const RESERVED_BITS: u32 = 0x0020_83FF;
+}
+
+#[cfg(feature = "serde")]
+impl Default for CamFields {
+    fn default() -> Self {
+        ControlAckMode::default().into()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<ControlAckMode> for CamFields {
+    fn from(cam: ControlAckMode) -> Self {
+        CamFields {
+            controllee_enabled: cam.controllee_enabled(),
+            controllee_id_format: cam.controllee_id_format(),
+            controller_enabled: cam.controller_enabled(),
+            controller_id_format: cam.controller_id_format(),
+            partial_packet_impl_permitted: cam.partial_packet_impl_permitted(),
+            warnings_permitted: cam.warnings_permitted(),
+            errors_permitted: cam.errors_permitted(),
+            action_mode: cam.action_mode(),
+            nack_only: cam.nack_only(),
+            validation: cam.validation(),
+            execution: cam.execution(),
+            state: cam.state(),
+            warning: cam.warning(),
+            error: cam.error(),
+            timing_control: cam.timing_control(),
+            partial_action_taken: cam.partial_action_taken(),
+            action_scheduled_or_executed: cam.action_scheduled_or_executed(),
+            reserved: cam.0 & CamFields::RESERVED_BITS,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<CamFields> for ControlAckMode {
+    fn from(fields: CamFields) -> Self {
+        let mut cam = ControlAckMode(fields.reserved & CamFields::RESERVED_BITS);
+        let bits = [
+            (31, fields.controllee_enabled),
+            (30, fields.controllee_id_format == IdFormat::Uuid128bit),
+            (29, fields.controller_enabled),
+            (28, fields.controller_id_format == IdFormat::Uuid128bit),
+            (27, fields.partial_packet_impl_permitted),
+            (26, fields.warnings_permitted),
+            (25, fields.errors_permitted),
+            (22, fields.nack_only),
+            (20, fields.validation),
+            (19, fields.execution),
+            (18, fields.state),
+            (17, fields.warning),
+            (16, fields.error),
+            (11, fields.partial_action_taken),
+            (10, fields.action_scheduled_or_executed),
+        ];
+        for (bit, set) in bits {
+            if set {
+                cam.set_bit(bit);
+            }
+        }
+        // `set_action_mode()` won't write the reserved value, but it
+        // should survive a round trip.
+        if fields.action_mode == ActionMode::Reserved {
+            cam.0 |= 0b11 << 23;
+        } else {
+            cam.set_action_mode(fields.action_mode);
+        }
+        cam.set_timing_control(fields.timing_control);
+        cam
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ControlAckMode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CamFields::from(*self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ControlAckMode {
+    /// Accepts the readable form or, for older fixtures, the raw CAM word.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Word(u32),
+            Fields(CamFields),
+        }
+        match Repr::deserialize(deserializer)? {
+            Repr::Word(word) => ControlAckMode::from_u32(word).map_err(serde::de::Error::custom),
+            Repr::Fields(fields) => Ok(fields.into()),
+        }
+    }
+}
+
 impl fmt::Display for ControlAckMode {
     #[rustfmt::skip]
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         writeln!(f, "CAM:")?;
         writeln!(f, "  Controllee enabled: {}", self.controllee_enabled())?;
         writeln!(f, "  Controllee ID format: {:?}", self.controllee_id_format())?;
@@ -369,3 +635,88 @@ impl fmt::Display for ControlAckMode {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cif_fields_by_action_mode() {
+        let mut cam = ControlAckMode::default();
+        assert_eq!(cam.action_mode(), ActionMode::NoAction);
+        assert!(!cam.will_include_cif_fields());
+        cam.set_action_mode(ActionMode::DryRun);
+        assert!(cam.will_include_cif_fields());
+        cam.set_action_mode(ActionMode::Execute);
+        assert!(cam.will_include_cif_fields());
+    }
+
+    #[test]
+    fn raw_word_round_trip() {
+        let mut configured = ControlAckMode::default();
+        configured.enable_controllee();
+        configured.set_controllee_id_format(IdFormat::Uuid128bit);
+        configured.set_warnings_permitted();
+        configured.set_action_mode(ActionMode::DryRun);
+        configured.set_validation();
+        configured.set_timing_control(TimingControlMode::PermittedEarlyOrLateExecution);
+        let mut ack = ControlAckMode::default();
+        ack.enable_controller();
+        ack.set_execution();
+        ack.set_warning();
+        ack.set_error();
+        ack.set_partial_action_taken();
+        ack.set_action_scheduled_or_executed();
+
+        for cam in [ControlAckMode::default(), configured, ack] {
+            assert_eq!(ControlAckMode::from_u32(cam.as_u32()).unwrap(), cam);
+        }
+        assert_eq!(configured.as_u32(), 0xC490_4000);
+        assert!(matches!(
+            ControlAckMode::from_u32(0b101 << 12),
+            Err(VitaError::ReservedField)
+        ));
+    }
+
+    #[test]
+    fn any_ack_requested() {
+        for bits in 0..8u8 {
+            let mut cam = ControlAckMode::default();
+            if bits & 0b001 != 0 {
+                cam.set_validation();
+            }
+            if bits & 0b010 != 0 {
+                cam.set_execution();
+            }
+            if bits & 0b100 != 0 {
+                cam.set_state();
+            }
+            assert_eq!(cam.any_ack_requested(), bits != 0, "bits {bits:03b}");
+        }
+
+        // Other bits don't count as requesting an ACK.
+        let mut cam = ControlAckMode::default();
+        cam.set_nack_only();
+        cam.set_warning();
+        cam.set_error();
+        assert!(!cam.any_ack_requested());
+    }
+
+    #[test]
+    fn validate_combinations() {
+        let mut cam = ControlAckMode::default();
+        assert!(cam.validate().is_ok());
+        cam.set_warning();
+        assert!(matches!(cam.validate(), Err(VitaError::InconsistentCam(_))));
+        cam.set_warnings_permitted();
+        assert!(cam.validate().is_ok());
+        cam.unset_warning();
+        assert!(cam.validate().is_ok());
+
+        let reserved = ControlAckMode::from_u32(0b11 << 23).unwrap();
+        assert!(matches!(
+            reserved.validate(),
+            Err(VitaError::InconsistentCam(_))
+        ));
+    }
+}
@@ -9,6 +9,8 @@ Data structures and methods related to the device identifier field
 use core::fmt;
 use deku::prelude::*;
 
+use crate::VitaError;
+
 /// Base device ID data structure.
 #[derive(
     Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, DekuRead, DekuWrite,
@@ -18,9 +20,35 @@ use deku::prelude::*;
 pub struct DeviceId(u64);
 
 impl DeviceId {
+    /// Creates a new device ID from a manufacturer OUI and device code.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::OutOfRange`] if `oui` doesn't fit in 24 bits.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::{prelude::*, DeviceId};
+    /// # fn main() -> Result<(), VitaError> {
+    /// let device_id = DeviceId::new(0x12_3456, 0xABCD)?;
+    /// assert_eq!(device_id.oui(), 0x12_3456);
+    /// assert_eq!(device_id.device_code(), 0xABCD);
+    /// assert!(DeviceId::new(0x100_0000, 0).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(oui: u32, device_code: u16) -> Result<DeviceId, VitaError> {
+        if oui > 0xFF_FFFF {
+            return Err(VitaError::OutOfRange);
+        }
+        let mut ret = DeviceId::default();
+        ret.set_manufacturer_oui(oui);
+        ret.set_device_code(device_code);
+        Ok(ret)
+    }
+
     /// Gets the size of the device ID structure in 32-bit words.
-    pub fn size_words(&self) -> u16 {
-        (std::mem::size_of_val(&self.0) / std::mem::size_of::<u32>()) as u16
+    pub fn size_words(&self) -> usize {
+        core::mem::size_of_val(&self.0) / core::mem::size_of::<u32>()
     }
 
     /// Gets the manufacturer Organizational Unique Identifier (OUI).
@@ -28,6 +56,11 @@ impl DeviceId {
         ((self.0 >> 32) & 0xFF_FFFF) as u32
     }
 
+    /// Gets the manufacturer OUI. Shorthand for [`DeviceId::manufacturer_oui()`].
+    pub fn oui(&self) -> u32 {
+        self.manufacturer_oui()
+    }
+
     /// Sets the manufacturer Organizational Unique Identifier (OUI).
     ///
     /// Note: while this API takes a 32-bit integer, only the least
@@ -49,10 +82,34 @@ impl DeviceId {
 }
 
 impl fmt::Display for DeviceId {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Device ID:")?;
-        writeln!(f, "  Manufacturer OUI: {:#x}", self.manufacturer_oui())?;
-        writeln!(f, "  Device code: {}", self.device_code())?;
-        Ok(())
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let oui = self.manufacturer_oui();
+        write!(
+            f,
+            "OUI: {:02X}:{:02X}:{:02X}, Device code: {:#06x}",
+            (oui >> 16) & 0xFF,
+            (oui >> 8) & 0xFF,
+            oui & 0xFF,
+            self.device_code()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn device_id_layout() {
+        let device_id = DeviceId::new(0xAB_CDEF, 0x1234).unwrap();
+        // Word 1: 8 reserved bits, then the OUI.
+        // Word 2: 16 reserved bits, then the device code.
+        assert_eq!(device_id.0, 0x00AB_CDEF_0000_1234);
+        assert_eq!(device_id.size_words(), 2);
+        assert_eq!(format!("{device_id}"), "OUI: AB:CD:EF, Device code: 0x1234");
+        assert!(matches!(
+            DeviceId::new(0x100_0000, 0x1234),
+            Err(VitaError::OutOfRange)
+        ));
     }
 }
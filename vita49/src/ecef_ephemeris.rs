@@ -7,6 +7,49 @@ Data structures and methods related to the ECEF ephemeris format
 */
 
 use deku::prelude::*;
+use fixed::{
+    types::extra::{U16, U22, U5},
+    FixedI32,
+};
+
+/// Raw value used by ephemeris and formatted GPS fields that are not specified.
+pub(crate) const UNSPECIFIED: i32 = 0x7FFF_FFFF;
+
+/// Generates a getter/setter pair for a fixed-point geolocation field
+/// where the unspecified sentinel maps to `None`. Shared with the
+/// formatted GPS structure.
+macro_rules! geolocation_field {
+    ($field:ident, $getter:ident, $setter:ident, $fixed:ty, $doc:literal) => {
+        #[doc = concat!("Gets the ", $doc, ". If `None` is returned, the value is unspecified.")]
+        pub fn $getter(&self) -> Option<f64> {
+            if self.$field == $crate::ecef_ephemeris::UNSPECIFIED {
+                None
+            } else {
+                Some(<$fixed>::from_bits(self.$field).to_num())
+            }
+        }
+
+        #[doc = concat!("Sets the ", $doc, ". If `None` is passed, the value will be marked unspecified.")]
+        ///
+        /// Values beyond the field's range are clamped to it, stopping one
+        /// step short of the largest value, which is reserved to mark the
+        /// field unspecified.
+        ///
+        /// # Errors
+        /// Returns [`VitaError::OutOfRange`](crate::VitaError::OutOfRange)
+        /// if the value is NaN.
+        pub fn $setter(&mut self, value: Option<f64>) -> Result<(), $crate::VitaError> {
+            self.$field = match value {
+                Some(v) => $crate::fixed_point::saturating_from_f64::<$fixed>(v)?
+                    .to_bits()
+                    .min($crate::ecef_ephemeris::UNSPECIFIED - 1),
+                None => $crate::ecef_ephemeris::UNSPECIFIED,
+            };
+            Ok(())
+        }
+    };
+}
+pub(crate) use geolocation_field;
 
 /// Base ECEF ephemeris data structure.
 #[derive(
@@ -32,7 +75,130 @@ pub struct EcefEphemeris {
 
 impl EcefEphemeris {
     /// Gets the size of the ECEF ephemeris field in 32-bit words.
-    pub fn size_words(&self) -> u16 {
-        (std::mem::size_of_val(self) / std::mem::size_of::<u32>()) as u16
+    pub fn size_words(&self) -> usize {
+        core::mem::size_of_val(self) / core::mem::size_of::<u32>()
+    }
+
+    geolocation_field!(
+        position_x,
+        position_x_m,
+        set_position_x_m,
+        FixedI32<U5>,
+        "X position (meters)"
+    );
+    geolocation_field!(
+        position_y,
+        position_y_m,
+        set_position_y_m,
+        FixedI32<U5>,
+        "Y position (meters)"
+    );
+    geolocation_field!(
+        position_z,
+        position_z_m,
+        set_position_z_m,
+        FixedI32<U5>,
+        "Z position (meters)"
+    );
+    geolocation_field!(
+        attitude_alpha,
+        attitude_alpha_deg,
+        set_attitude_alpha_deg,
+        FixedI32<U22>,
+        "alpha attitude angle (degrees)"
+    );
+    geolocation_field!(
+        attitude_beta,
+        attitude_beta_deg,
+        set_attitude_beta_deg,
+        FixedI32<U22>,
+        "beta attitude angle (degrees)"
+    );
+    geolocation_field!(
+        attitude_phi,
+        attitude_phi_deg,
+        set_attitude_phi_deg,
+        FixedI32<U22>,
+        "phi attitude angle (degrees)"
+    );
+    geolocation_field!(
+        velocity_dx,
+        velocity_dx_mps,
+        set_velocity_dx_mps,
+        FixedI32<U16>,
+        "X velocity (meters/second)"
+    );
+    geolocation_field!(
+        velocity_dy,
+        velocity_dy_mps,
+        set_velocity_dy_mps,
+        FixedI32<U16>,
+        "Y velocity (meters/second)"
+    );
+    geolocation_field!(
+        velocity_dz,
+        velocity_dz_mps,
+        set_velocity_dz_mps,
+        FixedI32<U16>,
+        "Z velocity (meters/second)"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn position_round_trip() {
+        let mut ephemeris = EcefEphemeris::default();
+        ephemeris.set_position_x_m(Some(1000.0)).unwrap();
+        ephemeris.set_position_y_m(Some(2000.0)).unwrap();
+        ephemeris.set_position_z_m(Some(-500.0)).unwrap();
+        ephemeris.set_attitude_alpha_deg(Some(45.5)).unwrap();
+        ephemeris.set_velocity_dz_mps(Some(-1.25)).unwrap();
+        ephemeris.set_attitude_phi_deg(None).unwrap();
+        // Position has 5 fractional bits.
+        assert_eq!(ephemeris.position_x, 1000 << 5);
+        assert_eq!(ephemeris.position_z, -500 << 5);
+        assert_eq!(ephemeris.attitude_phi, UNSPECIFIED);
+
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_ecef_ephemeris(Some(ephemeris));
+        packet.update_packet_size();
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        let parsed = parsed
+            .payload()
+            .context()
+            .unwrap()
+            .ecef_ephemeris()
+            .unwrap();
+        assert_eq!(parsed.position_x_m(), Some(1000.0));
+        assert_eq!(parsed.position_y_m(), Some(2000.0));
+        assert_eq!(parsed.position_z_m(), Some(-500.0));
+        assert_eq!(parsed.attitude_alpha_deg(), Some(45.5));
+        assert_eq!(parsed.attitude_beta_deg(), Some(0.0));
+        assert_eq!(parsed.attitude_phi_deg(), None);
+        assert_eq!(parsed.velocity_dz_mps(), Some(-1.25));
+    }
+
+    #[test]
+    fn setters_reject_nan_and_avoid_sentinel() {
+        let mut ephemeris = EcefEphemeris::default();
+        ephemeris.set_position_x_m(Some(1.0)).unwrap();
+        assert!(matches!(
+            ephemeris.set_position_x_m(Some(f64::NAN)),
+            Err(VitaError::OutOfRange)
+        ));
+        assert_eq!(ephemeris.position_x_m(), Some(1.0));
+
+        ephemeris.set_position_x_m(Some(1e12)).unwrap();
+        assert_eq!(ephemeris.position_x, UNSPECIFIED - 1);
+        assert!(ephemeris.position_x_m().is_some());
+        ephemeris.set_velocity_dx_mps(Some(f64::INFINITY)).unwrap();
+        assert_eq!(ephemeris.velocity_dx, UNSPECIFIED - 1);
+        ephemeris.set_velocity_dx_mps(Some(-1e12)).unwrap();
+        assert_eq!(ephemeris.velocity_dx, i32::MIN);
     }
 }
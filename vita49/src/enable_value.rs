@@ -0,0 +1,138 @@
+// SPDX-FileCopyrightText: 2025 The vita49-rs Authors
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Data structures and methods related to "enable bit + value bit"
+indicator pairs, as used by the signal data trailer (ANSI/VITA-49.2-2017
+section 5.1.6) and the state and event indicators field (section 9.10.8).
+*/
+
+use core::fmt;
+
+/// An indicator made of an enable bit and a value bit. The value is
+/// only meaningful when the enable bit is set.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnableValue {
+    enabled: bool,
+    value: bool,
+}
+
+impl EnableValue {
+    /// Creates a new indicator from its enable and value bits.
+    pub fn new(enabled: bool, value: bool) -> EnableValue {
+        EnableValue { enabled, value }
+    }
+
+    /// Returns true if the indicator is enabled, false if not.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the value bit. This is only meaningful if the indicator
+    /// is [enabled](EnableValue::enabled).
+    pub fn value(&self) -> bool {
+        self.value
+    }
+
+    /// Sets the enable and value bits.
+    pub fn set(&mut self, enabled: bool, value: bool) {
+        self.enabled = enabled;
+        self.value = value;
+    }
+
+    /// Reads the indicator from the given bit positions of `word`.
+    pub(crate) fn from_bits(word: u32, enable_bit: u32, value_bit: u32) -> EnableValue {
+        EnableValue {
+            enabled: word & (1 << enable_bit) > 0,
+            value: word & (1 << value_bit) > 0,
+        }
+    }
+
+    /// Writes the indicator into the given bit positions of `word`,
+    /// leaving the other bits alone.
+    pub(crate) fn write_bits(&self, word: &mut u32, enable_bit: u32, value_bit: u32) {
+        *word &= !((1 << enable_bit) | (1 << value_bit));
+        *word |= (self.enabled as u32) << enable_bit | (self.value as u32) << value_bit;
+    }
+}
+
+impl From<Option<bool>> for EnableValue {
+    fn from(value: Option<bool>) -> Self {
+        EnableValue {
+            enabled: value.is_some(),
+            value: value.unwrap_or(false),
+        }
+    }
+}
+
+impl From<EnableValue> for Option<bool> {
+    fn from(value: EnableValue) -> Self {
+        value.enabled.then_some(value.value)
+    }
+}
+
+impl fmt::Display for EnableValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.enabled {
+            write!(f, "{}", self.value)
+        } else {
+            write!(f, "disabled")
+        }
+    }
+}
+
+/// Generates a getter/setter pair for an [`EnableValue`] indicator
+/// stored in a `u32` newtype. Shared by the trailer and state and event
+/// indicators structures.
+macro_rules! indicator {
+    ($getter:ident, $setter:ident, $enable_bit:literal, $value_bit:literal, $doc:literal) => {
+        #[doc = concat!("Returns the ", $doc, " indicator status if enabled.")]
+        pub fn $getter(&self) -> Option<bool> {
+            $crate::enable_value::EnableValue::from_bits(self.0, $enable_bit, $value_bit).into()
+        }
+
+        #[doc = concat!("Sets the ", $doc, " indicator. If `None` is passed, the indicator will be disabled.")]
+        pub fn $setter(&mut self, value: Option<bool>) {
+            $crate::enable_value::EnableValue::from(value).write_bits(
+                &mut self.0,
+                $enable_bit,
+                $value_bit,
+            );
+        }
+    };
+}
+pub(crate) use indicator;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn enable_value_combinations() {
+        for (enabled, value, option, display) in [
+            (false, false, None, "disabled"),
+            (false, true, None, "disabled"),
+            (true, false, Some(false), "false"),
+            (true, true, Some(true), "true"),
+        ] {
+            let mut ev = EnableValue::default();
+            ev.set(enabled, value);
+            assert_eq!(ev, EnableValue::new(enabled, value));
+            assert_eq!(ev.enabled(), enabled);
+            assert_eq!(ev.value(), value);
+            assert_eq!(Option::<bool>::from(ev), option);
+            assert_eq!(ev.to_string(), display);
+
+            // Only the two chosen bits are touched.
+            let mut word = 0x0F0F_0F0F;
+            ev.write_bits(&mut word, 31, 19);
+            assert_eq!(word & !((1 << 31) | (1 << 19)), 0x0F07_0F0F);
+            assert_eq!(EnableValue::from_bits(word, 31, 19), ev);
+        }
+
+        assert_eq!(EnableValue::from(None), EnableValue::new(false, false));
+        assert_eq!(EnableValue::from(Some(true)), EnableValue::new(true, true));
+    }
+}
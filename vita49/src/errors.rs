@@ -5,10 +5,17 @@
 Error types/enumerations for the `vita49` crate.
 */
 
+use alloc::borrow::Cow;
+use alloc::string::ToString;
+use deku::DekuError;
 use thiserror::Error;
 
 /// Generic `vita49` crate error enumeration.
+///
+/// New variants may be added in minor releases, so matches on it need a
+/// wildcard arm.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum VitaError {
     /// Indicates a payload that requires an even number of 32-bit words
     /// was given something else.
@@ -22,6 +29,10 @@ pub enum VitaError {
     /// packets is executed on something else.
     #[error("function can only run on context packets")]
     ContextOnly,
+    /// Error given when a function that can only operate on extension
+    /// context packets is executed on something else.
+    #[error("function can only run on extension context packets")]
+    ExtensionContextOnly,
     /// Error given when a function that can only operate on command
     /// packets is executed on something else.
     #[error("function can only run on command packets")]
@@ -64,4 +75,193 @@ pub enum VitaError {
     /// Error given when trying to set a reserved value.
     #[error("attempted to set reserved field")]
     ReservedField,
+    /// Error given when an input buffer is shorter than the packet
+    /// it is supposed to hold.
+    #[error("buffer too short: need {needed} bytes, got {available}")]
+    BufferTooShort {
+        /// Number of bytes required.
+        needed: usize,
+        /// Number of bytes available.
+        available: usize,
+    },
+    /// Error given when a real-time (picosecond) fractional timestamp
+    /// is one second or more.
+    #[error("fractional timestamp out of range: {0} ps is not below one second")]
+    FractionalTimestampOutOfRange(u64),
+    /// Error given when a packet header claims a size above the limit
+    /// passed to [`Vrt::try_from_with_limit()`](crate::Vrt::try_from_with_limit()).
+    #[error("packet too large: header says {words} words, limit is {max}")]
+    PacketExceedsLimit {
+        /// Packet size from the header (32-bit words).
+        words: u16,
+        /// Maximum accepted packet size (32-bit words).
+        max: u16,
+    },
+    /// Error given when the packet type field of a header holds a
+    /// reserved value.
+    #[error("invalid packet type: {0:#x}")]
+    InvalidPacketType(u8),
+    /// Error given when text that must be ASCII contains other characters.
+    #[error("text must be ASCII")]
+    NonAsciiText,
+    /// Error given when a payload would make the packet larger than the
+    /// 16-bit packet size field can describe (65535 32-bit words).
+    #[error("payload too large: packet would be {words} words, max is 65535")]
+    PayloadTooLarge {
+        /// Size the packet would have (32-bit words).
+        words: usize,
+    },
+    /// Error given when a buffer that must hold whole 32-bit words has a
+    /// length that isn't a multiple of 4.
+    #[error("{len} bytes isn't a whole number of 32-bit words")]
+    PartialWord {
+        /// Length of the buffer (bytes).
+        len: usize,
+    },
+    /// Error given when the packet size in a header is too small to
+    /// hold the fields its indicator bits say are present.
+    #[error("packet size field is too small for the fields it claims to include")]
+    InvalidPacketSize,
+    /// Error given when a data packet's trailer-included bit is set but
+    /// the packet size leaves no room for the trailer word after the
+    /// header, stream ID, class ID and timestamps.
+    #[error("trailer-included bit is set but the packet size leaves no room for a trailer")]
+    TrailerSizeConflict,
+    /// Error given when an ACK packet's CAM field doesn't select exactly
+    /// one of the validation, execution, or query ACK types.
+    #[error(
+        "CAM field in ACK packet does not exclusively select one of Validation, Exec, or Query"
+    )]
+    AmbiguousAckCam,
+    /// Error given when a CAM field combines bits the standard doesn't
+    /// allow together, e.g. reporting warnings that weren't permitted.
+    #[error("inconsistent CAM field: {0}")]
+    InconsistentCam(&'static str),
+    /// Error given when a command packet's payload type doesn't match
+    /// the type selected by its header indicators and CAM field.
+    #[error("command payload is a {payload} but the header/CAM select a {expected}")]
+    CommandPayloadMismatch {
+        /// Payload type selected by the header and CAM field.
+        expected: &'static str,
+        /// Payload type actually stored.
+        payload: &'static str,
+    },
+    /// Error given when the packet size field in a header doesn't match
+    /// the size of the packet's contents.
+    #[error("packet size mismatch: header says {header} words, contents are {computed} words")]
+    PacketSizeMismatch {
+        /// Packet size from the header (32-bit words).
+        header: u16,
+        /// Packet size computed from the contents (32-bit words).
+        computed: u16,
+    },
+    /// Error given when the packet ends before a field could be read.
+    /// Reported by the slice-aware parsers (e.g.
+    /// [`Vrt::try_parse()`](crate::Vrt::try_parse())), which know where
+    /// the field starts; a bare [`DekuError`] converts to
+    /// [`VitaError::Parse`] instead.
+    #[error("incomplete packet: field at byte offset {offset} needs {needed} more bytes")]
+    Incomplete {
+        /// Byte offset of the field that couldn't be read.
+        offset: usize,
+        /// Number of bytes missing to read the field.
+        needed: usize,
+    },
+    /// Error given when a field doesn't hold a valid value (e.g. an
+    /// unknown enumeration).
+    #[error("invalid field value: {0}")]
+    InvalidValue(Cow<'static, str>),
+    /// Error given when a packet can't be parsed for any other reason.
+    #[error("failed to parse packet: {0}")]
+    Parse(DekuError),
+    /// Error given when reading or writing a packet fails at the I/O layer.
+    #[cfg(feature = "std")]
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// Error given when a packet parsed in VITA 49.0 mode uses a construct
+    /// that only exists in VITA 49.2.
+    #[error("not a VITA 49.0 packet: {0}")]
+    NotV490(&'static str),
+    /// Error given when building an ACK reply for a command packet that
+    /// doesn't request any ACK.
+    #[error("command packet doesn't request an ACK")]
+    NoAckRequested,
+    /// Error given when a context packet's bandwidth is larger than its
+    /// sample rate (see [`Context::check_nyquist()`](crate::Context::check_nyquist())).
+    #[error("bandwidth {bandwidth_hz} Hz exceeds sample rate {sample_rate_sps} sps")]
+    BandwidthExceedsSampleRate {
+        /// Bandwidth from the packet (Hz).
+        bandwidth_hz: f64,
+        /// Sample rate from the packet (samples per second).
+        sample_rate_sps: f64,
+    },
+    /// Error given when a context packet carries a relative ephemeris
+    /// without the ephemeris reference ID identifying its reference frame.
+    #[error("relative ephemeris is set without an ephemeris reference ID")]
+    MissingEphemerisRefId,
+    /// Error given when a frame checksum doesn't match the packet bytes.
+    #[error("CRC mismatch: expected {expected:#010x}, computed {actual:#010x}")]
+    CrcMismatch {
+        /// Checksum carried in the frame.
+        expected: u32,
+        /// Checksum computed over the packet bytes.
+        actual: u32,
+    },
+}
+
+/// A [`DekuError`] can only carry a message, so a `VitaError` raised while
+/// parsing crosses deku as its `Display` text. Converting the `DekuError`
+/// back restores the typed variant for the errors the crate's own readers
+/// raise (a reserved packet type, an ambiguous ACK CAM, or a trailer the
+/// packet size leaves no room for); any other variant comes back as
+/// [`VitaError::InvalidValue`].
+impl From<VitaError> for DekuError {
+    fn from(e: VitaError) -> DekuError {
+        DekuError::Parse(Cow::from(format!("{e}")))
+    }
+}
+
+impl VitaError {
+    /// Recovers an error raised by one of the crate's deku readers from
+    /// the message it was carried in.
+    fn from_reader_message(msg: &str) -> Option<VitaError> {
+        [
+            VitaError::TrailerSizeConflict,
+            VitaError::AmbiguousAckCam,
+            VitaError::CommandOnly,
+        ]
+        .into_iter()
+        .chain((0..=0xF).map(VitaError::InvalidPacketType))
+        .find(|e| e.to_string() == msg)
+    }
+
+    /// Converts a [`DekuError`] raised while reading a `len`-byte input,
+    /// `bits_read` bits in, turning an incomplete read into
+    /// [`VitaError::Incomplete`] with the offset and the actual shortfall.
+    pub(crate) fn from_deku_at(e: DekuError, len: usize, bits_read: usize) -> VitaError {
+        match e {
+            DekuError::Incomplete(size) => {
+                let offset = bits_read / 8;
+                VitaError::Incomplete {
+                    offset,
+                    needed: size.byte_size().saturating_sub(len.saturating_sub(offset)),
+                }
+            }
+            e => e.into(),
+        }
+    }
+}
+
+impl From<DekuError> for VitaError {
+    fn from(e: DekuError) -> VitaError {
+        match e {
+            DekuError::Parse(msg) | DekuError::Assertion(msg) => {
+                VitaError::from_reader_message(&msg).unwrap_or(VitaError::InvalidValue(msg))
+            }
+            DekuError::IdVariantNotFound => {
+                VitaError::InvalidValue(Cow::from("no matching variant for field value"))
+            }
+            e => VitaError::Parse(e),
+        }
+    }
 }
@@ -0,0 +1,166 @@
+// SPDX-FileCopyrightText: 2025 The vita49-rs Authors
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Extension context packet payloads (ANSI/VITA-49.2-2017 section 5.1.3).
+The payload format of an extension context packet is defined by the
+class ID rather than the standard, so it's kept as opaque bytes.
+*/
+
+use alloc::vec::Vec;
+use deku::prelude::*;
+
+use crate::packet_header::PacketHeader;
+use crate::payload::Payload;
+use crate::signal_data::{check_payload_len, SignalData};
+use crate::VitaError;
+
+/// Extension context payload. The standard packet prologue (stream ID,
+/// class ID, and timestamps) is parsed as usual; everything after it
+/// is kept as raw bytes.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, DekuRead, DekuWrite)]
+#[deku(
+    endian = "endian",
+    ctx = "endian: deku::ctx::Endian, _packet_header: &PacketHeader"
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExtensionContext {
+    #[deku(
+        reader = "SignalData::read_payload(deku::reader, _packet_header.payload_size_words(), endian)",
+        writer = "SignalData::write_payload(deku::writer, &self.data, endian)"
+    )]
+    data: Vec<u8>,
+}
+
+impl TryFrom<Payload> for ExtensionContext {
+    type Error = Payload;
+
+    fn try_from(value: Payload) -> Result<Self, Self::Error> {
+        match value {
+            Payload::ExtensionContext(c) => Ok(c),
+            a => Err(a),
+        }
+    }
+}
+
+impl ExtensionContext {
+    /// Create a new, empty extension context payload.
+    pub fn new() -> ExtensionContext {
+        ExtensionContext::default()
+    }
+
+    /// Get the raw payload bytes.
+    pub fn payload(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Set the raw payload bytes. The payload is zero-padded to a whole
+    /// number of 32-bit words when serialized.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::PayloadTooLarge`] (leaving the payload
+    /// unchanged) if the payload alone wouldn't fit in a 65535-word
+    /// packet.
+    pub fn set_payload(&mut self, bytes: impl Into<Vec<u8>>) -> Result<(), VitaError> {
+        let bytes = bytes.into();
+        check_payload_len(bytes.len())?;
+        self.data = bytes;
+        Ok(())
+    }
+
+    /// Gets the size of the payload in 32-bit words. This isn't limited
+    /// to what the packet size field can hold: an oversized payload is
+    /// reported by [`Vrt::try_update_packet_size()`](crate::Vrt::try_update_packet_size()).
+    pub fn size_words(&self) -> usize {
+        (self.data.len() + 3) / 4
+    }
+
+    /// Gets the size of the payload in bytes, including the padding of a
+    /// final partial word.
+    pub fn payload_size_bytes(&self) -> usize {
+        self.size_words() * 4
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn extension_context_round_trip() {
+        #[rustfmt::skip]
+        let bytes: [u8; 36] = [
+            // Header: extension context, class ID, UTC/real-time timestamps
+            0x58, 0x60, 0x00, 0x09,
+            // Stream ID
+            0x00, 0x00, 0x00, 0x2A,
+            // Class ID
+            0x00, 0xFF, 0xFF, 0xFA, 0x00, 0x01, 0x00, 0x02,
+            // Integer timestamp
+            0x65, 0x00, 0x00, 0x01,
+            // Fractional timestamp
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xE8,
+            // Opaque payload
+            0xDE, 0xAD, 0xBE, 0xEF, 0x01, 0x02, 0x03, 0x04,
+        ];
+        let packet = Vrt::try_from(&bytes[..]).unwrap();
+        assert_eq!(packet.header().packet_type(), PacketType::ExtensionContext);
+        assert!(packet.is_extension_context());
+        assert!(!packet.is_context());
+        assert_eq!(packet.stream_id(), Some(0x2A));
+        assert_eq!(packet.class_id().unwrap().oui(), 0xFF_FFFA);
+        assert_eq!(packet.integer_timestamp(), Some(0x6500_0001));
+        assert_eq!(packet.fractional_timestamp(), Some(1000));
+        let ext = packet.payload().extension_context().unwrap();
+        assert_eq!(
+            ext.payload(),
+            &[0xDE, 0xAD, 0xBE, 0xEF, 0x01, 0x02, 0x03, 0x04]
+        );
+        assert_eq!(packet.to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn new_extension_context_packet() {
+        let mut packet = Vrt::new_extension_context_packet();
+        packet
+            .payload_mut()
+            .extension_context_mut()
+            .unwrap()
+            .set_payload([1, 2, 3, 4, 5])
+            .unwrap();
+        packet.update_packet_size();
+        assert!(packet.validate_size().is_ok());
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        assert_eq!(
+            parsed.payload().extension_context().unwrap().payload(),
+            &[1, 2, 3, 4, 5, 0, 0, 0]
+        );
+        assert!(matches!(
+            packet.payload().context(),
+            Err(VitaError::ContextOnly)
+        ));
+    }
+
+    #[test]
+    fn oversized_payload() {
+        let mut packet = Vrt::new_extension_context_packet();
+        let ext = packet.payload_mut().extension_context_mut().unwrap();
+        ext.set_payload([1, 2, 3, 4]).unwrap();
+        assert!(matches!(
+            ext.set_payload(vec![0; 280_000]),
+            Err(VitaError::PayloadTooLarge { words: 70_001 })
+        ));
+        assert_eq!(ext.payload(), &[1, 2, 3, 4]);
+        // The payload fits, but not along with the header and stream ID.
+        ext.set_payload(vec![0; 65534 * 4]).unwrap();
+        assert!(matches!(
+            packet.try_update_packet_size(),
+            Err(VitaError::PayloadTooLarge { words: 65536 })
+        ));
+        let ext = packet.payload_mut().extension_context_mut().unwrap();
+        ext.set_payload(vec![0; 65533 * 4]).unwrap();
+        packet.try_update_packet_size().unwrap();
+        assert_eq!(packet.header().packet_size(), u16::MAX);
+        assert_eq!(packet.len_bytes(), packet.to_bytes().unwrap().len());
+    }
+}
@@ -0,0 +1,53 @@
+// SPDX-FileCopyrightText: 2025 The vita49-rs Authors
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Helpers for converting floating-point values into the fixed-point
+formats used throughout the standard.
+
+Every setter taking a floating-point value goes through
+[`saturating_from_f64()`], so they all follow the same rules: values
+outside the range of the field are clamped to its minimum or maximum,
+and NaN, which has no fixed-point representation, is rejected with
+[`VitaError::OutOfRange`] without changing the field. Fields with an
+"unspecified" marker value (e.g. geolocation) are clamped short of it.
+*/
+
+use crate::VitaError;
+use fixed::traits::Fixed;
+
+/// Converts `value` to the fixed-point type `F`, clamping values outside
+/// its range to its minimum or maximum.
+///
+/// # Errors
+/// Returns [`VitaError::OutOfRange`] if `value` is NaN.
+pub(crate) fn saturating_from_f64<F: Fixed>(value: f64) -> Result<F, VitaError> {
+    if value.is_nan() {
+        return Err(VitaError::OutOfRange);
+    }
+    Ok(F::saturating_from_num(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fixed::types::extra::{U20, U7};
+    use fixed::{FixedI16, FixedU64};
+
+    #[test]
+    fn saturating_conversions() {
+        type Db = FixedI16<U7>;
+        assert_eq!(saturating_from_f64::<Db>(1.5).ok(), Some(Db::from_num(1.5)));
+        assert_eq!(saturating_from_f64::<Db>(1e6).ok(), Some(Db::MAX));
+        assert_eq!(
+            saturating_from_f64::<Db>(f64::NEG_INFINITY).ok(),
+            Some(Db::MIN)
+        );
+        assert!(matches!(
+            saturating_from_f64::<Db>(f64::NAN),
+            Err(VitaError::OutOfRange)
+        ));
+        type Hz = FixedU64<U20>;
+        assert_eq!(saturating_from_f64::<Hz>(-1.0).ok(), Some(Hz::ZERO));
+    }
+}
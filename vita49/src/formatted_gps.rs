@@ -6,7 +6,16 @@ Data structures and methods related to the formatted GPS format
 (ANSI/VITA-49.2-2017 section 9.4.5).
 */
 
+use core::fmt;
+
 use deku::prelude::*;
+use fixed::{
+    types::extra::{U16, U22, U5},
+    FixedI32,
+};
+
+use crate::ecef_ephemeris::geolocation_field;
+use crate::{Tsf, Tsi};
 
 /// Base formatted GPS data structure.
 #[derive(
@@ -30,7 +39,208 @@ pub struct FormattedGps {
 
 impl FormattedGps {
     /// Gets the size of the formatted GPS structure in 32-bit words.
-    pub fn size_words(&self) -> u16 {
-        (std::mem::size_of_val(self) / std::mem::size_of::<u32>()) as u16
+    pub fn size_words(&self) -> usize {
+        core::mem::size_of_val(self) / core::mem::size_of::<u32>()
+    }
+
+    /// Gets the TimeStamp-Integer (TSI) mode of the position fix timestamp.
+    pub fn tsi(&self) -> Tsi {
+        (((self.w1 >> 26) & 0b11) as u8).try_into().unwrap()
+    }
+
+    /// Sets the TimeStamp-Integer (TSI) mode of the position fix timestamp.
+    pub fn set_tsi(&mut self, tsi: Tsi) {
+        self.w1 = (self.w1 & !(0b11 << 26)) | ((tsi as u32) << 26);
+    }
+
+    /// Gets the TimeStamp-Fractional (TSF) mode of the position fix timestamp.
+    pub fn tsf(&self) -> Tsf {
+        (((self.w1 >> 24) & 0b11) as u8).try_into().unwrap()
+    }
+
+    /// Sets the TimeStamp-Fractional (TSF) mode of the position fix timestamp.
+    pub fn set_tsf(&mut self, tsf: Tsf) {
+        self.w1 = (self.w1 & !(0b11 << 24)) | ((tsf as u32) << 24);
+    }
+
+    /// Gets the GPS/INS manufacturer OUI.
+    pub fn manufacturer_oui(&self) -> u32 {
+        self.w1 & 0xFF_FFFF
+    }
+
+    /// Sets the GPS/INS manufacturer OUI (only the lower 24 bits are used).
+    pub fn set_manufacturer_oui(&mut self, oui: u32) {
+        self.w1 = (self.w1 & !0xFF_FFFF) | (oui & 0xFF_FFFF);
+    }
+
+    /// Gets the integer timestamp of the position fix. If `None` is
+    /// returned, the timestamp is unspecified.
+    pub fn integer_timestamp(&self) -> Option<u32> {
+        (self.ts1 != u32::MAX).then_some(self.ts1)
+    }
+
+    /// Sets the integer timestamp of the position fix. If `None` is
+    /// passed, the timestamp will be marked unspecified.
+    pub fn set_integer_timestamp(&mut self, timestamp: Option<u32>) {
+        self.ts1 = timestamp.unwrap_or(u32::MAX);
+    }
+
+    /// Gets the fractional timestamp of the position fix. If `None` is
+    /// returned, the timestamp is unspecified.
+    pub fn fractional_timestamp(&self) -> Option<u64> {
+        let ts = ((self.ts2 as u64) << 32) | self.ts3 as u64;
+        (ts != u64::MAX).then_some(ts)
+    }
+
+    /// Sets the fractional timestamp of the position fix. If `None` is
+    /// passed, the timestamp will be marked unspecified.
+    pub fn set_fractional_timestamp(&mut self, timestamp: Option<u64>) {
+        let ts = timestamp.unwrap_or(u64::MAX);
+        self.ts2 = (ts >> 32) as u32;
+        self.ts3 = ts as u32;
+    }
+
+    geolocation_field!(
+        latitude,
+        latitude_deg,
+        set_latitude_deg,
+        FixedI32<U22>,
+        "latitude (degrees)"
+    );
+    geolocation_field!(
+        longitude,
+        longitude_deg,
+        set_longitude_deg,
+        FixedI32<U22>,
+        "longitude (degrees)"
+    );
+    geolocation_field!(
+        altitude,
+        altitude_m,
+        set_altitude_m,
+        FixedI32<U5>,
+        "altitude (meters)"
+    );
+    geolocation_field!(
+        speed_over_ground,
+        speed_over_ground_mps,
+        set_speed_over_ground_mps,
+        FixedI32<U16>,
+        "speed over ground (meters/second)"
+    );
+    geolocation_field!(
+        heading_angle,
+        heading_angle_deg,
+        set_heading_angle_deg,
+        FixedI32<U22>,
+        "heading angle (degrees)"
+    );
+    geolocation_field!(
+        track_angle,
+        track_angle_deg,
+        set_track_angle_deg,
+        FixedI32<U22>,
+        "track angle (degrees)"
+    );
+    geolocation_field!(
+        magnetic_variation,
+        magnetic_variation_deg,
+        set_magnetic_variation_deg,
+        FixedI32<U22>,
+        "magnetic variation (degrees)"
+    );
+}
+
+/// Writes an optional value with its unit, or "unspecified".
+fn write_opt(f: &mut fmt::Formatter<'_>, name: &str, v: Option<f64>, unit: &str) -> fmt::Result {
+    match v {
+        Some(v) => writeln!(f, "  {name}: {v} {unit}"),
+        None => writeln!(f, "  {name}: unspecified"),
+    }
+}
+
+impl fmt::Display for FormattedGps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Formatted GPS:")?;
+        writeln!(f, "  Manufacturer OUI: {:06X}", self.manufacturer_oui())?;
+        writeln!(f, "  TSI: {:?}, TSF: {:?}", self.tsi(), self.tsf())?;
+        write_opt(f, "Latitude", self.latitude_deg(), "deg")?;
+        write_opt(f, "Longitude", self.longitude_deg(), "deg")?;
+        write_opt(f, "Altitude", self.altitude_m(), "m")?;
+        write_opt(f, "Speed over ground", self.speed_over_ground_mps(), "m/s")?;
+        write_opt(f, "Heading angle", self.heading_angle_deg(), "deg")?;
+        write_opt(f, "Track angle", self.track_angle_deg(), "deg")?;
+        write_opt(
+            f,
+            "Magnetic variation",
+            self.magnetic_variation_deg(),
+            "deg",
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecef_ephemeris::UNSPECIFIED;
+    use crate::prelude::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn known_coordinate() {
+        let mut gps = FormattedGps::default();
+        gps.set_tsi(Tsi::Utc);
+        gps.set_tsf(Tsf::RealTimePs);
+        gps.set_manufacturer_oui(0x12_3456);
+        gps.set_integer_timestamp(Some(1_700_000_000));
+        gps.set_fractional_timestamp(None);
+        gps.set_latitude_deg(Some(38.8977)).unwrap();
+        gps.set_longitude_deg(Some(-77.0365)).unwrap();
+        gps.set_altitude_m(Some(18.5)).unwrap();
+        gps.set_speed_over_ground_mps(None).unwrap();
+        gps.set_heading_angle_deg(None).unwrap();
+        gps.set_track_angle_deg(None).unwrap();
+        gps.set_magnetic_variation_deg(None).unwrap();
+        assert_eq!(gps.w1, 0x0612_3456);
+        assert_eq!(gps.altitude, 592);
+        assert_eq!(gps.heading_angle, UNSPECIFIED);
+
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_formatted_gps(Some(gps));
+        packet.update_packet_size();
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        let parsed = parsed.payload().context().unwrap().formatted_gps().unwrap();
+        assert_eq!(parsed.tsi(), Tsi::Utc);
+        assert_eq!(parsed.tsf(), Tsf::RealTimePs);
+        assert_eq!(parsed.manufacturer_oui(), 0x12_3456);
+        assert_eq!(parsed.integer_timestamp(), Some(1_700_000_000));
+        assert_eq!(parsed.fractional_timestamp(), None);
+        assert_relative_eq!(parsed.latitude_deg().unwrap(), 38.8977, epsilon = 1e-6);
+        assert_relative_eq!(parsed.longitude_deg().unwrap(), -77.0365, epsilon = 1e-6);
+        assert_eq!(parsed.altitude_m(), Some(18.5));
+        assert_eq!(parsed.speed_over_ground_mps(), None);
+
+        let s = format!("{parsed}");
+        assert!(s.contains("Altitude: 18.5 m"));
+        assert!(s.contains("Heading angle: unspecified"));
+    }
+
+    #[test]
+    fn setters_reject_nan_and_avoid_sentinel() {
+        let mut gps = FormattedGps::default();
+        gps.set_latitude_deg(Some(10.0)).unwrap();
+        assert!(matches!(
+            gps.set_latitude_deg(Some(f64::NAN)),
+            Err(VitaError::OutOfRange)
+        ));
+        assert_eq!(gps.latitude_deg(), Some(10.0));
+
+        // Out-of-range values clamp without reading back as unspecified.
+        gps.set_heading_angle_deg(Some(1000.0)).unwrap();
+        assert_eq!(gps.heading_angle, UNSPECIFIED - 1);
+        assert!(gps.heading_angle_deg().unwrap() > 511.0);
+        gps.set_altitude_m(Some(f64::NEG_INFINITY)).unwrap();
+        assert_eq!(gps.altitude, i32::MIN);
     }
 }
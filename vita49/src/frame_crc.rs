@@ -0,0 +1,105 @@
+// SPDX-FileCopyrightText: 2025 The vita49-rs Authors
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Helpers for transports that frame each VRT packet with a trailing
+CRC32 checksum. This framing is not part of VITA 49 itself. Only
+available with the `frame-crc` feature.
+*/
+
+use alloc::vec::Vec;
+
+use crate::prelude::*;
+
+/// Size of the trailing checksum in bytes.
+const CRC_LEN: usize = 4;
+
+impl Vrt {
+    /// Serializes the packet and appends a CRC32 (IEEE) checksum of the
+    /// serialized bytes. The checksum is written big-endian.
+    ///
+    /// # Errors
+    /// Returns an error if the packet can't be serialized.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_signal_payload(&[1, 2, 3, 4])?;
+    /// let framed = packet.to_bytes_with_crc()?;
+    /// assert_eq!(Vrt::try_from_with_crc(&framed)?, packet);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_bytes_with_crc(&self) -> Result<Vec<u8>, VitaError> {
+        let mut bytes = self.to_bytes()?;
+        let crc = crc32fast::hash(&bytes);
+        bytes.extend_from_slice(&crc.to_be_bytes());
+        Ok(bytes)
+    }
+
+    /// Parses a packet followed by a big-endian CRC32 (IEEE) checksum,
+    /// as produced by [`Vrt::to_bytes_with_crc()`].
+    ///
+    /// # Errors
+    /// Returns [`VitaError::CrcMismatch`] if the checksum doesn't match
+    /// the packet bytes, [`VitaError::BufferTooShort`] if there's no room
+    /// for a checksum, or an error if the packet can't be parsed.
+    pub fn try_from_with_crc(bytes: &[u8]) -> Result<Vrt, VitaError> {
+        if bytes.len() < CRC_LEN {
+            return Err(VitaError::BufferTooShort {
+                needed: CRC_LEN,
+                available: bytes.len(),
+            });
+        }
+        let (packet, crc) = bytes.split_at(bytes.len() - CRC_LEN);
+        let expected = u32::from_be_bytes([crc[0], crc[1], crc[2], crc[3]]);
+        let actual = crc32fast::hash(packet);
+        if actual != expected {
+            return Err(VitaError::CrcMismatch { expected, actual });
+        }
+        Ok(Vrt::try_from(packet)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc_round_trip() {
+        let mut packet = Vrt::new_signal_data_packet();
+        packet.set_stream_id(Some(0x1234));
+        packet.set_signal_payload([1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        let framed = packet.to_bytes_with_crc().unwrap();
+        let bytes = packet.to_bytes().unwrap();
+        assert_eq!(framed.len(), bytes.len() + CRC_LEN);
+        assert_eq!(&framed[..bytes.len()], &bytes[..]);
+        assert_eq!(Vrt::try_from_with_crc(&framed).unwrap(), packet);
+    }
+
+    #[test]
+    fn crc_mismatch() {
+        let packet = Vrt::new_context_packet();
+        let mut framed = packet.to_bytes_with_crc().unwrap();
+        framed[6] ^= 0x01;
+        assert!(matches!(
+            Vrt::try_from_with_crc(&framed),
+            Err(VitaError::CrcMismatch { .. })
+        ));
+
+        let mut framed = packet.to_bytes_with_crc().unwrap();
+        let last = framed.len() - 1;
+        framed[last] ^= 0xFF;
+        assert!(matches!(
+            Vrt::try_from_with_crc(&framed),
+            Err(VitaError::CrcMismatch { .. })
+        ));
+
+        assert!(matches!(
+            Vrt::try_from_with_crc(&framed[..3]),
+            Err(VitaError::BufferTooShort { .. })
+        ));
+    }
+}
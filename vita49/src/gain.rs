@@ -14,9 +14,11 @@ distribution, Stage 1 Gain provides the gain of the device, and
 Stage 2 Gain is set to zero."
 */
 
+use crate::fixed_point::saturating_from_f64;
+use crate::VitaError;
+use core::fmt;
 use deku::prelude::*;
 use fixed::{types::extra::U7, FixedI16};
-use std::fmt;
 
 /// Base gain data structure.
 #[derive(
@@ -28,15 +30,22 @@ pub struct Gain(i32);
 
 impl Gain {
     /// Create a new `Gain` object given stage 1 and 2 gain in dB.
-    pub fn new(stage_1_gain_db: f32, stage_2_gain_db: f32) -> Gain {
-        let s1 = FixedI16::<U7>::from_num(stage_1_gain_db).to_bits() as i32;
-        let s2 = FixedI16::<U7>::from_num(stage_2_gain_db).to_bits() as i32;
-        Gain((s2 << 16) | s1)
+    ///
+    /// Gains outside the representable range are clamped to it. The
+    /// setters below behave the same way.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::OutOfRange`] if either gain is NaN.
+    pub fn new(stage_1_gain_db: f32, stage_2_gain_db: f32) -> Result<Gain, VitaError> {
+        let mut ret = Gain::default();
+        ret.set_stage_1_gain_db(stage_1_gain_db)?;
+        ret.set_stage_2_gain_db(stage_2_gain_db)?;
+        Ok(ret)
     }
 
     /// Gets the size of the gain structure in 32-bit words.
-    pub fn size_words(&self) -> u16 {
-        (std::mem::size_of_val(&self.0) / std::mem::size_of::<u32>()) as u16
+    pub fn size_words(&self) -> usize {
+        core::mem::size_of_val(&self.0) / core::mem::size_of::<u32>()
     }
 
     /// Gets stage 1 gain (dB)
@@ -46,9 +55,13 @@ impl Gain {
     }
 
     /// Sets stage 1 gain (dB)
-    pub fn set_stage_1_gain_db(&mut self, stage_1_gain_db: f32) {
-        let s1 = FixedI16::<U7>::from_num(stage_1_gain_db).to_bits() as i32;
-        self.0 = (self.0 & (0xFFFF_0000u32 as i32)) | s1
+    ///
+    /// # Errors
+    /// Returns [`VitaError::OutOfRange`] if the gain is NaN.
+    pub fn set_stage_1_gain_db(&mut self, stage_1_gain_db: f32) -> Result<(), VitaError> {
+        let s1 = saturating_from_f64::<FixedI16<U7>>(stage_1_gain_db.into())?.to_bits() as i32;
+        self.0 = (self.0 & (0xFFFF_0000u32 as i32)) | s1;
+        Ok(())
     }
 
     /// Gets stage 2 gain (dB)
@@ -58,9 +71,13 @@ impl Gain {
     }
 
     /// Sets stage 2 gain (dB)
-    pub fn set_stage_2_gain_db(&mut self, stage_2_gain_db: f32) {
-        let s2 = FixedI16::<U7>::from_num(stage_2_gain_db).to_bits() as i32;
-        self.0 = (self.0 & 0x0000_FFFF) | (s2 << 16)
+    ///
+    /// # Errors
+    /// Returns [`VitaError::OutOfRange`] if the gain is NaN.
+    pub fn set_stage_2_gain_db(&mut self, stage_2_gain_db: f32) -> Result<(), VitaError> {
+        let s2 = saturating_from_f64::<FixedI16<U7>>(stage_2_gain_db.into())?.to_bits() as i32;
+        self.0 = (self.0 & 0x0000_FFFF) | (s2 << 16);
+        Ok(())
     }
 }
 
@@ -89,7 +106,7 @@ mod tests {
         let context = packet.payload_mut().context_mut().unwrap();
         let mut s1: f32 = 25.2;
         let mut s2: f32 = 0.23;
-        let mut g = Gain::new(s1, s2);
+        let mut g = Gain::new(s1, s2).unwrap();
         context.set_gain(Some(g));
         assert_relative_eq!(
             context.gain().unwrap().stage_1_gain_db(),
@@ -103,8 +120,8 @@ mod tests {
         );
         s1 = -20.5;
         s2 = -11.1;
-        g.set_stage_1_gain_db(s1);
-        g.set_stage_2_gain_db(s2);
+        g.set_stage_1_gain_db(s1).unwrap();
+        g.set_stage_2_gain_db(s2).unwrap();
         context.set_gain(Some(g));
         assert_relative_eq!(
             context.gain().unwrap().stage_1_gain_db(),
@@ -117,4 +134,13 @@ mod tests {
             max_relative = 0.1
         );
     }
+    #[test]
+    fn gain_clamps_and_rejects_nan() {
+        assert!(Gain::new(f32::NAN, 1.0).is_err());
+        let mut g = Gain::new(-1000.0, 1000.0).unwrap();
+        assert_eq!(g.stage_1_gain_db(), -256.0);
+        assert_eq!(g.stage_2_gain_db(), 256.0 - 1.0 / 128.0);
+        assert!(g.set_stage_2_gain_db(f32::NAN).is_err());
+        assert_eq!(g.stage_2_gain_db(), 256.0 - 1.0 / 128.0);
+    }
 }
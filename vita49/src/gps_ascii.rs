@@ -6,14 +6,29 @@ Data structures and methods related to the ASCII GPS format
 (ANSI/VITA-49.2-2017 section 9.4.7).
 */
 
+use alloc::string::String;
+use alloc::vec::Vec;
 use deku::prelude::*;
 
+use crate::VitaError;
+
+/// Number of 32-bit words before the ASCII text: the manufacturer OUI
+/// and the word count.
+const HEADER_WORDS: usize = 2;
+
+/// Most ASCII words a field can carry while its total size still fits
+/// in the 16-bit packet size field.
+const MAX_TEXT_WORDS: usize = u16::MAX as usize - HEADER_WORDS;
+
 /// Base ASCII GPS data structure.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, DekuRead, DekuWrite)]
 #[deku(endian = "endian", ctx = "endian: deku::ctx::Endian")]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GpsAscii {
     w1: u32,
+    // A packet can't hold more than 65535 words, so reject larger counts
+    // before allocating.
+    #[deku(assert = "*num_words as usize <= MAX_TEXT_WORDS")]
     num_words: u32,
     #[deku(count = "num_words")]
     ascii: Vec<u32>,
@@ -21,9 +36,139 @@ pub struct GpsAscii {
 
 impl GpsAscii {
     /// Gets the size of the ASCII GPS field in 32-bit words.
-    pub fn size_words(&self) -> u16 {
-        (((std::mem::size_of_val(&self.w1) + std::mem::size_of_val(&self.num_words))
-            / std::mem::size_of::<u32>())
-            + self.num_words as usize) as u16
+    pub fn size_words(&self) -> usize {
+        HEADER_WORDS + self.num_words as usize
+    }
+
+    /// Gets the manufacturer Organizational Unique Identifier (OUI).
+    pub fn manufacturer_oui(&self) -> u32 {
+        self.w1 & 0xFF_FFFF
+    }
+
+    /// Sets the manufacturer Organizational Unique Identifier (OUI).
+    ///
+    /// Note: while this API takes a 32-bit integer, only the least
+    /// significant 24 bits are used.
+    pub fn set_manufacturer_oui(&mut self, oui: u32) {
+        self.w1 = (self.w1 & !0xFF_FFFF) | (oui & 0xFF_FFFF);
+    }
+
+    /// Gets the ASCII sentence (e.g. NMEA) carried by the field.
+    /// Null padding at the end of the last word is stripped.
+    ///
+    /// # Errors
+    /// Returns an error if the field contains non-ASCII bytes.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::{prelude::*, GpsAscii};
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut gps = GpsAscii::default();
+    /// gps.set_text("$GPGSA,A,3,,,,,,,,,,,,,1.0,1.0,1.0*30")?;
+    /// assert_eq!(gps.text()?, "$GPGSA,A,3,,,,,,,,,,,,,1.0,1.0,1.0*30");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn text(&self) -> Result<String, VitaError> {
+        let mut bytes: Vec<u8> = self.ascii.iter().flat_map(|w| w.to_be_bytes()).collect();
+        while bytes.last() == Some(&0) {
+            bytes.pop();
+        }
+        if !bytes.is_ascii() {
+            return Err(VitaError::NonAsciiText);
+        }
+        // ASCII is always valid UTF-8.
+        Ok(String::from_utf8(bytes).unwrap())
+    }
+
+    /// Sets the ASCII sentence carried by the field. The text is
+    /// packed four characters per 32-bit word and the final word is
+    /// padded with nulls. The word count is updated to match.
+    ///
+    /// [`update_packet_size()`](crate::Vrt::update_packet_size()) should be
+    /// executed after running this method.
+    ///
+    /// # Errors
+    /// Returns an error if `text` contains non-ASCII characters, or
+    /// [`VitaError::PayloadTooLarge`] if the field would be more than
+    /// 65535 words long. The field is left unchanged in both cases.
+    pub fn set_text(&mut self, text: &str) -> Result<(), VitaError> {
+        if !text.is_ascii() {
+            return Err(VitaError::NonAsciiText);
+        }
+        let text_words = (text.len() + 3) / 4;
+        if text_words > MAX_TEXT_WORDS {
+            return Err(VitaError::PayloadTooLarge {
+                words: text_words + HEADER_WORDS,
+            });
+        }
+        self.ascii = text
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| {
+                let mut word = [0; 4];
+                word[..chunk.len()].copy_from_slice(chunk);
+                u32::from_be_bytes(word)
+            })
+            .collect();
+        self.num_words = self.ascii.len() as u32;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    const GPGGA: &str = "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+
+    #[test]
+    fn gpgga_round_trip() {
+        let mut gps = GpsAscii::default();
+        gps.set_manufacturer_oui(0x12_3456);
+        gps.set_text(GPGGA).unwrap();
+        // 65 characters round up to 17 words, plus OUI and word count.
+        assert_eq!(GPGGA.len(), 65);
+        assert_eq!(gps.num_words, 17);
+        assert_eq!(gps.size_words(), 19);
+        assert_eq!(gps.ascii[0], u32::from_be_bytes(*b"$GPG"));
+        assert_eq!(gps.ascii[16], u32::from_be_bytes(*b"7\0\0\0"));
+
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_gps_ascii(Some(gps.clone()));
+        packet.update_packet_size();
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        let parsed_gps = parsed.payload().context().unwrap().gps_ascii().unwrap();
+        assert_eq!(parsed_gps, &gps);
+        assert_eq!(parsed_gps.manufacturer_oui(), 0x12_3456);
+        assert_eq!(parsed_gps.text().unwrap(), GPGGA);
+    }
+
+    #[test]
+    fn non_ascii_rejected() {
+        let mut gps = GpsAscii::default();
+        assert!(matches!(
+            gps.set_text("$GPGGA,12°"),
+            Err(VitaError::NonAsciiText)
+        ));
+        gps.ascii = vec![0xFF00_0000];
+        gps.num_words = 1;
+        assert!(matches!(gps.text(), Err(VitaError::NonAsciiText)));
+    }
+
+    #[test]
+    fn oversized_text_rejected() {
+        let mut gps = GpsAscii::default();
+        gps.set_text(GPGGA).unwrap();
+        assert!(matches!(
+            gps.set_text(&"A".repeat(280_000)),
+            Err(VitaError::PayloadTooLarge { words: 70_002 })
+        ));
+        assert_eq!(gps.text().unwrap(), GPGGA);
+        gps.set_text(&"A".repeat(MAX_TEXT_WORDS * 4)).unwrap();
+        assert_eq!(gps.size_words(), u16::MAX as usize);
+        assert!(gps.set_text(&"A".repeat(MAX_TEXT_WORDS * 4 + 1)).is_err());
     }
 }
@@ -0,0 +1,139 @@
+// SPDX-FileCopyrightText: 2025 The vita49-rs Authors
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Conversions between GPS time (as used by [`Tsi::Gps`](crate::Tsi::Gps)
+timestamps) and UTC.
+
+GPS time counts seconds from 1980-01-06T00:00:00Z without leap seconds,
+so it runs ahead of UTC by the number of leap seconds inserted since
+then. These helpers take that offset as a parameter instead of using a
+leap-second table, so they're only exact for times covered by the
+offset passed in (e.g. [`GPS_LEAP_SECONDS`] for times since 2017).
+*/
+
+use crate::{Tsi, VitaError};
+
+/// Seconds from the Unix epoch to the GPS epoch (1980-01-06T00:00:00Z).
+pub const GPS_EPOCH_UNIX_SECS: i64 = 315_964_800;
+
+/// Current GPS-UTC offset in seconds (GPS is ahead of UTC). This has been
+/// 18 since the leap second at the end of 2016.
+pub const GPS_LEAP_SECONDS: i64 = 18;
+
+/// Converts GPS seconds to UTC seconds since the Unix epoch, given the
+/// GPS-UTC leap-second offset (usually [`GPS_LEAP_SECONDS`]). Returns
+/// `None` if the result overflows an `i64`.
+///
+/// # Example
+/// ```
+/// use vita49::{gps_to_utc, GPS_LEAP_SECONDS};
+/// // 2017-01-01T00:00:00Z
+/// assert_eq!(gps_to_utc(1_167_264_018, GPS_LEAP_SECONDS), Some(1_483_228_800));
+/// ```
+pub fn gps_to_utc(gps_secs: u32, leap_seconds: i64) -> Option<i64> {
+    (GPS_EPOCH_UNIX_SECS + i64::from(gps_secs)).checked_sub(leap_seconds)
+}
+
+/// Converts UTC seconds since the Unix epoch to GPS seconds, given the
+/// GPS-UTC leap-second offset (usually [`GPS_LEAP_SECONDS`]). Returns
+/// `None` if the time can't be represented as a 32-bit GPS timestamp.
+pub fn utc_to_gps(unix_secs: i64, leap_seconds: i64) -> Option<u32> {
+    let gps_secs = unix_secs
+        .checked_sub(GPS_EPOCH_UNIX_SECS)?
+        .checked_add(leap_seconds)?;
+    u32::try_from(gps_secs).ok()
+}
+
+/// Converts seconds since the epoch given by `tsi` to UTC seconds since
+/// the Unix epoch, or `None` if `tsi` isn't [`Tsi::Utc`] or [`Tsi::Gps`]
+/// or the result overflows.
+pub(crate) fn tsi_to_unix_secs(secs: u32, tsi: Tsi, leap_seconds: i64) -> Option<i64> {
+    match tsi {
+        Tsi::Utc => Some(i64::from(secs)),
+        Tsi::Gps => gps_to_utc(secs, leap_seconds),
+        Tsi::Null | Tsi::Other => None,
+    }
+}
+
+/// Converts UTC seconds since the Unix epoch to seconds since the epoch
+/// given by `tsi`.
+///
+/// # Errors
+/// Returns [`VitaError::TimestampModeMismatch`] if `tsi` isn't
+/// [`Tsi::Utc`] or [`Tsi::Gps`], or [`VitaError::OutOfRange`] if the
+/// time can't be represented from that epoch.
+pub(crate) fn unix_secs_to_tsi(
+    unix_secs: i64,
+    tsi: Tsi,
+    leap_seconds: i64,
+) -> Result<u32, VitaError> {
+    let secs = match tsi {
+        Tsi::Utc => u32::try_from(unix_secs).ok(),
+        Tsi::Gps => utc_to_gps(unix_secs, leap_seconds),
+        Tsi::Null | Tsi::Other => return Err(VitaError::TimestampModeMismatch),
+    };
+    secs.ok_or(VitaError::OutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_gps_utc_pair() {
+        // 2017-01-01T00:00:00Z, just after the 18th leap second.
+        let utc = 1_483_228_800;
+        let gps = 1_167_264_018;
+        assert_eq!(gps_to_utc(gps, GPS_LEAP_SECONDS), Some(utc));
+        assert_eq!(utc_to_gps(utc, GPS_LEAP_SECONDS), Some(gps));
+        // GPS epoch, before any leap seconds.
+        assert_eq!(gps_to_utc(0, 0), Some(GPS_EPOCH_UNIX_SECS));
+        assert_eq!(utc_to_gps(GPS_EPOCH_UNIX_SECS - 1, 0), None);
+    }
+
+    #[test]
+    fn tsi_conversions() {
+        assert_eq!(
+            tsi_to_unix_secs(1_167_264_018, Tsi::Gps, 18),
+            Some(1_483_228_800)
+        );
+        // A different leap-second offset shifts the result.
+        assert_eq!(
+            tsi_to_unix_secs(1_167_264_018, Tsi::Gps, 17),
+            Some(1_483_228_801)
+        );
+        assert_eq!(tsi_to_unix_secs(5, Tsi::Utc, 18), Some(5));
+        assert_eq!(tsi_to_unix_secs(5, Tsi::Other, 18), None);
+        assert_eq!(
+            unix_secs_to_tsi(1_483_228_800, Tsi::Gps, 18).ok(),
+            Some(1_167_264_018)
+        );
+        assert!(matches!(
+            unix_secs_to_tsi(-1, Tsi::Utc, 18),
+            Err(VitaError::OutOfRange)
+        ));
+        assert!(matches!(
+            unix_secs_to_tsi(0, Tsi::Null, 18),
+            Err(VitaError::TimestampModeMismatch)
+        ));
+    }
+
+    #[test]
+    fn extreme_values_dont_overflow() {
+        assert_eq!(utc_to_gps(i64::MIN, GPS_LEAP_SECONDS), None);
+        assert_eq!(utc_to_gps(i64::MAX, GPS_LEAP_SECONDS), None);
+        assert_eq!(utc_to_gps(GPS_EPOCH_UNIX_SECS, i64::MAX), None);
+        assert_eq!(utc_to_gps(GPS_EPOCH_UNIX_SECS, i64::MIN), None);
+        assert_eq!(gps_to_utc(u32::MAX, i64::MIN), None);
+        assert_eq!(
+            gps_to_utc(0, i64::MAX),
+            Some(GPS_EPOCH_UNIX_SECS - i64::MAX)
+        );
+        assert_eq!(tsi_to_unix_secs(u32::MAX, Tsi::Gps, i64::MIN), None);
+        assert!(matches!(
+            unix_secs_to_tsi(i64::MIN, Tsi::Gps, GPS_LEAP_SECONDS),
+            Err(VitaError::OutOfRange)
+        ));
+    }
+}
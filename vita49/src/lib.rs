@@ -1,7 +1,8 @@
 // SPDX-FileCopyrightText: 2025 The vita49-rs Authors
 //
 // SPDX-License-Identifier: MIT OR Apache-2.0
-#![doc = include_str!(concat!("../", std::env!("CARGO_PKG_README")))]
+#![doc = include_str!(concat!("../", core::env!("CARGO_PKG_README")))]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
 #![deny(unstable_features, unused_import_braces, unreachable_pub)]
 // TODO: remove after 0.1.0 release
@@ -9,6 +10,9 @@
 #![warn(rustdoc::unescaped_backticks)]
 #![forbid(unsafe_code)]
 
+#[macro_use]
+extern crate alloc;
+
 mod ack;
 mod ack_response;
 mod cancellation;
@@ -18,6 +22,8 @@ mod cif2;
 mod cif3;
 mod cif7;
 mod class_id;
+#[cfg(feature = "codec")]
+mod codec;
 mod command;
 mod command_payload;
 mod context;
@@ -26,18 +32,34 @@ mod control;
 mod control_ack_mode;
 mod device_id;
 mod ecef_ephemeris;
+mod enable_value;
 mod errors;
+mod extension_context;
+mod fixed_point;
 mod formatted_gps;
+#[cfg(feature = "frame-crc")]
+mod frame_crc;
 mod gain;
 mod gps_ascii;
+mod gps_time;
 mod packet_header;
 mod payload;
+mod payload_format;
 mod query_ack;
+mod sequencer;
 mod signal_data;
+mod spectral_reassembly;
 mod spectrum;
+mod state_event_indicators;
+mod stream_id;
 mod threshold;
+mod timestamp_details;
+#[cfg(feature = "tokio")]
+mod tokio_io;
 mod trailer;
+mod v490;
 mod vrt;
+mod vrt_view;
 
 // Public exports
 pub use crate::ack::{Ack, AckLevel};
@@ -49,26 +71,40 @@ pub use crate::cif2::*;
 pub use crate::cif3::*;
 pub use crate::cif7::Cif7;
 pub use crate::class_id::ClassIdentifier;
-pub use crate::command::Command;
+#[cfg(feature = "codec")]
+pub use crate::codec::VrtCodec;
+pub use crate::command::{Command, Identity};
 pub use crate::command_payload::CommandPayload;
-pub use crate::context::Context;
+pub use crate::context::{Context, FieldDiff};
 pub use crate::context_association_lists::ContextAssociationLists;
 pub use crate::control::Control;
 pub use crate::control_ack_mode::*;
 pub use crate::device_id::DeviceId;
 pub use crate::ecef_ephemeris::EcefEphemeris;
+pub use crate::enable_value::EnableValue;
 pub use crate::errors::VitaError;
+pub use crate::extension_context::ExtensionContext;
 pub use crate::formatted_gps::FormattedGps;
 pub use crate::gain::Gain;
 pub use crate::gps_ascii::GpsAscii;
+pub use crate::gps_time::{gps_to_utc, utc_to_gps, GPS_EPOCH_UNIX_SECS, GPS_LEAP_SECONDS};
 pub use crate::packet_header::*;
 pub use crate::payload::Payload;
+pub use crate::payload_format::{DataItemFormat, PackingMethod, PayloadFormat, RealComplexType};
 pub use crate::query_ack::QueryAck;
+pub use crate::sequencer::VrtSequencer;
 pub use crate::signal_data::SignalData;
+pub use crate::spectral_reassembly::SpectralReassembler;
 pub use crate::spectrum::*;
+pub use crate::state_event_indicators::StateAndEventIndicators;
+pub use crate::stream_id::StreamId;
 pub use crate::threshold::Threshold;
+pub use crate::timestamp_details::TimestampDetails;
+#[cfg(feature = "tokio")]
+pub use crate::tokio_io::{read_vrt, VrtStream};
 pub use crate::trailer::{SampleFrameIndicator, Trailer};
 pub use crate::vrt::Vrt;
+pub use crate::vrt_view::VrtView;
 
 /// Standard imports for the most commonly used structures and
 /// traits in the vita49 crate.
@@ -85,6 +121,7 @@ pub mod prelude {
     pub use crate::payload::Payload;
     pub use crate::signal_data::SignalData;
     pub use crate::vrt::Vrt;
+    pub use crate::vrt_view::VrtView;
     pub use deku::writer::Writer;
     pub use deku::{DekuContainerRead, DekuContainerWrite, DekuReader, DekuWriter};
 }
@@ -96,6 +133,6 @@ pub mod command_prelude {
     pub use crate::cif1::{Cif1AckFields, Cif1AckManipulators};
     pub use crate::{
         Ack, AckLevel, AckResponse, ActionMode, Cancellation, Command, CommandPayload, Control,
-        ControlAckMode, QueryAck,
+        ControlAckMode, Identity, QueryAck,
     };
 }
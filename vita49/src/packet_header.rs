@@ -6,6 +6,8 @@ Data structures and methods related to the packet header format
 (ANSI/VITA-49.2-2017 section 5.1.1).
 */
 
+use deku::ctx::Endian;
+use deku::no_std_io::{Read, Seek};
 use deku::prelude::*;
 
 use crate::VitaError;
@@ -17,7 +19,9 @@ use crate::VitaError;
 #[deku(endian = "endian", ctx = "endian: deku::ctx::Endian")]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PacketHeader {
+    #[deku(reader = "Self::read_hword_1(deku::reader, endian)")]
     hword_1: u16,
+    #[cfg_attr(feature = "serde", serde(rename = "len", alias = "packet_size"))]
     packet_size: u16,
 }
 
@@ -58,6 +62,43 @@ pub enum PacketType {
 }
 
 impl PacketType {
+    /// Returns every defined packet type, in order of their on-wire
+    /// codes.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// for packet_type in PacketType::all() {
+    ///     assert_eq!(PacketType::try_from(packet_type.code()), Ok(*packet_type));
+    /// }
+    /// ```
+    pub fn all() -> &'static [PacketType] {
+        &[
+            PacketType::SignalDataWithoutStreamId,
+            PacketType::SignalData,
+            PacketType::ExtensionDataWithoutStreamId,
+            PacketType::ExtensionData,
+            PacketType::Context,
+            PacketType::ExtensionContext,
+            PacketType::Command,
+            PacketType::ExtensionCommand,
+        ]
+    }
+
+    /// Returns the 4-bit packet type code sent in the header
+    /// (ANSI/VITA-49.2-2017 table 5.1.1-1). Codes 8 through 15 are
+    /// reserved.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// assert_eq!(PacketType::SignalData.code(), 1);
+    /// assert_eq!(PacketType::Context.code(), 4);
+    /// ```
+    pub fn code(&self) -> u8 {
+        *self as u8
+    }
+
     /// Returns true if the packet type has a signal data-style payload.
     pub fn has_signal_data_payload(&self) -> bool {
         !matches!(
@@ -332,6 +373,24 @@ impl TryFrom<u8> for Tsf {
 }
 
 impl PacketHeader {
+    /// Reads the first half-word, rejecting reserved packet types so
+    /// later fields can rely on [`PacketHeader::packet_type()`].
+    fn read_hword_1<R: Read + Seek>(
+        reader: &mut Reader<R>,
+        endian: Endian,
+    ) -> Result<u16, DekuError> {
+        let hword_1 = u16::from_reader_with_ctx(reader, endian)?;
+        let packet_type = (hword_1 >> 12) as u8;
+        PacketType::try_from(packet_type).map_err(|_| VitaError::InvalidPacketType(packet_type))?;
+        Ok(hword_1)
+    }
+    /// Builds a header from its raw 32-bit value.
+    pub(crate) fn from_u32(word: u32) -> PacketHeader {
+        PacketHeader {
+            hword_1: (word >> 16) as u16,
+            packet_size: word as u16,
+        }
+    }
     /// Gets the raw 32-bit value of the packet header.
     pub fn as_u32(&self) -> u32 {
         ((self.hword_1 as u32) << 16) | ((self.packet_size as u32) & 0xFFFF)
@@ -392,8 +451,8 @@ impl PacketHeader {
             _ => Err(VitaError::CommandOnly),
         }
     }
-    /// Returns Ok(true) if the packet is an Ack packet, Ok(false) if
-    /// it's some other kind of Command packet, and an error if it's
+    /// Returns Ok(true) if the packet is a cancellation packet, Ok(false)
+    /// if it's some other kind of Command packet, and an error if it's
     /// some other type of packet entirely.
     pub fn is_cancellation_packet(&self) -> Result<bool, VitaError> {
         match self.indicators() {
@@ -401,12 +460,59 @@ impl PacketHeader {
             _ => Err(VitaError::CommandOnly),
         }
     }
+    /// Gets the timestamp mode (TSM) indicator of a context packet,
+    /// which says whether the timestamp conveys the precise time of a
+    /// context change or only its general timing.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::ContextOnly`] if the packet isn't a context
+    /// packet.
+    pub fn timestamp_mode(&self) -> Result<TimestampMode, VitaError> {
+        match self.indicators() {
+            Indicators::Context(i) => Ok(i.timestamp_mode),
+            _ => Err(VitaError::ContextOnly),
+        }
+    }
+    /// Sets the timestamp mode (TSM) indicator of a context packet.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::ContextOnly`] if the packet isn't a context
+    /// packet.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_context_packet();
+    /// packet
+    ///     .header_mut()
+    ///     .set_timestamp_mode(TimestampMode::PreciseTiming)?;
+    /// assert_eq!(
+    ///     packet.header().timestamp_mode()?,
+    ///     TimestampMode::PreciseTiming
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_timestamp_mode(&mut self, mode: TimestampMode) -> Result<(), VitaError> {
+        let Indicators::Context(mut indicators) = self.indicators() else {
+            return Err(VitaError::ContextOnly);
+        };
+        indicators.timestamp_mode = mode;
+        self.set_indicators(Indicators::Context(indicators));
+        Ok(())
+    }
     /// Gets the TimeStamp-Integer (TSI) field.
     pub fn tsi(&self) -> Tsi {
         (((self.hword_1 >> 6) & 0b11) as u8).try_into().unwrap()
     }
 
     /// Sets the TimeStamp-Integer (TSI) field.
+    ///
+    /// This only changes the header bits. Users go through
+    /// [`Vrt::set_tsi()`](crate::Vrt::set_tsi) or
+    /// [`Vrt::set_integer_timestamp()`](crate::Vrt::set_integer_timestamp)
+    /// so the packet's timestamp field stays consistent with the mode.
     pub(crate) fn set_tsi(&mut self, tsi: Tsi) {
         self.hword_1 = (self.hword_1 & !(0b11 << 6)) | ((tsi as u16) << 6);
     }
@@ -417,20 +523,53 @@ impl PacketHeader {
     }
 
     /// Sets the TimeStamp-Fractional (TSF) field.
+    ///
+    /// This only changes the header bits. Users go through
+    /// [`Vrt::set_tsf()`](crate::Vrt::set_tsf) or
+    /// [`Vrt::set_fractional_timestamp()`](crate::Vrt::set_fractional_timestamp)
+    /// so the packet's timestamp field stays consistent with the mode.
     pub(crate) fn set_tsf(&mut self, tsf: Tsf) {
         self.hword_1 = (self.hword_1 & !(0b11 << 4)) | ((tsf as u16) << 4);
     }
 
     /// Gets the modulo-16 packet counter field.
+    ///
+    /// Receivers can compare this against the previous packet's count
+    /// on the same stream to detect dropped packets.
     pub fn packet_count(&self) -> u8 {
         (self.hword_1 & 0b1111) as u8
     }
     /// Sets the modulo-16 packet counter field.
+    ///
+    /// Note: the packet count is a 4-bit field. Values larger than 15
+    /// are masked to the lower 4 bits (i.e. they wrap modulo 16).
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.header_mut().set_packet_count(15);
+    /// assert_eq!(packet.header().packet_count(), 15);
+    /// packet.header_mut().set_packet_count(17);
+    /// assert_eq!(packet.header().packet_count(), 1);
+    /// ```
     pub fn set_packet_count(&mut self, count: u8) {
         let masked_count = (count & 0b1111) as u16;
         self.hword_1 = (self.hword_1 & (!0b1111)) | masked_count;
     }
     /// Increments the packet counter by one (wrapping at 16).
+    ///
+    /// Streaming senders should call this once per packet sent on
+    /// a given stream.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.header_mut().set_packet_count(15);
+    /// packet.header_mut().inc_packet_count();
+    /// assert_eq!(packet.header().packet_count(), 0);
+    /// ```
     pub fn inc_packet_count(&mut self) {
         self.set_packet_count((self.packet_count() + 1) % 16);
     }
@@ -443,6 +582,10 @@ impl PacketHeader {
     pub fn set_packet_size(&mut self, n_words: u16) {
         self.packet_size = n_words;
     }
+    /// Gets the packet size field in bytes.
+    pub fn packet_size_bytes(&self) -> usize {
+        self.packet_size as usize * 4
+    }
 
     /// Returns true if a stream ID is included, false if not.
     pub fn stream_id_included(&self) -> bool {
@@ -470,26 +613,102 @@ impl PacketHeader {
         }
     }
 
-    /// Returns the payload size in 32-bit words.
+    /// Returns the payload size in 32-bit words. If the packet size is
+    /// too small to hold the fields the header says are present, 0 is
+    /// returned.
     pub fn payload_size_words(&self) -> usize {
-        // Start with packet size minus 32 bits for the packet header
-        let mut ret = self.packet_size as usize - 1;
+        let mut prologue = self.prologue_size_words();
+        if self.trailer_included() {
+            prologue += 1;
+        }
+        (self.packet_size as usize).saturating_sub(prologue)
+    }
+
+    /// Returns the size of the header, stream ID, class ID and
+    /// timestamps in 32-bit words.
+    pub(crate) fn prologue_size_words(&self) -> usize {
+        // Start with 32 bits for the packet header
+        let mut prologue = 1;
         if self.stream_id_included() {
-            ret -= 1;
+            prologue += 1;
         }
         if self.class_id_included() {
-            ret -= 2;
+            prologue += 2;
         }
         if self.integer_timestamp_included() {
-            ret -= 1;
+            prologue += 1;
         }
         if self.fractional_timestamp_included() {
-            ret -= 2;
+            prologue += 2;
         }
-        if self.trailer_included() {
-            ret -= 1;
+        prologue
+    }
+
+    /// Decodes the header of the packet at the start of `bytes` without
+    /// parsing the rest of the packet, e.g. to route packets by type.
+    ///
+    /// Only the prologue (header, stream ID, class ID and timestamps)
+    /// needs to be present; the payload is neither read nor copied. To
+    /// also read the other prologue fields of a complete packet without
+    /// allocating, see [`VrtView`](crate::VrtView).
+    ///
+    /// # Errors
+    /// Returns [`VitaError::BufferTooShort`] if `bytes` doesn't hold the
+    /// whole prologue, [`VitaError::InvalidPacketType`] if the header has
+    /// a reserved packet type, [`VitaError::InvalidPacketSize`] if the
+    /// packet size is too small for the prologue, or
+    /// [`VitaError::TrailerSizeConflict`] if the trailer-included bit is
+    /// set but the packet size leaves no room for the trailer.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_signal_payload(vec![0; 1024])?;
+    /// let bytes = packet.to_bytes().unwrap();
+    /// // Only the header and stream ID are needed.
+    /// let header = PacketHeader::peek(&bytes[..8])?;
+    /// assert_eq!(header.packet_type(), PacketType::SignalData);
+    /// assert_eq!(header.packet_size_bytes(), bytes.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn peek(bytes: &[u8]) -> Result<PacketHeader, VitaError> {
+        let header = PacketHeader::from_u32(crate::vrt_view::read_u32(bytes, 0)?);
+        let packet_type = (header.hword_1 >> 12) as u8;
+        PacketType::try_from(packet_type).map_err(|_| VitaError::InvalidPacketType(packet_type))?;
+        let prologue_len = header.prologue_size_words() * 4;
+        if bytes.len() < prologue_len {
+            return Err(VitaError::BufferTooShort {
+                needed: prologue_len,
+                available: bytes.len(),
+            });
         }
-        ret
+        if header.packet_size_bytes() < prologue_len {
+            return Err(VitaError::InvalidPacketSize);
+        }
+        if header.trailer_included() && header.packet_size_bytes() < prologue_len + 4 {
+            return Err(VitaError::TrailerSizeConflict);
+        }
+        Ok(header)
+    }
+
+    /// Returns the payload size in bytes.
+    pub fn payload_size_bytes(&self) -> usize {
+        self.payload_size_words() * 4
+    }
+
+    /// Sets the packet size to fit the prologue followed by `payload_len`
+    /// bytes of payload. Used to parse standalone payloads.
+    pub(crate) fn with_payload_len(
+        mut self,
+        payload_len: usize,
+    ) -> Result<PacketHeader, VitaError> {
+        let words = self.prologue_size_words() + (payload_len + 3) / 4;
+        self.packet_size =
+            u16::try_from(words).map_err(|_| VitaError::PayloadTooLarge { words })?;
+        Ok(self)
     }
 
     /// Creates a new signal data packet header with some sane defaults.
@@ -521,6 +740,13 @@ impl PacketHeader {
         ret
     }
 
+    /// Creates a new extension context packet header.
+    pub fn new_extension_context_header() -> PacketHeader {
+        let mut ret = PacketHeader::new_context_header();
+        ret.set_packet_type(PacketType::ExtensionContext);
+        ret
+    }
+
     /// Creates a new control packet header.
     pub fn new_control_header() -> PacketHeader {
         let mut ret = PacketHeader::default();
@@ -557,8 +783,6 @@ impl PacketHeader {
 
 #[cfg(test)]
 mod tests {
-    use crate::Indicators;
-
     #[test]
     fn packet_header() {
         use crate::prelude::*;
@@ -567,6 +791,65 @@ mod tests {
         assert_eq!(packet.header().as_u32() >> 28, 0b0110);
     }
 
+    #[test]
+    fn timestamp_mode() {
+        use crate::prelude::*;
+        let mut packet = Vrt::new_context_packet();
+        assert_eq!(
+            packet.header().timestamp_mode().unwrap(),
+            TimestampMode::GeneralTiming
+        );
+        for (mode, bit) in [
+            (TimestampMode::PreciseTiming, 0),
+            (TimestampMode::GeneralTiming, 1),
+        ] {
+            packet.header_mut().set_timestamp_mode(mode).unwrap();
+            let bytes = packet.to_bytes().unwrap();
+            assert_eq!(bytes[0] & 0x01, bit);
+            let parsed = Vrt::try_from(&bytes[..]).unwrap();
+            assert_eq!(parsed.header().timestamp_mode().unwrap(), mode);
+        }
+
+        let mut packet = Vrt::new_signal_data_packet();
+        assert!(matches!(
+            packet.header().timestamp_mode(),
+            Err(VitaError::ContextOnly)
+        ));
+        assert!(matches!(
+            packet
+                .header_mut()
+                .set_timestamp_mode(TimestampMode::PreciseTiming),
+            Err(VitaError::ContextOnly)
+        ));
+    }
+
+    #[test]
+    fn packet_count() {
+        use crate::prelude::*;
+        let mut packet = Vrt::new_signal_data_packet();
+        for count in 0..=15 {
+            packet.header_mut().set_packet_count(count);
+            assert_eq!(packet.header().packet_count(), count);
+            // The count lives in the low nibble of the second header byte
+            let bytes = packet.to_bytes().unwrap();
+            assert_eq!(bytes[1] & 0x0F, count);
+            assert_eq!(bytes[0], 0x10);
+        }
+
+        // Out-of-range values are masked to 4 bits
+        packet.header_mut().set_packet_count(0x1A);
+        assert_eq!(packet.header().packet_count(), 0xA);
+
+        // Incrementing wraps at 16 without touching other header bits
+        packet.header_mut().set_packet_count(14);
+        let before = packet.header().as_u32() & !0xF_0000;
+        packet.header_mut().inc_packet_count();
+        assert_eq!(packet.header().packet_count(), 15);
+        packet.header_mut().inc_packet_count();
+        assert_eq!(packet.header().packet_count(), 0);
+        assert_eq!(packet.header().as_u32() & !0xF_0000, before);
+    }
+
     #[test]
     fn set_class_id_sets_class_id_included_bit() {
         use crate::prelude::*;
@@ -756,4 +1039,234 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn peek_matches_full_parse() {
+        use crate::prelude::*;
+        let mut data = Vrt::new_signal_data_packet();
+        data.set_stream_id(Some(0x1234));
+        data.set_class_id(Some(ClassIdentifier::new(0x12_3456, 1, 2)));
+        data.set_integer_timestamp(Some(10), Tsi::Utc).unwrap();
+        data.set_fractional_timestamp(Some(20), Tsf::SampleCount)
+            .unwrap();
+        data.set_signal_payload(vec![0xAB; 64]).unwrap();
+        let mut context = Vrt::new_context_packet();
+        context
+            .payload_mut()
+            .context_mut()
+            .unwrap()
+            .set_bandwidth_hz(Some(8e6))
+            .unwrap();
+        context.update_packet_size();
+
+        for packet in [data, context, Vrt::new_control_packet()] {
+            let bytes = packet.to_bytes().unwrap();
+            let parsed = Vrt::try_from(&bytes[..]).unwrap();
+            assert_eq!(&PacketHeader::peek(&bytes).unwrap(), parsed.header());
+            let prologue_len = parsed.header().prologue_size_words() * 4;
+            assert_eq!(
+                &PacketHeader::peek(&bytes[..prologue_len]).unwrap(),
+                parsed.header()
+            );
+            assert!(matches!(
+                PacketHeader::peek(&bytes[..prologue_len - 1]),
+                Err(VitaError::BufferTooShort { .. })
+            ));
+        }
+
+        assert!(matches!(
+            PacketHeader::peek(&[0x80, 0, 0, 1]),
+            Err(VitaError::InvalidPacketType(0x8))
+        ));
+        // Stream ID included, but the size only covers the header.
+        assert!(matches!(
+            PacketHeader::peek(&[0x10, 0, 0, 1, 0, 0, 0, 0]),
+            Err(VitaError::InvalidPacketSize)
+        ));
+    }
+
+    #[test]
+    fn packet_type_codes() {
+        use crate::prelude::*;
+        let expected = [
+            (PacketType::SignalDataWithoutStreamId, 0b0000),
+            (PacketType::SignalData, 0b0001),
+            (PacketType::ExtensionDataWithoutStreamId, 0b0010),
+            (PacketType::ExtensionData, 0b0011),
+            (PacketType::Context, 0b0100),
+            (PacketType::ExtensionContext, 0b0101),
+            (PacketType::Command, 0b0110),
+            (PacketType::ExtensionCommand, 0b0111),
+        ];
+        assert_eq!(PacketType::all().len(), expected.len());
+        for (packet_type, (expected_type, code)) in PacketType::all().iter().zip(expected) {
+            assert_eq!(*packet_type, expected_type);
+            assert_eq!(packet_type.code(), code);
+            assert_eq!(PacketType::try_from(code), Ok(expected_type));
+            let mut header = PacketHeader::default();
+            header.set_packet_type(expected_type);
+            assert_eq!(header.as_u32() >> 28, u32::from(code));
+        }
+        for code in 8..=15 {
+            assert!(PacketType::try_from(code).is_err());
+        }
+    }
+}
+
+/// Property tests checking that every combination of header fields
+/// survives a serialize/parse round trip.
+#[cfg(test)]
+mod header_roundtrip {
+    use crate::prelude::*;
+    use crate::Trailer;
+    use proptest::prelude::*;
+
+    fn packet_type() -> impl Strategy<Value = PacketType> {
+        prop::sample::select(PacketType::all())
+    }
+
+    fn tsi() -> impl Strategy<Value = Tsi> {
+        prop::sample::select(vec![Tsi::Null, Tsi::Utc, Tsi::Gps, Tsi::Other])
+    }
+
+    fn tsf() -> impl Strategy<Value = Tsf> {
+        prop::sample::select(vec![
+            Tsf::Null,
+            Tsf::SampleCount,
+            Tsf::RealTimePs,
+            Tsf::FreeRunningCount,
+        ])
+    }
+
+    /// Builds the indicators for `packet_type` from three raw bits,
+    /// dropping the bits that are reserved for that packet type.
+    fn indicators_for(packet_type: PacketType, bits: [bool; 3]) -> Indicators {
+        match packet_type {
+            PacketType::Context | PacketType::ExtensionContext => {
+                Indicators::Context(ContextIndicators {
+                    not_a_vita490_packet: bits[1],
+                    timestamp_mode: bits[2].try_into().unwrap(),
+                })
+            }
+            PacketType::Command | PacketType::ExtensionCommand => {
+                Indicators::Command(CommandIndicators {
+                    ack_packet: bits[0],
+                    cancellation_packet: bits[2],
+                })
+            }
+            _ => Indicators::SignalData(SignalDataIndicators {
+                trailer_included: bits[0],
+                not_a_vita490_packet: bits[1],
+                signal_spectral_data: bits[2],
+            }),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn header_fields_round_trip(
+            packet_type in packet_type(),
+            class_id_included: bool,
+            bits: [bool; 3],
+            tsi in tsi(),
+            tsf in tsf(),
+            count in 0u8..16,
+            payload_words in 0usize..64,
+        ) {
+            let indicators = indicators_for(packet_type, bits);
+            // Start from a packet whose payload has the layout the
+            // indicators select.
+            let mut packet = match indicators {
+                Indicators::Command(i) if i.ack_packet => Vrt::new_validation_ack_packet(),
+                Indicators::Command(i) if i.cancellation_packet => Vrt::new_cancellation_packet(),
+                Indicators::Command(_) => Vrt::new_control_packet(),
+                Indicators::Context(_) if packet_type == PacketType::ExtensionContext => {
+                    Vrt::new_extension_context_packet()
+                }
+                Indicators::Context(_) => Vrt::new_context_packet(),
+                Indicators::SignalData(_) => {
+                    let mut packet = Vrt::new_signal_data_packet();
+                    packet.set_signal_payload(vec![0xA5; payload_words * 4]).unwrap();
+                    packet
+                }
+            };
+            packet.header_mut().set_packet_type(packet_type);
+            packet.set_stream_id(packet.header().stream_id_included().then_some(0x1234));
+            packet.header_mut().set_indicators(indicators);
+            if let Indicators::SignalData(i) = indicators {
+                packet.set_trailer(i.trailer_included.then(Trailer::default)).unwrap();
+            }
+            packet.set_class_id(class_id_included.then(|| ClassIdentifier::new(0x12_3456, 1, 2)));
+            packet
+                .set_integer_timestamp((tsi != Tsi::Null).then_some(7), tsi)
+                .unwrap();
+            packet
+                .set_fractional_timestamp((tsf != Tsf::Null).then_some(9), tsf)
+                .unwrap();
+            packet.header_mut().set_packet_count(count);
+            packet.update_packet_size();
+
+            let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+            prop_assert_eq!(&parsed, &packet);
+            let header = parsed.header();
+            prop_assert_eq!(header.packet_type(), packet_type);
+            prop_assert_eq!(header.class_id_included(), class_id_included);
+            prop_assert_eq!(header.indicators(), indicators);
+            prop_assert_eq!(header.tsi(), tsi);
+            prop_assert_eq!(header.tsf(), tsf);
+            prop_assert_eq!(header.packet_count(), count);
+            if matches!(indicators, Indicators::SignalData(_)) {
+                prop_assert_eq!(header.payload_size_words(), payload_words);
+            }
+        }
+
+        #[test]
+        fn packet_prologue_round_trip(
+            packet_type in prop::sample::select(vec![
+                PacketType::SignalData,
+                PacketType::SignalDataWithoutStreamId,
+                PacketType::Context,
+                PacketType::Command,
+            ]),
+            class_id in prop::option::of((0u32..1 << 24, any::<u16>(), any::<u16>())),
+            tsi in tsi(),
+            integer_timestamp: u32,
+            tsf in tsf(),
+            fractional_timestamp in 0u64..1_000_000_000_000,
+            count in 0u8..16,
+            bits: [bool; 3],
+        ) {
+            let mut packet = match packet_type {
+                PacketType::SignalData => Vrt::new_signal_data_packet(),
+                PacketType::SignalDataWithoutStreamId => Vrt::new_signal_data_packet_no_stream_id(),
+                PacketType::Context => Vrt::new_context_packet(),
+                _ => Vrt::new_control_packet(),
+            };
+            // Bits that change the packet's layout (trailer, ACK and
+            // cancellation) are left alone since the payload would have
+            // to change along with them.
+            let mut indicators = indicators_for(packet_type, [false, bits[1], bits[2]]);
+            if let Indicators::Command(i) = &mut indicators {
+                i.cancellation_packet = false;
+            }
+            packet.header_mut().set_indicators(indicators);
+            packet.header_mut().set_packet_count(count);
+            packet.set_class_id(class_id.map(|(oui, icc, pcc)| ClassIdentifier::new(oui, icc, pcc)));
+            packet
+                .set_integer_timestamp((tsi != Tsi::Null).then_some(integer_timestamp), tsi)
+                .unwrap();
+            packet
+                .set_fractional_timestamp((tsf != Tsf::Null).then_some(fractional_timestamp), tsf)
+                .unwrap();
+            packet.update_packet_size();
+
+            let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+            prop_assert_eq!(parsed.header(), packet.header());
+            prop_assert_eq!(parsed.header().class_id_included(), class_id.is_some());
+            prop_assert_eq!(parsed.header().indicators(), indicators);
+            prop_assert_eq!(parsed.class_id(), packet.class_id());
+            prop_assert_eq!(parsed.integer_timestamp(), packet.integer_timestamp());
+            prop_assert_eq!(parsed.fractional_timestamp(), packet.fractional_timestamp());
+        }
+    }
 }
@@ -6,7 +6,9 @@ use deku::prelude::*;
 use crate::packet_header::{PacketHeader, PacketType};
 use crate::signal_data::SignalData;
 use crate::Command;
+use crate::CommandPayload;
 use crate::Context;
+use crate::ExtensionContext;
 use crate::VitaError;
 
 /// Generic payload enumeration. The payload format will differ depending on the
@@ -21,7 +23,7 @@ use crate::VitaError;
 /// let mut packet = Vrt::new_context_packet();
 /// // Safe to unwrap as you just made it a context packet above.
 /// let context = packet.payload_mut().context_mut().unwrap();
-/// context.set_bandwidth_hz(Some(8e6));
+/// context.set_bandwidth_hz(Some(8e6)).unwrap();
 /// ```
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, DekuRead, DekuWrite)]
 #[deku(
@@ -33,8 +35,11 @@ use crate::VitaError;
 #[allow(clippy::large_enum_variant)]
 pub enum Payload {
     /// Payload for a context packet.
-    #[deku(id = "PacketType::Context | PacketType::ExtensionContext")]
+    #[deku(id = "PacketType::Context")]
     Context(Context),
+    /// Payload for an extension context packet.
+    #[deku(id = "PacketType::ExtensionContext")]
+    ExtensionContext(#[deku(ctx = "packet_header")] ExtensionContext),
     /// Payload for a command packet.
     #[deku(id = "PacketType::Command | PacketType::ExtensionCommand")]
     Command(#[deku(ctx = "packet_header")] Command),
@@ -98,7 +103,7 @@ impl Payload {
     /// use vita49::prelude::*;
     /// let mut packet = Vrt::new_signal_data_packet();
     /// let signal_data_mut = packet.payload_mut().signal_data_mut().unwrap();
-    /// signal_data_mut.set_payload(&[1, 2, 3, 4]);
+    /// signal_data_mut.set_payload(&[1, 2, 3, 4]).unwrap();
     /// assert_eq!(signal_data_mut.payload_size_bytes(), 4);
     /// ```
     pub fn signal_data_mut(&mut self) -> Result<&mut SignalData, VitaError> {
@@ -140,7 +145,7 @@ impl Payload {
     /// use vita49::prelude::*;
     /// let mut packet = Vrt::new_context_packet();
     /// let context_mut = packet.payload_mut().context_mut().unwrap();
-    /// context_mut.set_bandwidth_hz(Some(8e6));
+    /// context_mut.set_bandwidth_hz(Some(8e6)).unwrap();
     /// assert_eq!(context_mut.bandwidth_hz(), Some(8e6));
     /// ```
     pub fn context_mut(&mut self) -> Result<&mut Context, VitaError> {
@@ -150,6 +155,38 @@ impl Payload {
         }
     }
 
+    /// Gets a reference to the extension context payload.
+    ///
+    /// # Errors
+    /// This function will return an error if run on a packet other
+    /// than an extension context packet.
+    pub fn extension_context(&self) -> Result<&ExtensionContext, VitaError> {
+        match self {
+            Payload::ExtensionContext(p) => Ok(p),
+            _ => Err(VitaError::ExtensionContextOnly),
+        }
+    }
+    /// Gets a mutable reference to the extension context payload.
+    ///
+    /// # Errors
+    /// This function will return an error if run on a packet other
+    /// than an extension context packet.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut packet = Vrt::new_extension_context_packet();
+    /// let ext = packet.payload_mut().extension_context_mut().unwrap();
+    /// ext.set_payload([1, 2, 3, 4]).unwrap();
+    /// assert_eq!(ext.payload(), &[1, 2, 3, 4]);
+    /// ```
+    pub fn extension_context_mut(&mut self) -> Result<&mut ExtensionContext, VitaError> {
+        match self {
+            Payload::ExtensionContext(p) => Ok(p),
+            _ => Err(VitaError::ExtensionContextOnly),
+        }
+    }
+
     /// Gets a reference to the command payload. This "unwraps"
     /// the generic `Payload` into a `Command` payload.
     ///
@@ -186,12 +223,170 @@ impl Payload {
         }
     }
 
+    /// Gets a reference to the signal data payload, or `None` if this
+    /// is another kind of payload. See [`signal_data()`](Payload::signal_data())
+    /// for a version returning an error instead.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let packet = Vrt::new_signal_data_packet();
+    /// if let Some(signal_data) = packet.payload().as_signal_data() {
+    ///     assert_eq!(signal_data.payload_size_bytes(), 0);
+    /// }
+    /// assert!(packet.payload().as_context().is_none());
+    /// ```
+    pub fn as_signal_data(&self) -> Option<&SignalData> {
+        match self {
+            Payload::SignalData(p) => Some(p),
+            _ => None,
+        }
+    }
+
+    /// Gets a reference to the context payload, or `None` if this is
+    /// another kind of payload. See [`context()`](Payload::context())
+    /// for a version returning an error instead.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let packet = Vrt::new_context_packet();
+    /// if let Some(context) = packet.payload().as_context() {
+    ///     assert_eq!(context.bandwidth_hz(), None);
+    /// }
+    /// assert!(packet.payload().as_command().is_none());
+    /// ```
+    pub fn as_context(&self) -> Option<&Context> {
+        match self {
+            Payload::Context(p) => Some(p),
+            _ => None,
+        }
+    }
+
+    /// Gets a reference to the extension context payload, or `None` if
+    /// this is another kind of payload. See
+    /// [`extension_context()`](Payload::extension_context()) for a
+    /// version returning an error instead.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let packet = Vrt::new_extension_context_packet();
+    /// if let Some(ext) = packet.payload().as_extension_context() {
+    ///     assert!(ext.payload().is_empty());
+    /// }
+    /// assert!(packet.payload().as_context().is_none());
+    /// ```
+    pub fn as_extension_context(&self) -> Option<&ExtensionContext> {
+        match self {
+            Payload::ExtensionContext(p) => Some(p),
+            _ => None,
+        }
+    }
+
+    /// Gets a reference to the command payload, or `None` if this is
+    /// another kind of payload. See [`command()`](Payload::command())
+    /// for a version returning an error instead.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let packet = Vrt::new_control_packet();
+    /// if let Some(command) = packet.payload().as_command() {
+    ///     assert_eq!(command.message_id(), 0);
+    /// }
+    /// assert!(packet.payload().as_signal_data().is_none());
+    /// ```
+    pub fn as_command(&self) -> Option<&Command> {
+        match self {
+            Payload::Command(p) => Some(p),
+            _ => None,
+        }
+    }
+
     /// Gets the payload size in 32-bit words.
-    pub fn size_words(&self) -> u16 {
+    pub fn size_words(&self) -> usize {
         match self {
             Payload::SignalData(p) => p.size_words(),
             Payload::Context(p) => p.size_words(),
+            Payload::ExtensionContext(p) => p.size_words(),
             Payload::Command(p) => p.size_words(),
         }
     }
+
+    /// Makes the CIF indicator bits of context, control and query ACK
+    /// payloads match the data fields that are present, leaving the
+    /// indicator-only control packets of Action Mode 0 alone.
+    pub(crate) fn sync_indicators(&mut self) {
+        match self {
+            Payload::Context(p) => p.sync_indicators(),
+            Payload::Command(p) => {
+                let cam = p.cam();
+                match p.payload_mut() {
+                    CommandPayload::Control(c) if cam.will_include_cif_fields() => {
+                        c.sync_indicators()
+                    }
+                    CommandPayload::QueryAck(q) => q.sync_indicators(),
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Gets the payload size in bytes, including the padding of a final
+    /// partial word of signal data. This matches the payload size in the
+    /// packet header.
+    pub fn payload_size_bytes(&self) -> usize {
+        self.size_words() * 4
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn as_signal_data() {
+        let mut packet = Vrt::new_signal_data_packet();
+        packet.set_signal_payload(vec![1, 2, 3, 4]).unwrap();
+        let payload = packet.payload();
+        assert_eq!(payload.as_signal_data().unwrap().payload(), &[1, 2, 3, 4]);
+        assert!(payload.as_context().is_none());
+        assert!(payload.as_command().is_none());
+    }
+
+    #[test]
+    fn as_context() {
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_bandwidth_hz(Some(8e6)).unwrap();
+        let payload = packet.payload();
+        assert_eq!(payload.as_context().unwrap().bandwidth_hz(), Some(8e6));
+        assert!(payload.as_signal_data().is_none());
+        assert!(payload.as_command().is_none());
+        assert!(Vrt::new_extension_context_packet()
+            .payload()
+            .as_context()
+            .is_none());
+    }
+
+    #[test]
+    fn as_command() {
+        let mut packet = Vrt::new_control_packet();
+        let command = packet.payload_mut().command_mut().unwrap();
+        command.set_message_id(42);
+        let payload = packet.payload();
+        assert_eq!(payload.as_command().unwrap().message_id(), 42);
+        assert!(payload.as_signal_data().is_none());
+        assert!(payload.as_context().is_none());
+        for packet in [
+            Vrt::new_cancellation_packet(),
+            Vrt::new_validation_ack_packet(),
+            Vrt::new_exec_ack_packet(),
+            Vrt::new_query_ack_packet(),
+        ] {
+            assert!(packet.payload().as_command().is_some());
+        }
+    }
 }
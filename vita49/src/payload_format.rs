@@ -0,0 +1,459 @@
+// SPDX-FileCopyrightText: 2025 The vita49-rs Authors
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Data structures and methods related to the data packet payload
+format field (ANSI/VITA-49.2-2017 section 9.13.3).
+
+This field describes how samples are packed into the payload of
+the signal data packets associated with a context stream.
+*/
+
+use core::fmt;
+
+use deku::prelude::*;
+
+use crate::VitaError;
+
+/// Method used to pack data items into the payload.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PackingMethod {
+    /// Item packing fields never span a 32-bit word boundary.
+    ProcessingEfficient = 0,
+    /// Item packing fields are packed back-to-back across word boundaries.
+    LinkEfficient = 1,
+}
+
+/// Real or complex sample type.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RealComplexType {
+    /// Real samples.
+    Real = 0,
+    /// Complex samples in Cartesian (I, Q) form.
+    ComplexCartesian = 1,
+    /// Complex samples in polar (magnitude, phase) form.
+    ComplexPolar = 2,
+    /// Reserved for future expansion.
+    Reserved,
+}
+
+impl From<u8> for RealComplexType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => RealComplexType::Real,
+            1 => RealComplexType::ComplexCartesian,
+            2 => RealComplexType::ComplexPolar,
+            _ => RealComplexType::Reserved,
+        }
+    }
+}
+
+impl From<RealComplexType> for u8 {
+    fn from(value: RealComplexType) -> Self {
+        match value {
+            RealComplexType::Real => 0,
+            RealComplexType::ComplexCartesian => 1,
+            RealComplexType::ComplexPolar => 2,
+            RealComplexType::Reserved => panic!("can't convert reserved variant"),
+        }
+    }
+}
+
+/// Numeric format of each data item.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataItemFormat {
+    /// Signed fixed-point.
+    SignedFixedPoint = 0x00,
+    /// Signed VRT with a 1-bit exponent.
+    SignedVrt1BitExponent = 0x01,
+    /// Signed VRT with a 2-bit exponent.
+    SignedVrt2BitExponent = 0x02,
+    /// Signed VRT with a 3-bit exponent.
+    SignedVrt3BitExponent = 0x03,
+    /// Signed VRT with a 4-bit exponent.
+    SignedVrt4BitExponent = 0x04,
+    /// Signed VRT with a 5-bit exponent.
+    SignedVrt5BitExponent = 0x05,
+    /// Signed VRT with a 6-bit exponent.
+    SignedVrt6BitExponent = 0x06,
+    /// Signed fixed-point, non-normalized.
+    SignedFixedPointNonNormalized = 0x07,
+    /// IEEE-754 half-precision floating-point.
+    IeeeHalfPrecision = 0x0D,
+    /// IEEE-754 single-precision floating-point.
+    IeeeSinglePrecision = 0x0E,
+    /// IEEE-754 double-precision floating-point.
+    IeeeDoublePrecision = 0x0F,
+    /// Unsigned fixed-point.
+    UnsignedFixedPoint = 0x10,
+    /// Unsigned VRT with a 1-bit exponent.
+    UnsignedVrt1BitExponent = 0x11,
+    /// Unsigned VRT with a 2-bit exponent.
+    UnsignedVrt2BitExponent = 0x12,
+    /// Unsigned VRT with a 3-bit exponent.
+    UnsignedVrt3BitExponent = 0x13,
+    /// Unsigned VRT with a 4-bit exponent.
+    UnsignedVrt4BitExponent = 0x14,
+    /// Unsigned VRT with a 5-bit exponent.
+    UnsignedVrt5BitExponent = 0x15,
+    /// Unsigned VRT with a 6-bit exponent.
+    UnsignedVrt6BitExponent = 0x16,
+    /// Unsigned fixed-point, non-normalized.
+    UnsignedFixedPointNonNormalized = 0x17,
+    /// Reserved for future expansion.
+    Reserved,
+}
+
+impl From<u8> for DataItemFormat {
+    fn from(value: u8) -> Self {
+        match value {
+            0x00 => DataItemFormat::SignedFixedPoint,
+            0x01 => DataItemFormat::SignedVrt1BitExponent,
+            0x02 => DataItemFormat::SignedVrt2BitExponent,
+            0x03 => DataItemFormat::SignedVrt3BitExponent,
+            0x04 => DataItemFormat::SignedVrt4BitExponent,
+            0x05 => DataItemFormat::SignedVrt5BitExponent,
+            0x06 => DataItemFormat::SignedVrt6BitExponent,
+            0x07 => DataItemFormat::SignedFixedPointNonNormalized,
+            0x0D => DataItemFormat::IeeeHalfPrecision,
+            0x0E => DataItemFormat::IeeeSinglePrecision,
+            0x0F => DataItemFormat::IeeeDoublePrecision,
+            0x10 => DataItemFormat::UnsignedFixedPoint,
+            0x11 => DataItemFormat::UnsignedVrt1BitExponent,
+            0x12 => DataItemFormat::UnsignedVrt2BitExponent,
+            0x13 => DataItemFormat::UnsignedVrt3BitExponent,
+            0x14 => DataItemFormat::UnsignedVrt4BitExponent,
+            0x15 => DataItemFormat::UnsignedVrt5BitExponent,
+            0x16 => DataItemFormat::UnsignedVrt6BitExponent,
+            0x17 => DataItemFormat::UnsignedFixedPointNonNormalized,
+            _ => DataItemFormat::Reserved,
+        }
+    }
+}
+
+impl From<DataItemFormat> for u8 {
+    fn from(value: DataItemFormat) -> Self {
+        match value {
+            DataItemFormat::Reserved => panic!("can't convert reserved variant"),
+            v => v as u8,
+        }
+    }
+}
+
+/// Base data packet payload format data structure.
+#[derive(
+    Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, DekuRead, DekuWrite,
+)]
+#[deku(endian = "endian", ctx = "endian: deku::ctx::Endian")]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PayloadFormat(u64);
+
+impl PayloadFormat {
+    /// Builds the payload format from its raw 64-bit value.
+    pub fn from_u64(value: u64) -> PayloadFormat {
+        PayloadFormat(value)
+    }
+
+    /// Gets the raw 64-bit value of the payload format.
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+
+    /// Gets the size of the payload format field in 32-bit words.
+    pub fn size_words(&self) -> usize {
+        core::mem::size_of_val(self) / core::mem::size_of::<u32>()
+    }
+
+    fn bits(&self, shift: u32, width: u32) -> u64 {
+        (self.0 >> shift) & ((1 << width) - 1)
+    }
+
+    fn set_bits(&mut self, shift: u32, width: u32, value: u64) {
+        let mask = ((1 << width) - 1) << shift;
+        self.0 = (self.0 & !mask) | ((value << shift) & mask);
+    }
+
+    /// Gets the packing method.
+    pub fn packing_method(&self) -> PackingMethod {
+        if self.bits(63, 1) == 1 {
+            PackingMethod::LinkEfficient
+        } else {
+            PackingMethod::ProcessingEfficient
+        }
+    }
+
+    /// Sets the packing method.
+    pub fn set_packing_method(&mut self, method: PackingMethod) {
+        self.set_bits(63, 1, method as u64);
+    }
+
+    /// Gets the real/complex sample type.
+    pub fn real_complex_type(&self) -> RealComplexType {
+        RealComplexType::from(self.bits(61, 2) as u8)
+    }
+
+    /// Sets the real/complex sample type.
+    ///
+    /// # Errors
+    /// Returns an error if [`RealComplexType::Reserved`] is passed.
+    pub fn set_real_complex_type(&mut self, t: RealComplexType) -> Result<(), VitaError> {
+        if t == RealComplexType::Reserved {
+            return Err(VitaError::ReservedField);
+        }
+        self.set_bits(61, 2, u8::from(t) as u64);
+        Ok(())
+    }
+
+    /// Gets the data item format.
+    pub fn data_item_format(&self) -> DataItemFormat {
+        DataItemFormat::from(self.bits(56, 5) as u8)
+    }
+
+    /// Sets the data item format.
+    ///
+    /// # Errors
+    /// Returns an error if [`DataItemFormat::Reserved`] is passed.
+    pub fn set_data_item_format(&mut self, format: DataItemFormat) -> Result<(), VitaError> {
+        if format == DataItemFormat::Reserved {
+            return Err(VitaError::ReservedField);
+        }
+        self.set_bits(56, 5, u8::from(format) as u64);
+        Ok(())
+    }
+
+    /// Returns true if the sample-component repeat indicator is set.
+    pub fn sample_component_repeat(&self) -> bool {
+        self.bits(55, 1) == 1
+    }
+
+    /// Sets the sample-component repeat indicator.
+    pub fn set_sample_component_repeat(&mut self, repeat: bool) {
+        self.set_bits(55, 1, repeat as u64);
+    }
+
+    /// Gets the event-tag size (in bits).
+    pub fn event_tag_size(&self) -> u8 {
+        self.bits(52, 3) as u8
+    }
+
+    /// Sets the event-tag size (in bits).
+    ///
+    /// # Errors
+    /// Returns an error if the size is greater than 7.
+    pub fn set_event_tag_size(&mut self, size: u8) -> Result<(), VitaError> {
+        if size > 7 {
+            return Err(VitaError::OutOfRange);
+        }
+        self.set_bits(52, 3, size as u64);
+        Ok(())
+    }
+
+    /// Gets the channel-tag size (in bits).
+    pub fn channel_tag_size(&self) -> u8 {
+        self.bits(48, 4) as u8
+    }
+
+    /// Sets the channel-tag size (in bits).
+    ///
+    /// # Errors
+    /// Returns an error if the size is greater than 15.
+    pub fn set_channel_tag_size(&mut self, size: u8) -> Result<(), VitaError> {
+        if size > 15 {
+            return Err(VitaError::OutOfRange);
+        }
+        self.set_bits(48, 4, size as u64);
+        Ok(())
+    }
+
+    /// Gets the data item fraction size (in bits).
+    pub fn data_item_fraction_size(&self) -> u8 {
+        self.bits(44, 4) as u8
+    }
+
+    /// Sets the data item fraction size (in bits).
+    ///
+    /// # Errors
+    /// Returns an error if the size is greater than 15.
+    pub fn set_data_item_fraction_size(&mut self, size: u8) -> Result<(), VitaError> {
+        if size > 15 {
+            return Err(VitaError::OutOfRange);
+        }
+        self.set_bits(44, 4, size as u64);
+        Ok(())
+    }
+
+    /// Gets the item packing field size (in bits, 1-64).
+    pub fn item_packing_field_size(&self) -> u8 {
+        self.bits(38, 6) as u8 + 1
+    }
+
+    /// Sets the item packing field size (in bits).
+    ///
+    /// # Errors
+    /// Returns an error if the size is not between 1 and 64 inclusively.
+    pub fn set_item_packing_field_size(&mut self, size: u8) -> Result<(), VitaError> {
+        if !(1..=64).contains(&size) {
+            return Err(VitaError::OutOfRange);
+        }
+        self.set_bits(38, 6, (size - 1) as u64);
+        Ok(())
+    }
+
+    /// Gets the data item size (in bits, 1-64).
+    pub fn data_item_size(&self) -> u8 {
+        self.bits(32, 6) as u8 + 1
+    }
+
+    /// Sets the data item size (in bits).
+    ///
+    /// # Errors
+    /// Returns an error if the size is not between 1 and 64 inclusively.
+    pub fn set_data_item_size(&mut self, size: u8) -> Result<(), VitaError> {
+        if !(1..=64).contains(&size) {
+            return Err(VitaError::OutOfRange);
+        }
+        self.set_bits(32, 6, (size - 1) as u64);
+        Ok(())
+    }
+
+    /// Gets the repeat count (1-65536).
+    pub fn repeat_count(&self) -> u32 {
+        self.bits(16, 16) as u32 + 1
+    }
+
+    /// Sets the repeat count.
+    ///
+    /// # Errors
+    /// Returns an error if the count is not between 1 and 65536 inclusively.
+    pub fn set_repeat_count(&mut self, count: u32) -> Result<(), VitaError> {
+        if !(1..=65536).contains(&count) {
+            return Err(VitaError::OutOfRange);
+        }
+        self.set_bits(16, 16, (count - 1) as u64);
+        Ok(())
+    }
+
+    /// Gets the vector size (1-65536).
+    pub fn vector_size(&self) -> u32 {
+        self.bits(0, 16) as u32 + 1
+    }
+
+    /// Sets the vector size.
+    ///
+    /// # Errors
+    /// Returns an error if the size is not between 1 and 65536 inclusively.
+    pub fn set_vector_size(&mut self, size: u32) -> Result<(), VitaError> {
+        if !(1..=65536).contains(&size) {
+            return Err(VitaError::OutOfRange);
+        }
+        self.set_bits(0, 16, (size - 1) as u64);
+        Ok(())
+    }
+}
+
+impl fmt::Display for PayloadFormat {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(f, "Payload format:")?;
+        writeln!(f, "  Packing method: {:?}", self.packing_method())?;
+        writeln!(f, "  Real/complex type: {:?}", self.real_complex_type())?;
+        writeln!(f, "  Data item format: {:?}", self.data_item_format())?;
+        writeln!(
+            f,
+            "  Sample-component repeat: {}",
+            self.sample_component_repeat()
+        )?;
+        writeln!(f, "  Event-tag size: {}", self.event_tag_size())?;
+        writeln!(f, "  Channel-tag size: {}", self.channel_tag_size())?;
+        writeln!(
+            f,
+            "  Data item fraction size: {}",
+            self.data_item_fraction_size()
+        )?;
+        writeln!(
+            f,
+            "  Item packing field size: {}",
+            self.item_packing_field_size()
+        )?;
+        writeln!(f, "  Data item size: {}", self.data_item_size())?;
+        writeln!(f, "  Repeat count: {}", self.repeat_count())?;
+        writeln!(f, "  Vector size: {}", self.vector_size())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn signed_16_bit_complex() {
+        let mut format = PayloadFormat::default();
+        format.set_packing_method(PackingMethod::ProcessingEfficient);
+        format
+            .set_real_complex_type(RealComplexType::ComplexCartesian)
+            .unwrap();
+        format
+            .set_data_item_format(DataItemFormat::SignedFixedPoint)
+            .unwrap();
+        format.set_item_packing_field_size(16).unwrap();
+        format.set_data_item_size(16).unwrap();
+        format.set_repeat_count(1).unwrap();
+        format.set_vector_size(1).unwrap();
+        assert_eq!(format.as_u64(), 0x2000_03CF_0000_0000);
+
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_signal_data_payload_format(Some(format));
+        packet.update_packet_size();
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        let parsed = *parsed
+            .payload()
+            .context()
+            .unwrap()
+            .signal_data_payload_format()
+            .unwrap();
+        assert_eq!(parsed, format);
+        assert_eq!(parsed.packing_method(), PackingMethod::ProcessingEfficient);
+        assert_eq!(
+            parsed.real_complex_type(),
+            RealComplexType::ComplexCartesian
+        );
+        assert_eq!(parsed.data_item_format(), DataItemFormat::SignedFixedPoint);
+        assert!(!parsed.sample_component_repeat());
+        assert_eq!(parsed.event_tag_size(), 0);
+        assert_eq!(parsed.channel_tag_size(), 0);
+        assert_eq!(parsed.item_packing_field_size(), 16);
+        assert_eq!(parsed.data_item_size(), 16);
+        assert_eq!(parsed.repeat_count(), 1);
+        assert_eq!(parsed.vector_size(), 1);
+    }
+
+    #[test]
+    fn payload_format_range_checks() {
+        let mut format = PayloadFormat::default();
+        assert!(format.set_data_item_size(0).is_err());
+        assert!(format.set_data_item_size(65).is_err());
+        assert!(format.set_event_tag_size(8).is_err());
+        assert!(format.set_vector_size(65537).is_err());
+        assert!(format
+            .set_data_item_format(DataItemFormat::Reserved)
+            .is_err());
+        assert!(format
+            .set_real_complex_type(RealComplexType::Reserved)
+            .is_err());
+        format
+            .set_data_item_format(DataItemFormat::IeeeSinglePrecision)
+            .unwrap();
+        format.set_packing_method(PackingMethod::LinkEfficient);
+        assert_eq!(
+            format.data_item_format(),
+            DataItemFormat::IeeeSinglePrecision
+        );
+        assert_eq!(format.packing_method(), PackingMethod::LinkEfficient);
+        assert_eq!(format.as_u64(), 0x8E00_0000_0000_0000);
+    }
+}
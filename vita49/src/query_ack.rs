@@ -3,8 +3,8 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use crate::{cif7::Cif7Opts, prelude::*};
+use core::fmt;
 use deku::prelude::*;
-use std::fmt;
 
 /// Query ACK data structure used to report current state back to some controller. Functionally,
 /// this packet is very similar to [`Context`], but is produced on-demand, not in-line with
@@ -52,8 +52,10 @@ pub struct QueryAck {
 }
 
 impl QueryAck {
+    crate::cif0::sync_indicators_method!();
+
     /// Get the size of the query ACK (in 32-bit words).
-    pub fn size_words(&self) -> u16 {
+    pub fn size_words(&self) -> usize {
         // Start with 1 32-bit word for the CIF0 field
         let mut ret = 1 + self.cif0_fields.size_words();
         if let Some(f) = &self.cif1_fields {
@@ -70,6 +72,37 @@ impl QueryAck {
         }
         ret
     }
+
+    /// Copies the reported CIF fields into a [`Context`] payload, e.g.
+    /// to cache the controllee's state alongside received context.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut ack = vita49::QueryAck::default();
+    /// ack.set_bandwidth_hz(Some(8e6)).unwrap();
+    /// let context = ack.to_context();
+    /// assert_eq!(context.bandwidth_hz(), Some(8e6));
+    /// ```
+    pub fn to_context(&self) -> Context {
+        let mut context = Context::new();
+        *Cif0Manipulators::cif0_mut(&mut context) = self.cif0;
+        *Cif0Manipulators::cif0_fields_mut(&mut context) = self.cif0_fields.clone();
+        *context.cif1_mut() = self.cif1;
+        *context.cif1_fields_mut() = self.cif1_fields.clone();
+        *context.cif2_mut() = self.cif2;
+        *context.cif2_fields_mut() = self.cif2_fields.clone();
+        *context.cif3_mut() = self.cif3;
+        *context.cif3_fields_mut() = self.cif3_fields.clone();
+        context.cif7 = self.cif7;
+        context
+    }
+}
+
+impl From<&QueryAck> for Context {
+    fn from(ack: &QueryAck) -> Context {
+        ack.to_context()
+    }
 }
 
 impl Cif0Manipulators for QueryAck {
@@ -151,7 +184,7 @@ impl Cif3Manipulators for QueryAck {
 }
 
 impl fmt::Display for QueryAck {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         writeln!(f, "Query ACK")?;
         if let Some(bw) = &self.bandwidth_hz() {
             writeln!(f, "Bandwidth: {bw} Hz")?;
@@ -163,7 +196,7 @@ impl fmt::Display for QueryAck {
             writeln!(f, "Sample rate: {samp_rate} sps")?;
         }
         if let Some(device_id) = &self.device_id() {
-            write!(f, "{device_id}")?;
+            writeln!(f, "Device ID: {device_id}")?;
         }
         if let Some(spectrum) = self.spectrum() {
             write!(f, "{spectrum}")?;
@@ -171,3 +204,33 @@ impl fmt::Display for QueryAck {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_context() {
+        let mut packet = Vrt::new_query_ack_packet();
+        let command = packet.payload_mut().command_mut().unwrap();
+        let ack = command.payload_mut().query_ack_mut().unwrap();
+        ack.set_bandwidth_hz(Some(8e6)).unwrap();
+        ack.set_rf_ref_freq_hz(Some(100e6)).unwrap();
+        ack.set_range_m(Some(1.5)).unwrap();
+        packet.update_packet_size();
+
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        let ack = parsed
+            .payload()
+            .command()
+            .unwrap()
+            .payload()
+            .query_ack()
+            .unwrap();
+        let context = Context::from(ack);
+        assert_eq!(context.bandwidth_hz(), Some(8e6));
+        assert_eq!(context.rf_ref_freq_hz(), Some(100e6));
+        assert_eq!(context.range_m(), Some(1.5));
+        assert_eq!(context.size_words(), ack.size_words());
+    }
+}
@@ -0,0 +1,216 @@
+// SPDX-FileCopyrightText: 2025 The vita49-rs Authors
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Generation of consecutive signal data packets for a transmitter.
+
+Each packet of a stream carries a 4-bit packet count that receivers use
+to detect loss (ANSI/VITA-49.2-2017 section 5.1.1.1), and usually a
+timestamp that advances by the duration of the previous packet's data.
+*/
+
+use crate::prelude::*;
+use crate::vrt::PS_PER_SEC;
+
+/// Produces a stream of signal data packets from a template packet,
+/// managing the packet count and (optionally) the timestamp.
+///
+/// Every packet is a copy of the template with a new payload. The first
+/// packet keeps the template's packet count and timestamp; each later
+/// packet advances the count by one (wrapping at 16) and the fractional
+/// timestamp by the configured increment.
+///
+/// # Example
+/// ```
+/// use vita49::{prelude::*, VrtSequencer};
+/// # fn main() -> Result<(), VitaError> {
+/// let mut template = Vrt::new_signal_data_packet();
+/// template.set_stream_id(Some(0x1234));
+/// template.set_fractional_timestamp(Some(0), Tsf::SampleCount)?;
+/// let mut sequencer = VrtSequencer::new(template);
+/// // Each packet carries 2 samples of 2 bytes.
+/// sequencer.set_timestamp_increment(2);
+///
+/// let first = sequencer.next(&[1, 2, 3, 4])?;
+/// let second = sequencer.next(&[5, 6, 7, 8])?;
+/// assert_eq!(first.header().packet_count(), 0);
+/// assert_eq!(second.header().packet_count(), 1);
+/// assert_eq!(second.fractional_timestamp(), Some(2));
+/// # Ok(())
+/// # }
+/// ```
+pub struct VrtSequencer {
+    template: Vrt,
+    timestamp_increment: u64,
+}
+
+impl VrtSequencer {
+    /// Creates a sequencer starting from the template's packet count
+    /// and timestamp. The timestamp doesn't advance until an increment
+    /// is set with [`VrtSequencer::set_timestamp_increment()`].
+    pub fn new(template: Vrt) -> VrtSequencer {
+        VrtSequencer {
+            template,
+            timestamp_increment: 0,
+        }
+    }
+
+    /// Gets the packet the next call to [`VrtSequencer::next()`] will
+    /// be based on.
+    pub fn template(&self) -> &Vrt {
+        &self.template
+    }
+
+    /// Sets how far the fractional timestamp advances per packet, in
+    /// the units of the template's TSF mode: samples for
+    /// [`Tsf::SampleCount`], picoseconds for [`Tsf::RealTimePs`]
+    /// (carrying into the integer timestamp each second), or counts for
+    /// [`Tsf::FreeRunningCount`]. Has no effect if the template has no
+    /// fractional timestamp.
+    pub fn set_timestamp_increment(&mut self, increment: u64) {
+        self.timestamp_increment = increment;
+    }
+
+    /// Builds the next packet in the stream carrying `payload`.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::SignalDataOnly`] if the template isn't a
+    /// signal data packet, [`VitaError::PayloadTooLarge`] if the
+    /// payload doesn't fit in a packet, [`VitaError::OutOfRange`] if the
+    /// fractional timestamp would overflow, or
+    /// [`VitaError::FractionalTimestampOutOfRange`] if a
+    /// [`Tsf::RealTimePs`] timestamp would pass one second with no
+    /// integer timestamp to carry into. The sequence doesn't advance
+    /// on error.
+    pub fn next(&mut self, payload: &[u8]) -> Result<Vrt, VitaError> {
+        let mut packet = self.template.clone();
+        packet.set_signal_payload(payload)?;
+        self.advance()?;
+        Ok(packet)
+    }
+
+    fn advance(&mut self) -> Result<(), VitaError> {
+        // Work out the next timestamps before touching the template so
+        // that a failure leaves the sequence where it was.
+        let tsi = self.template.header().tsi();
+        let tsf = self.template.header().tsf();
+        let mut integer = self.template.integer_timestamp();
+        let fractional = match self.template.fractional_timestamp() {
+            None => None,
+            Some(fractional) => {
+                let total = fractional
+                    .checked_add(self.timestamp_increment)
+                    .ok_or(VitaError::OutOfRange)?;
+                match tsf {
+                    Tsf::RealTimePs if total >= PS_PER_SEC => {
+                        let Some(secs) = integer else {
+                            return Err(VitaError::FractionalTimestampOutOfRange(total));
+                        };
+                        integer = Some(secs.wrapping_add((total / PS_PER_SEC) as u32));
+                        Some(total % PS_PER_SEC)
+                    }
+                    _ => Some(total),
+                }
+            }
+        };
+
+        if let Some(fractional) = fractional {
+            if integer != self.template.integer_timestamp() {
+                self.template.set_integer_timestamp(integer, tsi)?;
+            }
+            self.template
+                .set_fractional_timestamp(Some(fractional), tsf)?;
+        }
+        self.template.header_mut().inc_packet_count();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn packet_count_wraps() {
+        let mut template = Vrt::new_signal_data_packet();
+        template.header_mut().set_packet_count(14);
+        let mut sequencer = VrtSequencer::new(template);
+        let counts: Vec<u8> = (0..4)
+            .map(|_| sequencer.next(&[0; 4]).unwrap().header().packet_count())
+            .collect();
+        assert_eq!(counts, [14, 15, 0, 1]);
+    }
+
+    #[test]
+    fn sample_count_timestamp_advances() {
+        let mut template = Vrt::new_signal_data_packet();
+        template
+            .set_fractional_timestamp(Some(100), Tsf::SampleCount)
+            .unwrap();
+        let mut sequencer = VrtSequencer::new(template);
+        sequencer.set_timestamp_increment(256);
+        for expected in [100, 356, 612] {
+            let packet = sequencer.next(&[1, 2, 3, 4]).unwrap();
+            assert_eq!(packet.fractional_timestamp(), Some(expected));
+            assert_eq!(packet.signal_payload().unwrap(), &[1, 2, 3, 4]);
+            assert!(packet.validate_size().is_ok());
+        }
+    }
+
+    #[test]
+    fn real_time_timestamp_carries() {
+        let mut template = Vrt::new_signal_data_packet();
+        template.set_integer_timestamp(Some(10), Tsi::Utc).unwrap();
+        template
+            .set_fractional_timestamp(Some(PS_PER_SEC - 1_000), Tsf::RealTimePs)
+            .unwrap();
+        let mut sequencer = VrtSequencer::new(template);
+        sequencer.set_timestamp_increment(1_500);
+        sequencer.next(&[]).unwrap();
+        let packet = sequencer.next(&[]).unwrap();
+        assert_eq!(packet.integer_timestamp(), Some(11));
+        assert_eq!(packet.fractional_timestamp(), Some(500));
+    }
+
+    #[test]
+    fn error_does_not_advance() {
+        let mut sequencer = VrtSequencer::new(Vrt::new_context_packet());
+        assert!(sequencer.next(&[0; 4]).is_err());
+        assert_eq!(sequencer.template().header().packet_count(), 0);
+    }
+
+    #[test]
+    fn timestamp_errors_do_not_advance() {
+        // The fractional timestamp can't overflow u64.
+        let mut template = Vrt::new_signal_data_packet();
+        template
+            .set_fractional_timestamp(Some(u64::MAX - 1), Tsf::FreeRunningCount)
+            .unwrap();
+        let mut sequencer = VrtSequencer::new(template);
+        sequencer.set_timestamp_increment(2);
+        assert!(matches!(sequencer.next(&[]), Err(VitaError::OutOfRange)));
+        assert_eq!(sequencer.template().header().packet_count(), 0);
+        assert_eq!(
+            sequencer.template().fractional_timestamp(),
+            Some(u64::MAX - 1)
+        );
+
+        // A real-time carry needs an integer timestamp to go into.
+        let mut template = Vrt::new_signal_data_packet();
+        template
+            .set_fractional_timestamp(Some(PS_PER_SEC - 1), Tsf::RealTimePs)
+            .unwrap();
+        let mut sequencer = VrtSequencer::new(template);
+        sequencer.set_timestamp_increment(1);
+        assert!(matches!(
+            sequencer.next(&[]),
+            Err(VitaError::FractionalTimestampOutOfRange(PS_PER_SEC))
+        ));
+        assert_eq!(sequencer.template().header().packet_count(), 0);
+        assert_eq!(
+            sequencer.template().fractional_timestamp(),
+            Some(PS_PER_SEC - 1)
+        );
+    }
+}
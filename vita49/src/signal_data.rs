@@ -2,12 +2,31 @@
 //
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use alloc::vec::Vec;
+use deku::no_std_io::{Read, Seek, Write};
 use deku::prelude::*;
 use deku::writer::Writer;
-use std::io::{Seek, Write};
 
 use crate::packet_header::PacketHeader;
 use crate::payload::Payload;
+use crate::VitaError;
+
+/// Largest amount of payload read (and allocated) at a time.
+const READ_CHUNK_BYTES: usize = 4096;
+
+/// Largest signal data payload in 32-bit words: the packet size field
+/// also has to count the header word.
+const MAX_PAYLOAD_WORDS: usize = u16::MAX as usize - 1;
+
+/// Checks that a payload of `len` bytes fits in a packet.
+pub(crate) fn check_payload_len(len: usize) -> Result<(), VitaError> {
+    let words = (len + 3) / 4;
+    if words > MAX_PAYLOAD_WORDS {
+        // Report the size of the smallest packet that could carry it.
+        return Err(VitaError::PayloadTooLarge { words: words + 1 });
+    }
+    Ok(())
+}
 
 /// Base signal data structure.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, DekuRead, DekuWrite)]
@@ -35,6 +54,44 @@ impl TryFrom<Payload> for SignalData {
     }
 }
 
+/// Parses a standalone signal data payload: all of `bytes` is taken as
+/// sample data, as if it came from a signal data packet without a
+/// trailer whose size matches `bytes`.
+///
+/// # Errors
+/// Returns [`VitaError::InvalidValue`] if `bytes` isn't a whole number of
+/// 32-bit words.
+///
+/// # Example
+/// ```
+/// use vita49::prelude::*;
+/// # fn main() -> Result<(), VitaError> {
+/// let data = SignalData::try_from(&[1, 2, 3, 4, 5, 6, 7, 8][..])?;
+/// assert_eq!(data.payload(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+/// assert!(matches!(
+///     SignalData::try_from(&[1, 2, 3][..]),
+///     Err(VitaError::PartialWord { len: 3 })
+/// ));
+/// # Ok(())
+/// # }
+/// ```
+impl TryFrom<&[u8]> for SignalData {
+    type Error = VitaError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() % 4 != 0 {
+            return Err(VitaError::PartialWord { len: bytes.len() });
+        }
+        let header = PacketHeader::new_signal_data_header().with_payload_len(bytes.len())?;
+        let mut cursor = deku::no_std_io::Cursor::new(bytes);
+        let mut reader = deku::reader::Reader::new(&mut cursor);
+        Ok(SignalData::from_reader_with_ctx(
+            &mut reader,
+            (deku::ctx::Endian::Big, &header),
+        )?)
+    }
+}
+
 impl SignalData {
     /// Create a new, empty signal data packet.
     pub fn new() -> SignalData {
@@ -119,6 +176,15 @@ impl SignalData {
     /// Set the packet payload to some raw bytes.
     /// Accepts either a `Vec<u8>` (zero-copy) or a `&[u8]` slice (allocates).
     ///
+    /// This doesn't update the packet's size field; prefer
+    /// [`Vrt::set_signal_payload()`](crate::Vrt::set_signal_payload),
+    /// which also accounts for the rest of the packet.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::PayloadTooLarge`] (leaving the payload
+    /// unchanged) if the payload alone wouldn't fit in a 65535-word
+    /// packet.
+    ///
     /// # Example
     /// ```
     /// # use std::io;
@@ -126,35 +192,79 @@ impl SignalData {
     /// # fn main() -> Result<(), VitaError> {
     /// let mut packet = Vrt::new_signal_data_packet();
     /// let sig_data = packet.payload_mut().signal_data_mut()?;
-    /// sig_data.set_payload(&[1, 2, 3, 4, 5, 6, 7, 8]);
+    /// sig_data.set_payload(&[1, 2, 3, 4, 5, 6, 7, 8])?;
     /// assert_eq!(packet.signal_payload()?, &[1, 2, 3, 4, 5, 6, 7, 8]);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn set_payload(&mut self, bytes: impl Into<Vec<u8>>) {
-        self.data = bytes.into()
+    pub fn set_payload(&mut self, bytes: impl Into<Vec<u8>>) -> Result<(), VitaError> {
+        let bytes = bytes.into();
+        check_payload_len(bytes.len())?;
+        self.data = bytes;
+        Ok(())
     }
 
-    /// Gets the size of the payload in 32-bit words.
-    pub fn size_words(&self) -> u16 {
-        (self.data.len() / 4) as u16
+    /// Set the packet payload from 32-bit sample words, e.g. straight
+    /// from an aligned DMA buffer. Each word is stored in network
+    /// (big-endian) order, so this is equivalent to passing the words'
+    /// big-endian bytes to [`set_payload()`](SignalData::set_payload)
+    /// without building the byte buffer first.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::PayloadTooLarge`] (leaving the payload
+    /// unchanged) if the payload alone wouldn't fit in a 65535-word
+    /// packet.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// let sig_data = packet.payload_mut().signal_data_mut()?;
+    /// sig_data.set_payload_u32(&[0x01020304, 0x05060708])?;
+    /// assert_eq!(packet.signal_payload()?, &[1, 2, 3, 4, 5, 6, 7, 8]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_payload_u32(&mut self, words: &[u32]) -> Result<(), VitaError> {
+        check_payload_len(words.len() * 4)?;
+        self.data.clear();
+        self.data
+            .extend(words.iter().flat_map(|word| word.to_be_bytes()));
+        Ok(())
     }
 
-    /// Gets the size of the payload in bytes.
+    /// Gets the size of the payload in 32-bit words, counting the
+    /// padding of a final partial word. This isn't limited to what the
+    /// packet size field can hold, so payloads built with
+    /// [`SignalData::from_owned()`] that are too large to send are
+    /// reported by [`Vrt::try_update_packet_size()`](crate::Vrt::try_update_packet_size()).
+    pub fn size_words(&self) -> usize {
+        (self.data.len() + 3) / 4
+    }
+
+    /// Gets the size of the payload in bytes, not counting the padding of
+    /// a final partial word (see [`SignalData::size_words()`]).
     pub fn payload_size_bytes(&self) -> usize {
         self.data.len()
     }
 
-    fn read_payload<R: std::io::Read + std::io::Seek>(
+    pub(crate) fn read_payload<R: Read + Seek>(
         reader: &mut deku::reader::Reader<R>,
         words: usize,
         endian: deku::ctx::Endian,
     ) -> Result<Vec<u8>, deku::DekuError> {
-        let byte_len = words * 4;
-
-        let mut data = vec![0u8; byte_len];
-
-        reader.read_bytes(byte_len, &mut data)?;
+        // Grow the buffer as data actually arrives so a header claiming
+        // a huge size can't force a large allocation up front.
+        let mut remaining = words * 4;
+        let mut data = Vec::new();
+        while remaining > 0 {
+            let start = data.len();
+            let len = remaining.min(READ_CHUNK_BYTES);
+            data.resize(start + len, 0);
+            reader.read_bytes(len, &mut data[start..])?;
+            remaining -= len;
+        }
 
         if endian == deku::ctx::Endian::Little {
             for chunk in data.chunks_exact_mut(4) {
@@ -165,19 +275,19 @@ impl SignalData {
         Ok(data)
     }
 
-    fn write_payload<W: Write + Seek>(
+    pub(crate) fn write_payload<W: Write + Seek>(
         writer: &mut Writer<W>,
         data: &[u8],
         endian: deku::ctx::Endian,
     ) -> Result<(), deku::DekuError> {
-        let mut final_data = std::borrow::Cow::Borrowed(data);
+        let mut final_data = alloc::borrow::Cow::Borrowed(data);
 
         if endian == deku::ctx::Endian::Little {
             let mut swapped = data.to_vec();
             for chunk in swapped.chunks_exact_mut(4) {
                 chunk.reverse();
             }
-            final_data = std::borrow::Cow::Owned(swapped);
+            final_data = alloc::borrow::Cow::Owned(swapped);
         }
 
         writer.write_bytes(final_data.as_ref())?;
@@ -193,3 +303,76 @@ impl SignalData {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn set_payload_u32_matches_bytes() {
+        let words: Vec<u32> = (0..1024u32).map(|i| i.wrapping_mul(0x9E37_79B9)).collect();
+        let bytes: Vec<u8> = words.iter().flat_map(|w| w.to_be_bytes()).collect();
+
+        let mut from_words = Vrt::new_signal_data_packet();
+        // Any previous payload is replaced.
+        from_words.set_signal_payload(vec![0xFF; 16]).unwrap();
+        from_words
+            .payload_mut()
+            .signal_data_mut()
+            .unwrap()
+            .set_payload_u32(&words)
+            .unwrap();
+        from_words.update_packet_size();
+        let mut from_bytes = Vrt::new_signal_data_packet();
+        from_bytes.set_signal_payload(&bytes[..]).unwrap();
+
+        assert_eq!(from_words, from_bytes);
+        assert_eq!(
+            from_words.to_bytes().unwrap(),
+            from_bytes.to_bytes().unwrap()
+        );
+        assert_eq!(
+            from_words.to_bytes_le().unwrap(),
+            from_bytes.to_bytes_le().unwrap()
+        );
+
+        let mut data = SignalData::new();
+        data.set_payload_u32(&[]).unwrap();
+        assert_eq!(data.size_words(), 0);
+    }
+
+    #[test]
+    fn payload_too_large() {
+        let mut data = SignalData::new();
+        data.set_payload(vec![0; MAX_PAYLOAD_WORDS * 4]).unwrap();
+        assert_eq!(data.size_words(), u16::MAX as usize - 1);
+        assert!(matches!(
+            data.set_payload(vec![0; MAX_PAYLOAD_WORDS * 4 + 1]),
+            Err(VitaError::PayloadTooLarge { words: 65536 })
+        ));
+        assert!(matches!(
+            data.set_payload_u32(&vec![0; MAX_PAYLOAD_WORDS + 1]),
+            Err(VitaError::PayloadTooLarge { words: 65536 })
+        ));
+        assert_eq!(data.payload_size_bytes(), MAX_PAYLOAD_WORDS * 4);
+
+        // A maximum-size payload fills a bare packet...
+        let mut packet = Vrt::new_signal_data_packet_no_stream_id();
+        *packet.payload_mut() = Payload::SignalData(data);
+        packet.try_update_packet_size().unwrap();
+        assert_eq!(packet.header().packet_size(), u16::MAX);
+
+        // ...so adding a prologue field can't be sized, and is reported
+        // rather than wrapping the size field.
+        packet.set_stream_id(Some(1));
+        assert!(matches!(
+            packet.computed_size_words(),
+            Err(VitaError::PayloadTooLarge { words: 65536 })
+        ));
+        assert!(packet.try_update_packet_size().is_err());
+        assert_eq!(packet.header().packet_size(), u16::MAX);
+        assert_eq!(packet.len_bytes(), 65536 * 4);
+        assert!(packet.checked_to_bytes().is_err());
+    }
+}
@@ -0,0 +1,261 @@
+// SPDX-FileCopyrightText: 2025 The vita49-rs Authors
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Reassembly of sample frames (e.g. large spectra) split across several
+signal data packets (ANSI/VITA-49.2-2017 section 5.1.6.1).
+
+Each fragment carries a sample frame indicator in its trailer marking
+it as the first, a middle, or the final packet of the frame. The packet
+count in the header orders the fragments and exposes gaps.
+*/
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use crate::prelude::*;
+use crate::SampleFrameIndicator;
+
+/// Number of distinct packet count values; a frame can't span more
+/// packets than this and still be ordered unambiguously.
+const PACKET_COUNT_MODULUS: u8 = 16;
+
+/// A fragment waiting for the rest of its frame.
+struct Fragment {
+    packet_count: u8,
+    indicator: SampleFrameIndicator,
+    data: Vec<u8>,
+}
+
+/// Collects sample frame fragments from signal data packets and yields
+/// each frame's payload once all of its fragments have arrived. Frames
+/// are tracked separately per stream ID.
+///
+/// Fragments of a frame may arrive in any order, but a frame must be
+/// complete before the first fragment of the stream's next frame
+/// arrives; any fragments still buffered at that point are dropped and
+/// counted as a lost frame. Frames are also dropped if the packet count
+/// shows a duplicate fragment, if a fragment reports sample loss, or if
+/// the frame would span more than 16 packets.
+///
+/// # Example
+/// ```
+/// use vita49::{prelude::*, SpectralReassembler};
+/// # fn main() -> Result<(), VitaError> {
+/// let mut reassembler = SpectralReassembler::new();
+/// // Packets without a sample frame indicator are complete frames.
+/// let mut packet = Vrt::new_signal_data_packet();
+/// packet.set_signal_payload(&[1, 2, 3, 4])?;
+/// assert_eq!(reassembler.push(&packet)?, Some(vec![1, 2, 3, 4]));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct SpectralReassembler {
+    partial: BTreeMap<Option<u32>, Vec<Fragment>>,
+    lost_frames: usize,
+}
+
+impl SpectralReassembler {
+    /// Creates an empty reassembler.
+    pub fn new() -> SpectralReassembler {
+        SpectralReassembler::default()
+    }
+
+    /// Gets the number of frames dropped so far because fragments were
+    /// lost.
+    pub fn lost_frames(&self) -> usize {
+        self.lost_frames
+    }
+
+    /// Gets the number of streams with a partially received frame.
+    pub fn pending_streams(&self) -> usize {
+        self.partial.len()
+    }
+
+    /// Adds a signal data packet. Returns the reassembled frame payload
+    /// if this packet completed a frame.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::SignalDataOnly`] if the packet isn't a signal
+    /// data packet.
+    pub fn push(&mut self, packet: &Vrt) -> Result<Option<Vec<u8>>, VitaError> {
+        let data = packet.signal_payload()?;
+        let stream_id = packet.stream_id();
+        let indicator = packet
+            .trailer()
+            .and_then(|t| t.sample_frame_indicator())
+            .unwrap_or(SampleFrameIndicator::NotApplicable);
+        let sample_loss = packet
+            .trailer()
+            .and_then(|t| t.sample_loss_indicator())
+            .unwrap_or(false);
+
+        match indicator {
+            SampleFrameIndicator::NotApplicable => {
+                self.drop_partial(stream_id);
+                return Ok((!sample_loss).then(|| data.to_vec()));
+            }
+            SampleFrameIndicator::FirstDataPacket => {
+                let restarted = self
+                    .partial
+                    .get(&stream_id)
+                    .is_some_and(|f| f.iter().any(|f| f.indicator == indicator));
+                if restarted {
+                    self.drop_partial(stream_id);
+                }
+            }
+            _ => (),
+        }
+
+        let packet_count = packet.header().packet_count();
+        let fragments = self.partial.entry(stream_id).or_default();
+        if sample_loss
+            || fragments.len() >= PACKET_COUNT_MODULUS as usize
+            || fragments.iter().any(|f| f.packet_count == packet_count)
+        {
+            self.drop_partial(stream_id);
+            return Ok(None);
+        }
+        fragments.push(Fragment {
+            packet_count,
+            indicator,
+            data: data.to_vec(),
+        });
+        Ok(self.try_complete(stream_id))
+    }
+
+    /// Drops the partial frame for a stream (if any), counting it as lost.
+    fn drop_partial(&mut self, stream_id: Option<u32>) {
+        if self.partial.remove(&stream_id).is_some() {
+            self.lost_frames += 1;
+        }
+    }
+
+    /// Returns the frame payload if every fragment between the first and
+    /// final fragment has arrived.
+    fn try_complete(&mut self, stream_id: Option<u32>) -> Option<Vec<u8>> {
+        let fragments = self.partial.get_mut(&stream_id)?;
+        let find = |indicator| {
+            fragments
+                .iter()
+                .find(|f| f.indicator == indicator)
+                .map(|f| f.packet_count)
+        };
+        let first = find(SampleFrameIndicator::FirstDataPacket)?;
+        let last = find(SampleFrameIndicator::FinalDataPacket)?;
+        let offset = |count: u8| (count + PACKET_COUNT_MODULUS - first) % PACKET_COUNT_MODULUS;
+        let span = offset(last) as usize + 1;
+        if fragments.len() < span {
+            return None;
+        }
+        let mut fragments = self.partial.remove(&stream_id)?;
+        if fragments.len() > span {
+            // Fragments from outside the frame: something was lost.
+            self.lost_frames += 1;
+            return None;
+        }
+        fragments.sort_by_key(|f| offset(f.packet_count));
+        Some(fragments.into_iter().flat_map(|f| f.data).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a signal data packet carrying a sample frame indicator
+    /// (and optionally the sample loss indicator) in its trailer.
+    fn fragment(stream_id: u32, count: u8, indicator: SampleFrameIndicator, data: u8) -> Vrt {
+        fragment_with_loss(stream_id, count, indicator, data, false)
+    }
+
+    fn fragment_with_loss(
+        stream_id: u32,
+        count: u8,
+        indicator: SampleFrameIndicator,
+        data: u8,
+        sample_loss: bool,
+    ) -> Vrt {
+        let mut packet = Vrt::new_signal_data_packet();
+        packet.set_stream_id(Some(stream_id));
+        packet.header_mut().set_packet_count(count);
+        packet.set_signal_payload([data; 4]).unwrap();
+        let mut bytes = packet.to_bytes().unwrap();
+        // Set the trailer included bit and grow the packet by one word.
+        bytes[0] |= 0x04;
+        let size = u16::from_be_bytes([bytes[2], bytes[3]]) + 1;
+        bytes[2..4].copy_from_slice(&size.to_be_bytes());
+        let mut trailer = (0b11 << 22) | ((indicator as u32) << 10);
+        if sample_loss {
+            trailer |= (1 << 24) | (1 << 12);
+        }
+        bytes.extend(trailer.to_be_bytes());
+        Vrt::try_from(&bytes[..]).unwrap()
+    }
+
+    use SampleFrameIndicator::*;
+
+    #[test]
+    fn in_order() {
+        let mut r = SpectralReassembler::new();
+        assert_eq!(r.push(&fragment(1, 14, FirstDataPacket, 1)).unwrap(), None);
+        assert_eq!(r.push(&fragment(1, 15, MiddleDataPacket, 2)).unwrap(), None);
+        // Other streams are tracked separately.
+        assert_eq!(
+            r.push(&fragment(2, 0, NotApplicable, 9)).unwrap(),
+            Some(vec![9; 4])
+        );
+        assert_eq!(r.push(&fragment(1, 0, MiddleDataPacket, 3)).unwrap(), None);
+        let frame = r
+            .push(&fragment(1, 1, FinalDataPacket, 4))
+            .unwrap()
+            .unwrap();
+        assert_eq!(frame, [[1; 4], [2; 4], [3; 4], [4; 4]].concat());
+        assert_eq!(r.pending_streams(), 0);
+        assert_eq!(r.lost_frames(), 0);
+    }
+
+    #[test]
+    fn out_of_order() {
+        let mut r = SpectralReassembler::new();
+        assert_eq!(r.push(&fragment(1, 7, FinalDataPacket, 3)).unwrap(), None);
+        assert_eq!(r.push(&fragment(1, 5, FirstDataPacket, 1)).unwrap(), None);
+        let frame = r
+            .push(&fragment(1, 6, MiddleDataPacket, 2))
+            .unwrap()
+            .unwrap();
+        assert_eq!(frame, [[1; 4], [2; 4], [3; 4]].concat());
+        assert_eq!(r.lost_frames(), 0);
+    }
+
+    #[test]
+    fn lossy() {
+        let mut r = SpectralReassembler::new();
+        // Middle fragment (count 1) never arrives; the next frame starts.
+        r.push(&fragment(1, 0, FirstDataPacket, 1)).unwrap();
+        r.push(&fragment(1, 2, FinalDataPacket, 3)).unwrap();
+        assert_eq!(r.pending_streams(), 1);
+        r.push(&fragment(1, 3, FirstDataPacket, 4)).unwrap();
+        assert_eq!(r.lost_frames(), 1);
+        let frame = r.push(&fragment(1, 4, FinalDataPacket, 5)).unwrap();
+        assert_eq!(frame, Some([[4; 4], [5; 4]].concat()));
+
+        // Duplicate packet count.
+        r.push(&fragment(1, 5, FirstDataPacket, 1)).unwrap();
+        assert_eq!(r.push(&fragment(1, 5, FinalDataPacket, 2)).unwrap(), None);
+        assert_eq!(r.lost_frames(), 2);
+
+        // Device-reported sample loss.
+        r.push(&fragment(1, 6, FirstDataPacket, 1)).unwrap();
+        let lossy = fragment_with_loss(1, 7, FinalDataPacket, 2, true);
+        assert_eq!(r.push(&lossy).unwrap(), None);
+        assert_eq!(r.lost_frames(), 3);
+        assert_eq!(r.pending_streams(), 0);
+
+        assert!(matches!(
+            r.push(&Vrt::new_context_packet()),
+            Err(VitaError::SignalDataOnly)
+        ));
+    }
+}
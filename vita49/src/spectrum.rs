@@ -7,7 +7,6 @@ Data structures and methods related to the spectral metadata field
 */
 
 use core::fmt;
-use std::convert::From;
 
 use deku::prelude::*;
 use fixed::{
@@ -15,6 +14,7 @@ use fixed::{
     FixedI32, FixedI64,
 };
 
+use crate::fixed_point::saturating_from_f64;
 use crate::VitaError;
 
 /// Base spectrum field data structure.
@@ -63,7 +63,7 @@ pub struct Spectrum {
 macro_rules! size_of_fields {
     ($self:expr, $($field:ident),*) => {{
         let mut acc = 0;
-        $(acc += (std::mem::size_of_val(&$self.$field) / std::mem::size_of::<u32>()) as u16;)*
+        $(acc += core::mem::size_of_val(&$self.$field) / core::mem::size_of::<u32>();)*
         acc
     }}
 }
@@ -439,10 +439,13 @@ impl WindowTimeDelta {
     }
 
     /// Create a new window time-delta from some percent overlap.
-    pub fn from_percent_overlap(percent_overlap: f32) -> WindowTimeDelta {
+    ///
+    /// # Errors
+    /// Returns [`VitaError::OutOfRange`] if `percent_overlap` is NaN.
+    pub fn from_percent_overlap(percent_overlap: f32) -> Result<WindowTimeDelta, VitaError> {
         let mut ret = WindowTimeDelta::default();
-        ret.set_percent_overlap(percent_overlap);
-        ret
+        ret.set_percent_overlap(percent_overlap)?;
+        Ok(ret)
     }
 
     /// Get the window time-delta as nanoseconds (resolved to the nearest whole sample).
@@ -471,8 +474,12 @@ impl WindowTimeDelta {
     }
 
     /// Set the window time-delta as percent overlap (resolved to the nearest whole sample).
-    pub fn set_percent_overlap(&mut self, percent_overlap: f32) {
-        self.0 = FixedI32::<U12>::from_num(percent_overlap).to_bits() as u32;
+    ///
+    /// # Errors
+    /// Returns [`VitaError::OutOfRange`] if `percent_overlap` is NaN.
+    pub fn set_percent_overlap(&mut self, percent_overlap: f32) -> Result<(), VitaError> {
+        self.0 = saturating_from_f64::<FixedI32<U12>>(percent_overlap.into())?.to_bits() as u32;
+        Ok(())
     }
 }
 
@@ -548,7 +555,7 @@ impl Spectrum {
             WindowTimeDeltaInterpretation::Reserved => return Err(VitaError::ReservedField),
             _ => {
                 let v = u8::from(window_time_delta_interpretation) as u32;
-                self.spectrum_type = (self.spectrum_type & !(0b1111 << 8)) | (v << 16)
+                self.spectrum_type = (self.spectrum_type & !(0b1111 << 16)) | (v << 16)
             }
         }
         Ok(())
@@ -602,8 +609,12 @@ impl Spectrum {
     }
 
     /// Set the spectral resolution (Hz).
-    pub fn set_resolution_hz(&mut self, resolution_hz: f64) {
-        self.resolution = FixedI64::<U20>::from_num(resolution_hz).to_bits();
+    ///
+    /// # Errors
+    /// Returns [`VitaError::OutOfRange`] if `resolution_hz` is NaN.
+    pub fn set_resolution_hz(&mut self, resolution_hz: f64) -> Result<(), VitaError> {
+        self.resolution = saturating_from_f64::<FixedI64<U20>>(resolution_hz)?.to_bits();
+        Ok(())
     }
 
     /// Get the spectral span (Hz).
@@ -612,8 +623,12 @@ impl Spectrum {
     }
 
     /// Set the spectral span (Hz).
-    pub fn set_span_hz(&mut self, span_hz: f64) {
-        self.span = FixedI64::<U20>::from_num(span_hz).to_bits();
+    ///
+    /// # Errors
+    /// Returns [`VitaError::OutOfRange`] if `span_hz` is NaN.
+    pub fn set_span_hz(&mut self, span_hz: f64) -> Result<(), VitaError> {
+        self.span = saturating_from_f64::<FixedI64<U20>>(span_hz)?.to_bits();
+        Ok(())
     }
 
     /// Get the number of averages.
@@ -666,8 +681,62 @@ impl Spectrum {
         self.window_time_delta = window_time_delta;
     }
 
+    /// Sets the resolution to `span / num_transform_points`.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::OutOfRange`] if the number of transform points
+    /// is zero.
+    pub fn derive_resolution_from_span(&mut self) -> Result<(), VitaError> {
+        if self.num_transform_points == 0 {
+            return Err(VitaError::OutOfRange);
+        }
+        self.set_resolution_hz(self.span_hz() / self.num_transform_points as f64)
+    }
+
+    /// Sets the span to `resolution * num_transform_points`.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::Spectrum;
+    /// let mut spectrum = Spectrum::new();
+    /// spectrum.set_num_transform_points(1024);
+    /// spectrum.set_resolution_hz(7812.5).unwrap();
+    /// spectrum.derive_span_from_resolution();
+    /// assert_eq!(spectrum.span_hz(), 8e6);
+    /// assert!(spectrum.validate().is_ok());
+    /// ```
+    pub fn derive_span_from_resolution(&mut self) {
+        // Both factors are finite, so the product is never NaN.
+        let _ = self.set_span_hz(self.resolution_hz() * self.num_transform_points as f64);
+    }
+
+    /// Checks that the span is consistent with the resolution and number
+    /// of transform points (`span ≈ resolution * num_transform_points`).
+    /// The allowed difference covers the rounding of the resolution to
+    /// its fixed-point format (2<sup>-20</sup> Hz per transform point).
+    ///
+    /// # Errors
+    /// Returns [`VitaError::InvalidValue`] if the fields are inconsistent.
+    pub fn validate(&self) -> Result<(), VitaError> {
+        let points = self.num_transform_points as f64;
+        let expected = self.resolution_hz() * points;
+        let tolerance = points / (1u64 << 20) as f64 + expected.abs() * 1e-12;
+        if (self.span_hz() - expected).abs() > tolerance {
+            return Err(VitaError::InvalidValue(
+                format!(
+                    "spectrum span {} Hz doesn't match resolution {} Hz * {} points",
+                    self.span_hz(),
+                    self.resolution_hz(),
+                    self.num_transform_points
+                )
+                .into(),
+            ));
+        }
+        Ok(())
+    }
+
     /// Gets the size of the spectral field in 32-bit words.
-    pub fn size_words(&self) -> u16 {
+    pub fn size_words(&self) -> usize {
         size_of_fields!(
             self,
             spectrum_type,
@@ -686,10 +755,16 @@ impl Spectrum {
 }
 
 impl fmt::Display for Spectrum {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         writeln!(f, "Spectrum:")?;
-        writeln!(f, "  Spectrum type: {:x}", self.spectrum_type)?;
-        writeln!(f, "  Window type: {:x}", self.window_type)?;
+        writeln!(f, "  Spectrum type: {:?}", self.spectrum_type())?;
+        writeln!(f, "  Averaging type: {:?}", self.averaging_type())?;
+        writeln!(
+            f,
+            "  Window time-delta interpretation: {:?}",
+            self.window_time_delta_interpretation()
+        )?;
+        writeln!(f, "  Window type: {:?}", self.window_type())?;
         writeln!(f, "  Num transform points: {}", self.num_transform_points())?;
         writeln!(f, "  Num window points: {}", self.num_window_points())?;
         writeln!(f, "  Resolution: {} Hz", self.resolution_hz())?;
@@ -727,3 +802,89 @@ impl fmt::Display for Spectrum {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spectrum_type_subfields() {
+        let mut spectrum = Spectrum::new();
+        spectrum
+            .set_spectrum_type(SpectrumType::LogPowerDb)
+            .unwrap();
+        spectrum
+            .set_averaging_type(AveragingType::Exponential)
+            .unwrap();
+        spectrum
+            .set_window_time_delta_interpretation(WindowTimeDeltaInterpretation::Samples)
+            .unwrap();
+        assert_eq!(spectrum.spectrum_type_as_u32(), 0x2_08_01);
+
+        // Changing the interpretation must not leave stale bits behind.
+        spectrum
+            .set_window_time_delta_interpretation(WindowTimeDeltaInterpretation::PercentOverlap)
+            .unwrap();
+        assert_eq!(spectrum.spectrum_type(), SpectrumType::LogPowerDb);
+        assert_eq!(spectrum.averaging_type(), AveragingType::Exponential);
+        assert_eq!(
+            spectrum.window_time_delta_interpretation(),
+            WindowTimeDeltaInterpretation::PercentOverlap
+        );
+        assert_eq!(spectrum.spectrum_type_as_u32(), 0x1_08_01);
+    }
+
+    #[test]
+    fn window_time_delta() {
+        let mut spectrum = Spectrum::new();
+        spectrum.set_num_averages(16);
+        spectrum.set_weighting_factor(-3);
+        spectrum.set_window_time_delta(WindowTimeDelta::from_percent_overlap(50.0).unwrap());
+        spectrum
+            .set_window_time_delta_interpretation(WindowTimeDeltaInterpretation::PercentOverlap)
+            .unwrap();
+        assert_eq!(spectrum.num_averages(), 16);
+        assert_eq!(spectrum.weighting_factor(), -3);
+        assert_eq!(spectrum.window_time_delta().as_percent_overlap(), 50.0);
+        // 12 fractional bits
+        assert_eq!(spectrum.window_time_delta().as_samples(), 50 << 12);
+
+        let display = format!("{spectrum}");
+        assert!(display.contains("Window time-delta interpretation: PercentOverlap"));
+        assert!(display.contains("Window time-delta: 50%"));
+        assert!(display.contains("Num averages: 16"));
+    }
+
+    #[test]
+    fn span_resolution_derivation() {
+        let mut spectrum = Spectrum::new();
+        assert!(spectrum.validate().is_ok());
+        assert!(matches!(
+            spectrum.derive_resolution_from_span(),
+            Err(VitaError::OutOfRange)
+        ));
+
+        spectrum.set_num_transform_points(1280);
+        spectrum.set_span_hz(8e6).unwrap();
+        assert!(spectrum.validate().is_err());
+        spectrum.derive_resolution_from_span().unwrap();
+        assert_eq!(spectrum.resolution_hz(), 6250.0);
+        assert!(spectrum.validate().is_ok());
+
+        // A resolution that doesn't fit the fixed-point format exactly
+        // still validates after deriving the span.
+        spectrum.set_num_transform_points(3);
+        spectrum.set_span_hz(1e6).unwrap();
+        spectrum.derive_resolution_from_span().unwrap();
+        assert!(spectrum.validate().is_ok());
+        spectrum.derive_span_from_resolution();
+        assert!(spectrum.validate().is_ok());
+
+        // Off by 1 Hz is outside the tolerance.
+        spectrum.set_span_hz(spectrum.span_hz() + 1.0).unwrap();
+        assert!(matches!(
+            spectrum.validate(),
+            Err(VitaError::InvalidValue(_))
+        ));
+    }
+}
@@ -0,0 +1,174 @@
+// SPDX-FileCopyrightText: 2025 The vita49-rs Authors
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Data structures and methods related to the state and event indicators
+field (ANSI/VITA-49.2-2017 section 9.10.8).
+
+The indicator bits mirror those of the signal data trailer: each
+indicator has an enable bit that says whether the indicator value
+is meaningful (see [`EnableValue`](crate::EnableValue)).
+*/
+
+use core::fmt;
+use deku::prelude::*;
+
+use crate::enable_value::indicator;
+
+/// Base state and event indicators data structure.
+#[derive(
+    Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, DekuRead, DekuWrite,
+)]
+#[deku(endian = "endian", ctx = "endian: deku::ctx::Endian")]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateAndEventIndicators(u32);
+
+impl StateAndEventIndicators {
+    /// Builds the indicators from their raw 32-bit value.
+    pub fn from_u32(word: u32) -> StateAndEventIndicators {
+        StateAndEventIndicators(word)
+    }
+
+    /// Gets the raw 32-bit value of the indicators.
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
+
+    /// Gets the size of the state and event indicators field in 32-bit words.
+    pub fn size_words(&self) -> usize {
+        core::mem::size_of_val(self) / core::mem::size_of::<u32>()
+    }
+
+    indicator!(
+        cal_time_indicator,
+        set_cal_time_indicator,
+        31,
+        19,
+        "calibrated time"
+    );
+    indicator!(
+        valid_data_indicator,
+        set_valid_data_indicator,
+        30,
+        18,
+        "valid data"
+    );
+    indicator!(
+        reference_lock_indicator,
+        set_reference_lock_indicator,
+        29,
+        17,
+        "reference lock"
+    );
+    indicator!(
+        agc_indicator,
+        set_agc_indicator,
+        28,
+        16,
+        "automatic gain control (AGC)"
+    );
+    indicator!(
+        detected_signal_indicator,
+        set_detected_signal_indicator,
+        27,
+        15,
+        "detected signal"
+    );
+    indicator!(
+        spectral_inversion_indicator,
+        set_spectral_inversion_indicator,
+        26,
+        14,
+        "spectral inversion"
+    );
+    indicator!(
+        over_range_indicator,
+        set_over_range_indicator,
+        25,
+        13,
+        "over-range"
+    );
+    indicator!(
+        sample_loss_indicator,
+        set_sample_loss_indicator,
+        24,
+        12,
+        "sample loss"
+    );
+
+    /// Gets the user-defined indicator bits.
+    pub fn user_defined(&self) -> u8 {
+        (self.0 & 0xFF) as u8
+    }
+
+    /// Sets the user-defined indicator bits.
+    pub fn set_user_defined(&mut self, value: u8) {
+        self.0 = (self.0 & !0xFF) | value as u32;
+    }
+}
+
+impl fmt::Display for StateAndEventIndicators {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let indicators = [
+            ("Calibrated time", self.cal_time_indicator()),
+            ("Valid data", self.valid_data_indicator()),
+            ("Reference lock", self.reference_lock_indicator()),
+            ("AGC", self.agc_indicator()),
+            ("Detected signal", self.detected_signal_indicator()),
+            ("Spectral inversion", self.spectral_inversion_indicator()),
+            ("Over-range", self.over_range_indicator()),
+            ("Sample loss", self.sample_loss_indicator()),
+        ];
+        let mut first = true;
+        for (name, value) in indicators {
+            if let Some(v) = value {
+                if !first {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{name}: {v}")?;
+                first = false;
+            }
+        }
+        if !first {
+            write!(f, ", ")?;
+        }
+        write!(f, "User-defined: {:#04x}", self.user_defined())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn state_indicators_round_trip() {
+        let mut indicators = StateAndEventIndicators::default();
+        indicators.set_cal_time_indicator(Some(true));
+        indicators.set_reference_lock_indicator(Some(false));
+        indicators.set_over_range_indicator(Some(true));
+        indicators.set_user_defined(0xA5);
+        assert_eq!(indicators.as_u32(), 0xA208_20A5);
+        assert_eq!(indicators.valid_data_indicator(), None);
+        assert_eq!(
+            format!("{indicators}"),
+            "Calibrated time: true, Reference lock: false, Over-range: true, User-defined: 0xa5"
+        );
+
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_state_indicators(Some(indicators));
+        packet.update_packet_size();
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        let parsed = parsed.payload().context().unwrap().state_indicators();
+        assert_eq!(parsed, Some(&indicators));
+        let parsed = parsed.unwrap();
+        assert_eq!(parsed.cal_time_indicator(), Some(true));
+        assert_eq!(parsed.reference_lock_indicator(), Some(false));
+        assert_eq!(parsed.over_range_indicator(), Some(true));
+
+        indicators.set_cal_time_indicator(None);
+        assert_eq!(indicators.cal_time_indicator(), None);
+        assert_eq!(indicators.as_u32(), 0x2200_20A5);
+    }
+}
@@ -0,0 +1,143 @@
+// SPDX-FileCopyrightText: 2025 The vita49-rs Authors
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+A typed wrapper for the stream identifier
+(ANSI/VITA-49.2-2017 section 5.1.2).
+
+The standard leaves the meaning of the 32 stream ID bits up to the
+system. [`StreamId`] helps systems that pack structured values (e.g. a
+channel and a flow number) into the stream ID. Packets still store a
+plain `u32`, so convert with `From`/`Into`:
+
+```
+use vita49::{prelude::*, StreamId};
+# fn main() -> Result<(), VitaError> {
+let mut id = StreamId::default();
+id.set_bits(16, 16, 3)?; // channel
+id.set_bits(0, 16, 7)?; // flow
+let mut packet = Vrt::new_signal_data_packet();
+packet.set_stream_id(Some(id.into()));
+let id = packet.stream_id().map(StreamId::from).unwrap();
+assert_eq!(id.bits(16, 16), 3);
+assert_eq!(id.to_string(), "0x00030007");
+# Ok(())
+# }
+```
+*/
+
+use core::fmt;
+
+use crate::VitaError;
+
+/// Stream identifier newtype.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StreamId(u32);
+
+impl StreamId {
+    /// Creates a stream ID from its raw 32-bit value.
+    pub fn new(id: u32) -> StreamId {
+        StreamId(id)
+    }
+
+    /// Gets the raw 32-bit value of the stream ID.
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
+
+    /// Gets `width` bits starting at bit `offset` (0 is the least
+    /// significant bit). Bits beyond bit 31 read as zero.
+    pub fn bits(&self, offset: u32, width: u32) -> u32 {
+        if offset >= 32 {
+            return 0;
+        }
+        (self.0 >> offset) & Self::mask(width)
+    }
+
+    /// Sets `width` bits starting at bit `offset` (0 is the least
+    /// significant bit) to `value`.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::OutOfRange`] if the bit range doesn't fit in
+    /// 32 bits or `value` doesn't fit in `width` bits.
+    pub fn set_bits(&mut self, offset: u32, width: u32, value: u32) -> Result<(), VitaError> {
+        let mask = Self::mask(width);
+        match offset.checked_add(width) {
+            Some(end) if end <= 32 && value & !mask == 0 => {}
+            _ => return Err(VitaError::OutOfRange),
+        }
+        // Shift in 64 bits so that a zero-width range at bit 32 is fine.
+        let field = u64::from(mask) << offset;
+        self.0 = ((u64::from(self.0) & !field) | (u64::from(value) << offset)) as u32;
+        Ok(())
+    }
+
+    fn mask(width: u32) -> u32 {
+        if width >= 32 {
+            u32::MAX
+        } else {
+            (1 << width) - 1
+        }
+    }
+}
+
+impl From<u32> for StreamId {
+    fn from(id: u32) -> StreamId {
+        StreamId(id)
+    }
+}
+
+impl From<StreamId> for u32 {
+    fn from(id: StreamId) -> u32 {
+        id.0
+    }
+}
+
+impl fmt::Display for StreamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#010x}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn stream_id_conversions() {
+        let id = StreamId::from(0xDEAD_BEEF);
+        assert_eq!(u32::from(id), 0xDEAD_BEEF);
+        assert_eq!(format!("{id}"), "0xdeadbeef");
+        assert_eq!(id.bits(16, 16), 0xDEAD);
+        assert_eq!(id.bits(0, 16), 0xBEEF);
+        assert_eq!(id.bits(28, 8), 0xD);
+        assert_eq!(id.bits(0, 32), 0xDEAD_BEEF);
+
+        let mut id = StreamId::default();
+        id.set_bits(24, 8, 0x12).unwrap();
+        id.set_bits(0, 4, 0xF).unwrap();
+        assert_eq!(id.as_u32(), 0x1200_000F);
+        id.set_bits(0, 4, 0x3).unwrap();
+        assert_eq!(id.as_u32(), 0x1200_0003);
+        assert!(id.set_bits(0, 4, 0x10).is_err());
+        assert!(id.set_bits(28, 8, 0).is_err());
+
+        // Edge cases: full width, empty range at the top, and offsets
+        // that would overflow.
+        id.set_bits(0, 32, 0xCAFE_F00D).unwrap();
+        assert_eq!(id.as_u32(), 0xCAFE_F00D);
+        id.set_bits(32, 0, 0).unwrap();
+        assert_eq!(id.as_u32(), 0xCAFE_F00D);
+        assert!(id.set_bits(1, 32, 0).is_err());
+        assert!(id.set_bits(u32::MAX, 1, 0).is_err());
+        assert!(id.set_bits(u32::MAX - 1, 2, 0).is_err());
+        assert!(id.set_bits(4, u32::MAX, 0).is_err());
+        assert_eq!(id.bits(u32::MAX, 32), 0);
+
+        let mut packet = Vrt::new_context_packet();
+        packet.set_stream_id(Some(id.into()));
+        assert_eq!(packet.stream_id().map(StreamId::from), Some(id));
+    }
+}
@@ -6,9 +6,11 @@ Data structures and methods related to the threshold field described in
 (ANSI/VITA-49.2-2017 section 9.5.13).
 */
 
+use crate::fixed_point::saturating_from_f64;
+use crate::VitaError;
+use core::fmt;
 use deku::prelude::*;
 use fixed::{types::extra::U7, FixedI16};
-use std::fmt;
 
 /// Base threshold data structure.
 #[derive(
@@ -20,15 +22,25 @@ pub struct Threshold(i32);
 
 impl Threshold {
     /// Create a new `Threshold` object given stage 1 and 2 in dB.
-    pub fn new(stage_1_threshold_db: f32, stage_2_threshold_db: f32) -> Threshold {
-        let s1 = FixedI16::<U7>::from_num(stage_1_threshold_db).to_bits() as i32;
-        let s2 = FixedI16::<U7>::from_num(stage_2_threshold_db).to_bits() as i32;
-        Threshold((s2 << 16) | s1)
+    ///
+    /// Thresholds outside the representable range are clamped to it. The
+    /// setters below behave the same way.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::OutOfRange`] if either threshold is NaN.
+    pub fn new(
+        stage_1_threshold_db: f32,
+        stage_2_threshold_db: f32,
+    ) -> Result<Threshold, VitaError> {
+        let mut ret = Threshold::default();
+        ret.set_stage_1_threshold_db(stage_1_threshold_db)?;
+        ret.set_stage_2_threshold_db(stage_2_threshold_db)?;
+        Ok(ret)
     }
 
     /// Gets the size of the threshold structure in 32-bit words.
-    pub fn size_words(&self) -> u16 {
-        (std::mem::size_of_val(&self.0) / std::mem::size_of::<u32>()) as u16
+    pub fn size_words(&self) -> usize {
+        core::mem::size_of_val(&self.0) / core::mem::size_of::<u32>()
     }
 
     /// Gets stage 1 threshold (dB)
@@ -38,9 +50,13 @@ impl Threshold {
     }
 
     /// Sets stage 1 threshold (dB)
-    pub fn set_stage_1_threshold_db(&mut self, stage_1_threshold_db: f32) {
-        let s1 = FixedI16::<U7>::from_num(stage_1_threshold_db).to_bits() as i32;
-        self.0 = (self.0 & (0xFFFF_0000u32 as i32)) | s1
+    ///
+    /// # Errors
+    /// Returns [`VitaError::OutOfRange`] if the threshold is NaN.
+    pub fn set_stage_1_threshold_db(&mut self, stage_1_threshold_db: f32) -> Result<(), VitaError> {
+        let s1 = saturating_from_f64::<FixedI16<U7>>(stage_1_threshold_db.into())?.to_bits() as i32;
+        self.0 = (self.0 & (0xFFFF_0000u32 as i32)) | s1;
+        Ok(())
     }
 
     /// Gets stage 2 threshold (dB)
@@ -50,9 +66,13 @@ impl Threshold {
     }
 
     /// Sets stage 2 threshold (dB)
-    pub fn set_stage_2_threshold_db(&mut self, stage_2_threshold_db: f32) {
-        let s2 = FixedI16::<U7>::from_num(stage_2_threshold_db).to_bits() as i32;
-        self.0 = (self.0 & 0x0000_FFFF) | (s2 << 16)
+    ///
+    /// # Errors
+    /// Returns [`VitaError::OutOfRange`] if the threshold is NaN.
+    pub fn set_stage_2_threshold_db(&mut self, stage_2_threshold_db: f32) -> Result<(), VitaError> {
+        let s2 = saturating_from_f64::<FixedI16<U7>>(stage_2_threshold_db.into())?.to_bits() as i32;
+        self.0 = (self.0 & 0x0000_FFFF) | (s2 << 16);
+        Ok(())
     }
 }
 
@@ -81,7 +101,7 @@ mod tests {
         let context = packet.payload_mut().context_mut().unwrap();
         let mut s1: f32 = 25.2;
         let mut s2: f32 = 0.23;
-        let mut t = Threshold::new(s1, s2);
+        let mut t = Threshold::new(s1, s2).unwrap();
         context.set_threshold(Some(t));
         assert_relative_eq!(
             context.threshold().unwrap().stage_1_threshold_db(),
@@ -95,8 +115,8 @@ mod tests {
         );
         s1 = -20.5;
         s2 = -11.1;
-        t.set_stage_1_threshold_db(s1);
-        t.set_stage_2_threshold_db(s2);
+        t.set_stage_1_threshold_db(s1).unwrap();
+        t.set_stage_2_threshold_db(s2).unwrap();
         context.set_threshold(Some(t));
         assert_relative_eq!(
             context.threshold().unwrap().stage_1_threshold_db(),
@@ -0,0 +1,142 @@
+// SPDX-FileCopyrightText: 2025 The vita49-rs Authors
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Data structures and methods related to the timestamp details field
+(ANSI/VITA-49.2-2017 section 9.7.3.1).
+
+The field is 64 bits wide. The first word holds user-defined bits and
+flags describing the packet timestamps; the second word holds the
+timestamp offset.
+*/
+
+use core::fmt;
+use deku::prelude::*;
+
+/// Base timestamp details data structure.
+#[derive(
+    Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, DekuRead, DekuWrite,
+)]
+#[deku(endian = "endian", ctx = "endian: deku::ctx::Endian")]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimestampDetails(u64);
+
+impl TimestampDetails {
+    const USER_DEFINED_SHIFT: u32 = 56;
+    const GLOBAL_BIT: u32 = 52;
+    const EPOCH_SHIFT: u32 = 50;
+
+    /// Builds the timestamp details from their raw 64-bit value.
+    pub fn from_u64(value: u64) -> TimestampDetails {
+        TimestampDetails(value)
+    }
+
+    /// Gets the raw 64-bit value of the timestamp details.
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+
+    /// Gets the size of the timestamp details field in 32-bit words.
+    pub fn size_words(&self) -> usize {
+        core::mem::size_of_val(self) / core::mem::size_of::<u32>()
+    }
+
+    /// Gets the user-defined bits (bits 31..24 of the first word).
+    pub fn user_defined(&self) -> u8 {
+        (self.0 >> Self::USER_DEFINED_SHIFT) as u8
+    }
+
+    /// Sets the user-defined bits (bits 31..24 of the first word).
+    pub fn set_user_defined(&mut self, value: u8) {
+        self.0 &= !(0xFF << Self::USER_DEFINED_SHIFT);
+        self.0 |= u64::from(value) << Self::USER_DEFINED_SHIFT;
+    }
+
+    /// Returns true if the timestamps are referenced to a global time
+    /// source (the G bit) rather than a local one.
+    pub fn global(&self) -> bool {
+        self.0 & (1 << Self::GLOBAL_BIT) != 0
+    }
+
+    /// Sets the G bit, marking the timestamps as referenced to a global
+    /// time source.
+    pub fn set_global(&mut self, global: bool) {
+        self.0 &= !(1 << Self::GLOBAL_BIT);
+        self.0 |= u64::from(global) << Self::GLOBAL_BIT;
+    }
+
+    /// Gets the 2-bit epoch code (the E bits) identifying the epoch of
+    /// the integer-seconds timestamp.
+    pub fn epoch(&self) -> u8 {
+        ((self.0 >> Self::EPOCH_SHIFT) & 0b11) as u8
+    }
+
+    /// Sets the 2-bit epoch code (the E bits). Only the two low bits of
+    /// `epoch` are used.
+    pub fn set_epoch(&mut self, epoch: u8) {
+        self.0 &= !(0b11 << Self::EPOCH_SHIFT);
+        self.0 |= u64::from(epoch & 0b11) << Self::EPOCH_SHIFT;
+    }
+
+    /// Gets the timestamp offset (the second word).
+    pub fn timestamp_offset(&self) -> u32 {
+        self.0 as u32
+    }
+
+    /// Sets the timestamp offset (the second word).
+    pub fn set_timestamp_offset(&mut self, offset: u32) {
+        self.0 = (self.0 & !u64::from(u32::MAX)) | u64::from(offset);
+    }
+}
+
+impl From<u64> for TimestampDetails {
+    fn from(value: u64) -> Self {
+        TimestampDetails(value)
+    }
+}
+
+impl fmt::Display for TimestampDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "Global: {}, Epoch: {}, Offset: {}, User-defined: {:#04x}",
+            self.global(),
+            self.epoch(),
+            self.timestamp_offset(),
+            self.user_defined()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn subfields() {
+        let mut details = TimestampDetails::default();
+        details.set_user_defined(0xA5);
+        details.set_global(true);
+        details.set_epoch(0b01);
+        details.set_timestamp_offset(0xDEAD_BEEF);
+        assert_eq!(details.as_u64(), 0xA514_0000_DEAD_BEEF);
+        assert_eq!(details.user_defined(), 0xA5);
+        assert!(details.global());
+        assert_eq!(details.epoch(), 0b01);
+        assert_eq!(details.timestamp_offset(), 0xDEAD_BEEF);
+        assert_eq!(
+            details.to_string(),
+            "Global: true, Epoch: 1, Offset: 3735928559, User-defined: 0xa5"
+        );
+
+        // Each setter only touches its own bits.
+        let mut details = TimestampDetails::from_u64(u64::MAX);
+        details.set_global(false);
+        details.set_epoch(0);
+        assert_eq!(details.as_u64(), !(0b111 << 50));
+        details.set_user_defined(0);
+        details.set_timestamp_offset(0);
+        assert_eq!(details.as_u64(), 0x00E3_FFFF_0000_0000);
+    }
+}
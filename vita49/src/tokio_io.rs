@@ -0,0 +1,180 @@
+// SPDX-FileCopyrightText: 2025 The vita49-rs Authors
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Async helpers for reading VRT packets from tokio byte streams
+(e.g. a `TcpStream`). Only available with the `tokio` feature.
+*/
+
+use std::io::{Error, ErrorKind};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::prelude::*;
+
+/// Wrapper around an [`AsyncRead`] that yields one VRT packet at a time.
+///
+/// Each packet is read by first decoding the header to learn the packet
+/// size, then reading the remaining bytes. The internal buffer is reused
+/// between packets.
+///
+/// # Example
+/// ```no_run
+/// use tokio::net::TcpStream;
+/// use vita49::VrtStream;
+/// # async fn run() -> std::io::Result<()> {
+/// let socket = TcpStream::connect("127.0.0.1:4991").await?;
+/// let mut stream = VrtStream::new(socket);
+/// while let Some(packet) = stream.next_packet().await? {
+///     println!("Got packet of type {:?}", packet.header().packet_type());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct VrtStream<R> {
+    reader: R,
+    buf: Vec<u8>,
+}
+
+impl<R: AsyncRead + Unpin> VrtStream<R> {
+    /// Wraps an async reader.
+    pub fn new(reader: R) -> VrtStream<R> {
+        VrtStream {
+            reader,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Reads the next packet from the stream. Returns `Ok(None)` if the
+    /// stream ended cleanly on a packet boundary.
+    ///
+    /// # Errors
+    /// Returns an [`ErrorKind::UnexpectedEof`] error if the stream ends
+    /// part way through a packet, an [`ErrorKind::InvalidData`] error if
+    /// the packet can't be parsed, or any error from the underlying reader.
+    pub async fn next_packet(&mut self) -> Result<Option<Vrt>, Error> {
+        self.buf.resize(4, 0);
+        let mut header_read = 0;
+        while header_read < 4 {
+            match self.reader.read(&mut self.buf[header_read..]).await? {
+                0 if header_read == 0 => return Ok(None),
+                0 => return Err(eof_error(header_read, 4)),
+                n => header_read += n,
+            }
+        }
+
+        let header = PacketHeader::from_u32(u32::from_be_bytes([
+            self.buf[0],
+            self.buf[1],
+            self.buf[2],
+            self.buf[3],
+        ]));
+        let packet_len = header.packet_size_bytes();
+        if packet_len < 4 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                VitaError::InvalidPacketSize,
+            ));
+        }
+        self.buf.resize(packet_len, 0);
+        let mut read = 4;
+        while read < packet_len {
+            match self.reader.read(&mut self.buf[read..]).await? {
+                0 => return Err(eof_error(read, packet_len)),
+                n => read += n,
+            }
+        }
+
+        let packet = Vrt::try_from(&self.buf[..])?;
+        Ok(Some(packet))
+    }
+
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Consumes the wrapper and returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// Reads a single VRT packet from an async reader.
+///
+/// # Errors
+/// Returns an [`ErrorKind::UnexpectedEof`] error if the reader ends
+/// before a full packet is read, an [`ErrorKind::InvalidData`] error if
+/// the packet can't be parsed, or any error from the underlying reader.
+pub async fn read_vrt<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Vrt, Error> {
+    VrtStream::new(reader)
+        .next_packet()
+        .await?
+        .ok_or_else(|| eof_error(0, 4))
+}
+
+fn eof_error(read: usize, needed: usize) -> Error {
+    Error::new(
+        ErrorKind::UnexpectedEof,
+        VitaError::BufferTooShort {
+            needed,
+            available: read,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_packets() -> (Vrt, Vrt) {
+        let mut data = Vrt::new_signal_data_packet();
+        data.set_stream_id(Some(0x1234));
+        data.set_signal_payload([1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        let mut context = Vrt::new_context_packet();
+        context
+            .payload_mut()
+            .context_mut()
+            .unwrap()
+            .set_bandwidth_hz(Some(8e6))
+            .unwrap();
+        context.update_packet_size();
+        (data, context)
+    }
+
+    #[tokio::test]
+    async fn read_across_partial_reads() {
+        let (data, context) = test_packets();
+        let data_bytes = data.to_bytes().unwrap();
+        let context_bytes = context.to_bytes().unwrap();
+
+        // Deliver the bytes in awkward chunk sizes to exercise partial reads.
+        let reader = tokio_test::io::Builder::new()
+            .read(&data_bytes[..2])
+            .read(&data_bytes[2..7])
+            .read(&data_bytes[7..])
+            .read(&context_bytes)
+            .build();
+        let mut stream = VrtStream::new(reader);
+        assert_eq!(stream.next_packet().await.unwrap(), Some(data));
+        assert_eq!(stream.next_packet().await.unwrap(), Some(context));
+        assert_eq!(stream.next_packet().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn eof_mid_packet() {
+        let (data, _) = test_packets();
+        let bytes = data.to_bytes().unwrap();
+        let mut reader = &bytes[..bytes.len() - 1];
+        let err = read_vrt(&mut reader).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+        let mut reader = &bytes[..2];
+        let err = read_vrt(&mut reader).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+        let mut reader = &bytes[..0];
+        let err = read_vrt(&mut reader).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+}
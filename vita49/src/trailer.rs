@@ -6,8 +6,12 @@ Data structures and methods related to the trailer field
 (ANSI/VITA-49.2-2017 section 5.1.6).
 */
 
+use core::fmt;
 use deku::prelude::*;
 
+use crate::enable_value::{indicator, EnableValue};
+use crate::VitaError;
+
 /// Sample frame indicator enumeration.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, DekuRead, DekuWrite)]
 #[deku(id_type = "u8", endian = "endian", ctx = "endian: deku::ctx::Endian")]
@@ -59,29 +63,9 @@ impl TryFrom<u32> for SampleFrameIndicator {
 pub struct Trailer(u32);
 
 impl Trailer {
-    fn cal_time_enabled(&self) -> bool {
-        self.0 & (1 << 31) > 0
-    }
-    fn valid_data_enabled(&self) -> bool {
-        self.0 & (1 << 30) > 0
-    }
-    fn reference_lock_enabled(&self) -> bool {
-        self.0 & (1 << 29) > 0
-    }
-    fn agc_enabled(&self) -> bool {
-        self.0 & (1 << 28) > 0
-    }
-    fn detected_signal_enabled(&self) -> bool {
-        self.0 & (1 << 27) > 0
-    }
-    fn spectral_inversion_enabled(&self) -> bool {
-        self.0 & (1 << 26) > 0
-    }
-    fn over_range_enabled(&self) -> bool {
-        self.0 & (1 << 25) > 0
-    }
-    fn sample_loss_enabled(&self) -> bool {
-        self.0 & (1 << 24) > 0
+    /// Builds a trailer from its raw 32-bit value.
+    pub(crate) fn from_u32(word: u32) -> Trailer {
+        Trailer(word)
     }
     fn sample_frame_enabled(&self) -> bool {
         self.0 & (1 << 23) > 0 && self.0 & (1 << 22) > 0
@@ -89,70 +73,77 @@ impl Trailer {
     fn user_defined_enabled(&self) -> bool {
         self.0 & (1 << 21) > 0 && self.0 & (1 << 20) > 0
     }
-    /// Returns the calibration time indicator status if present.
-    pub fn cal_time_indicator(&self) -> Option<bool> {
-        if self.cal_time_enabled() {
-            Some(self.0 & (1 << 19) > 0)
-        } else {
-            None
-        }
-    }
-    /// Returns the valid data indicator status if present.
-    pub fn valid_data_indicator(&self) -> Option<bool> {
-        if self.valid_data_enabled() {
-            Some(self.0 & (1 << 18) > 0)
-        } else {
-            None
-        }
-    }
-    /// Returns the reference lock indicator status if present.
-    pub fn reference_lock_indicator(&self) -> Option<bool> {
-        if self.reference_lock_enabled() {
-            Some(self.0 & (1 << 17) > 0)
-        } else {
-            None
-        }
-    }
-    /// Returns the automcatic gain control (AGC) indicator status if present.
-    pub fn agc_indicator(&self) -> Option<bool> {
-        if self.agc_enabled() {
-            Some(self.0 & (1 << 16) > 0)
-        } else {
-            None
-        }
-    }
-    /// Returns the detected signal indicator status if present.
-    pub fn detected_signal_indicator(&self) -> Option<bool> {
-        if self.detected_signal_enabled() {
-            Some(self.0 & (1 << 15) > 0)
-        } else {
-            None
-        }
-    }
-    /// Returns the spectral inversion indicator status if present.
-    pub fn spectral_inversion_indicator(&self) -> Option<bool> {
-        if self.spectral_inversion_enabled() {
-            Some(self.0 & (1 << 14) > 0)
-        } else {
-            None
-        }
-    }
+    indicator!(
+        cal_time_indicator,
+        set_cal_time_indicator,
+        31,
+        19,
+        "calibrated time"
+    );
+    indicator!(
+        valid_data_indicator,
+        set_valid_data_indicator,
+        30,
+        18,
+        "valid data"
+    );
+    indicator!(
+        reference_lock_indicator,
+        set_reference_lock_indicator,
+        29,
+        17,
+        "reference lock"
+    );
+    indicator!(
+        agc_indicator,
+        set_agc_indicator,
+        28,
+        16,
+        "automatic gain control (AGC)"
+    );
+    indicator!(
+        detected_signal_indicator,
+        set_detected_signal_indicator,
+        27,
+        15,
+        "detected signal"
+    );
+    indicator!(
+        spectral_inversion_indicator,
+        set_spectral_inversion_indicator,
+        26,
+        14,
+        "spectral inversion"
+    );
     /// Returns the over range indicator status if present.
     pub fn over_range_indicator(&self) -> Option<bool> {
-        if self.over_range_enabled() {
-            Some(self.0 & (1 << 13) > 0)
-        } else {
-            None
-        }
+        EnableValue::from_bits(self.0, 25, 13).into()
     }
-    /// Returns the sample loss indicator status if present.
-    pub fn sample_loss_indicator(&self) -> Option<bool> {
-        if self.sample_loss_enabled() {
-            Some(self.0 & (1 << 12) > 0)
-        } else {
-            None
-        }
+    /// Sets the over range indicator, or (with `None`) disables it.
+    ///
+    /// Over-range events are counted across packets by the context
+    /// over-range count field (see
+    /// [`Cif0Manipulators::increment_over_range_count()`](crate::Cif0Manipulators::increment_over_range_count)).
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::Trailer;
+    /// let mut trailer = Trailer::default();
+    /// trailer.set_over_range_indicator(Some(true));
+    /// assert_eq!(trailer.over_range_indicator(), Some(true));
+    /// trailer.set_over_range_indicator(None);
+    /// assert_eq!(trailer.over_range_indicator(), None);
+    /// ```
+    pub fn set_over_range_indicator(&mut self, over_range: Option<bool>) {
+        EnableValue::from(over_range).write_bits(&mut self.0, 25, 13);
     }
+    indicator!(
+        sample_loss_indicator,
+        set_sample_loss_indicator,
+        24,
+        12,
+        "sample loss"
+    );
     /// Returns the sample frame indicator status if present.
     pub fn sample_frame_indicator(&self) -> Option<SampleFrameIndicator> {
         if self.sample_frame_enabled() {
@@ -180,4 +171,153 @@ impl Trailer {
             None
         }
     }
+    /// Sets the associated context packet count (ANSI/VITA-49.2-2017
+    /// 5.1.6.1): the number of context packets describing this data
+    /// packet. Passing `None` clears the count and its enable (E) bit.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::OutOfRange`] if `count` doesn't fit in 7 bits.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::Trailer;
+    /// let mut trailer = Trailer::default();
+    /// trailer.set_associated_context_packet_count(Some(3)).unwrap();
+    /// assert_eq!(trailer.associated_context_packet_count(), Some(3));
+    /// assert!(trailer.set_associated_context_packet_count(Some(128)).is_err());
+    /// ```
+    pub fn set_associated_context_packet_count(
+        &mut self,
+        count: Option<u8>,
+    ) -> Result<(), VitaError> {
+        if count.is_some_and(|c| c > 0x7F) {
+            return Err(VitaError::OutOfRange);
+        }
+        self.0 &= !0xFF;
+        if let Some(count) = count {
+            self.0 |= (1 << 7) | count as u32;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Trailer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(f, "Trailer:")?;
+        let indicators = [
+            ("Calibrated time", self.cal_time_indicator()),
+            ("Valid data", self.valid_data_indicator()),
+            ("Reference lock", self.reference_lock_indicator()),
+            ("AGC/MGC", self.agc_indicator()),
+            ("Detected signal", self.detected_signal_indicator()),
+            ("Spectral inversion", self.spectral_inversion_indicator()),
+            ("Over-range", self.over_range_indicator()),
+            ("Sample loss", self.sample_loss_indicator()),
+        ];
+        for (name, value) in indicators {
+            if let Some(value) = value {
+                writeln!(f, "  {name}: {value}")?;
+            }
+        }
+        if let Some(sfi) = self.sample_frame_indicator() {
+            writeln!(f, "  Sample frame: {sfi:?}")?;
+        }
+        if let Some(count) = self.associated_context_packet_count() {
+            writeln!(f, "  Associated context packets: {count}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn associated_context_packet_count_round_trip() {
+        // Signal data packet with a trailer carrying a sample frame indicator.
+        let mut bytes = Vrt::new_signal_data_packet().to_bytes().unwrap();
+        bytes[0] |= 0x04;
+        bytes[3] += 1;
+        bytes.extend(0x00C0_0400u32.to_be_bytes());
+        let mut packet = Vrt::try_from(&bytes[..]).unwrap();
+        assert_eq!(
+            packet.trailer().unwrap().associated_context_packet_count(),
+            None
+        );
+
+        let trailer = packet.trailer_mut().unwrap();
+        trailer
+            .set_associated_context_packet_count(Some(5))
+            .unwrap();
+        assert!(matches!(
+            trailer.set_associated_context_packet_count(Some(0x80)),
+            Err(VitaError::OutOfRange)
+        ));
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        let trailer = parsed.trailer().unwrap();
+        assert_eq!(trailer.associated_context_packet_count(), Some(5));
+        // Other trailer fields are untouched.
+        assert_eq!(
+            trailer.sample_frame_indicator(),
+            Some(SampleFrameIndicator::FirstDataPacket)
+        );
+
+        let mut trailer = *trailer;
+        trailer.set_associated_context_packet_count(None).unwrap();
+        assert_eq!(trailer, Trailer::from_u32(0x00C0_0400));
+    }
+
+    #[test]
+    fn display_lists_enabled_indicators() {
+        // Spectral inversion and over-range set; sample loss enabled but clear.
+        let trailer = Trailer::from_u32((0b111 << 24) | (0b11 << 13));
+        let s = trailer.to_string();
+        assert!(s.contains("Spectral inversion: true"));
+        assert!(s.contains("Over-range: true"));
+        assert!(s.contains("Sample loss: false"));
+        assert!(!s.contains("Valid data"));
+    }
+
+    #[test]
+    fn set_indicators() {
+        let mut trailer = Trailer::from_u32(0x00C0_0400);
+        trailer.set_cal_time_indicator(Some(true));
+        trailer.set_valid_data_indicator(Some(false));
+        trailer.set_sample_loss_indicator(Some(true));
+        assert_eq!(trailer, Trailer::from_u32(0xC1C8_1400));
+        assert_eq!(trailer.agc_indicator(), None);
+
+        trailer.set_cal_time_indicator(None);
+        assert_eq!(trailer.cal_time_indicator(), None);
+        assert_eq!(trailer.valid_data_indicator(), Some(false));
+        assert_eq!(trailer, Trailer::from_u32(0x41C0_1400));
+    }
+
+    #[test]
+    fn over_range_indicator_and_count() {
+        let mut trailer = Trailer::from_u32(0x00C0_0400);
+        trailer.set_over_range_indicator(Some(false));
+        assert_eq!(trailer.over_range_indicator(), Some(false));
+        trailer.set_over_range_indicator(Some(true));
+        assert_eq!(trailer.over_range_indicator(), Some(true));
+        // Other trailer fields are untouched.
+        assert_eq!(
+            trailer.sample_frame_indicator(),
+            Some(SampleFrameIndicator::FirstDataPacket)
+        );
+
+        // Each flagged data packet adds to the context count.
+        let mut context = Context::new();
+        for _ in 0..3 {
+            if trailer.over_range_indicator() == Some(true) {
+                context.increment_over_range_count(1);
+            }
+        }
+        assert_eq!(context.over_range_count(), Some(&3));
+        context.increment_over_range_count(u32::MAX);
+        assert_eq!(context.over_range_count(), Some(&u32::MAX));
+    }
 }
@@ -0,0 +1,181 @@
+// SPDX-FileCopyrightText: 2025 The vita49-rs Authors
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Helpers for working with legacy VITA 49.0 equipment.
+
+VITA 49.2 is a superset of VITA 49.0, so a VITA 49.0 packet parses
+like any other. Strict 49.0 mode additionally rejects packets that
+use constructs added in VITA 49.2:
+
+- Command and extension command packet types.
+- The "Not a V49.0" and spectral data header indicator bits.
+- A non-zero class ID pad bit count (reserved in VITA 49.0).
+- CIF1, CIF2, CIF3, and CIF7 in context packets (VITA 49.0 only
+  defines CIF0).
+
+Note that VITA 49.0 treats the trailer's sample frame indicator bits
+as user-defined; they're still available through
+[`Trailer::sample_frame_indicator()`].
+*/
+
+use crate::prelude::*;
+
+impl Vrt {
+    /// Parses a packet and checks that it conforms to VITA 49.0 (see
+    /// [`Vrt::check_v490()`]).
+    ///
+    /// # Errors
+    /// Returns [`VitaError::NotV490`] if the packet uses a VITA 49.2-only
+    /// construct, or an error if the packet can't be parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_signal_payload(&[1, 2, 3, 4])?;
+    /// let bytes = packet.to_bytes()?;
+    /// assert_eq!(Vrt::try_from_v490(&bytes)?, packet);
+    ///
+    /// let bytes = Vrt::new_control_packet().to_bytes()?;
+    /// assert!(matches!(
+    ///     Vrt::try_from_v490(&bytes),
+    ///     Err(VitaError::NotV490(_))
+    /// ));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_from_v490(bytes: &[u8]) -> Result<Vrt, VitaError> {
+        let packet = Vrt::try_from(bytes)?;
+        packet.check_v490()?;
+        Ok(packet)
+    }
+
+    /// Checks that the packet only uses constructs defined by VITA 49.0.
+    /// Rejects command packets, the "Not a V49.0" and spectral data
+    /// header indicators, a non-zero class ID pad bit count, and context
+    /// packets using CIF1, CIF2, CIF3, or CIF7.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::NotV490`] describing the first VITA 49.2-only
+    /// construct found.
+    pub fn check_v490(&self) -> Result<(), VitaError> {
+        match self.header().indicators() {
+            Indicators::SignalData(i) => {
+                if i.not_a_vita490_packet {
+                    return Err(VitaError::NotV490("\"Not a V49.0\" indicator is set"));
+                }
+                if i.signal_spectral_data {
+                    return Err(VitaError::NotV490("spectral data indicator is set"));
+                }
+            }
+            Indicators::Context(i) => {
+                if i.not_a_vita490_packet {
+                    return Err(VitaError::NotV490("\"Not a V49.0\" indicator is set"));
+                }
+            }
+            Indicators::Command(_) => {
+                return Err(VitaError::NotV490("command packets are VITA 49.2 only"));
+            }
+        }
+        if self.class_id().is_some_and(|c| c.pad_bit_count() != 0) {
+            return Err(VitaError::NotV490("class ID pad bit count is set"));
+        }
+        if let Ok(context) = self.payload().context() {
+            let cif0 = Cif0Manipulators::cif0(context);
+            if cif0.cif1_enabled()
+                || cif0.cif2_enabled()
+                || cif0.cif3_enabled()
+                || cif0.field_attributes_enabled()
+            {
+                return Err(VitaError::NotV490("context uses CIFs other than CIF0"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An IF data packet laid out as a VITA 49.0 digitizer sends it:
+    /// stream ID, class ID, UTC/real-time timestamps, two payload words,
+    /// and a trailer reporting calibrated time, valid data and reference
+    /// lock, no AGC, over-range or sample loss, and two associated
+    /// context packets.
+    const V490_DATA_PACKET: [u8; 40] = [
+        0x1C, 0x61, 0x00, 0x0A, // header
+        0x00, 0x00, 0x00, 0x2A, // stream ID
+        0x00, 0x12, 0x34, 0x56, // class ID (OUI)
+        0x00, 0x01, 0x00, 0x02, // class ID (info/packet class codes)
+        0x65, 0x00, 0x00, 0x00, // integer timestamp
+        0x00, 0x00, 0x00, 0x00, // fractional timestamp
+        0x00, 0x00, 0x03, 0xE8, //
+        0x01, 0x02, 0x03, 0x04, // payload
+        0x05, 0x06, 0x07, 0x08, //
+        0xF3, 0x0E, 0x00, 0x82, // trailer
+    ];
+
+    #[test]
+    fn parse_v490_data_packet() {
+        let packet = Vrt::try_from_v490(&V490_DATA_PACKET).unwrap();
+        assert_eq!(packet.header().packet_type(), PacketType::SignalData);
+        assert_eq!(packet.stream_id(), Some(0x2A));
+        assert_eq!(packet.class_id().unwrap().oui(), 0x12_3456);
+        assert_eq!(packet.header().tsi(), Tsi::Utc);
+        assert_eq!(packet.header().tsf(), Tsf::RealTimePs);
+        assert_eq!(packet.signal_payload().unwrap(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+        let trailer = packet.trailer().unwrap();
+        assert_eq!(trailer.cal_time_indicator(), Some(true));
+        assert_eq!(trailer.valid_data_indicator(), Some(true));
+        assert_eq!(trailer.reference_lock_indicator(), Some(true));
+        assert_eq!(trailer.agc_indicator(), Some(false));
+        assert_eq!(trailer.detected_signal_indicator(), None);
+        assert_eq!(trailer.spectral_inversion_indicator(), None);
+        assert_eq!(trailer.over_range_indicator(), Some(false));
+        assert_eq!(trailer.sample_loss_indicator(), Some(false));
+        assert_eq!(trailer.sample_frame_indicator(), None);
+        assert_eq!(trailer.user_defined_indicator(), None);
+        assert_eq!(trailer.associated_context_packet_count(), Some(2));
+        assert_eq!(packet.to_bytes().unwrap(), V490_DATA_PACKET);
+    }
+
+    #[test]
+    fn reject_v492_constructs() {
+        // "Not a V49.0" indicator (header bit 25).
+        let mut bytes = V490_DATA_PACKET;
+        bytes[0] |= 0x02;
+        assert!(Vrt::try_from(&bytes[..]).is_ok());
+        assert!(matches!(
+            Vrt::try_from_v490(&bytes),
+            Err(VitaError::NotV490(_))
+        ));
+
+        // Class ID pad bit count.
+        let mut bytes = V490_DATA_PACKET;
+        bytes[8] = 0x08;
+        assert!(matches!(
+            Vrt::try_from_v490(&bytes),
+            Err(VitaError::NotV490(_))
+        ));
+
+        let mut packet = Vrt::new_context_packet();
+        packet
+            .payload_mut()
+            .context_mut()
+            .unwrap()
+            .set_bandwidth_hz(Some(8e6))
+            .unwrap();
+        packet.update_packet_size();
+        assert!(Vrt::try_from_v490(&packet.to_bytes().unwrap()).is_ok());
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_timestamp_details(Some(crate::TimestampDetails::from_u64(1)));
+        packet.update_packet_size();
+        assert!(matches!(
+            Vrt::try_from_v490(&packet.to_bytes().unwrap()),
+            Err(VitaError::NotV490(_))
+        ));
+    }
+}
@@ -8,9 +8,16 @@ be the main entrypoint for any users of this crate.
 
 use crate::command_prelude::*;
 use crate::prelude::*;
-use crate::Trailer;
+use crate::{ExtensionContext, Trailer, VrtView};
+use alloc::string::String;
+use alloc::vec::Vec;
+use deku::ctx::Endian;
 use deku::prelude::*;
 
+/// Picoseconds per second; real-time fractional timestamps must be
+/// below this.
+pub(crate) const PS_PER_SEC: u64 = 1_000_000_000_000;
+
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, DekuRead, DekuWrite)]
 #[deku(endian = "big")]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -18,29 +25,62 @@ use deku::prelude::*;
 /// of VRT packets.
 pub struct Vrt {
     /// VRT packet header (present on all packets).
+    #[cfg_attr(feature = "serde", serde(rename = "hdr", alias = "header"))]
     header: PacketHeader,
     /// Stream identifier.
     #[deku(cond = "header.stream_id_included()")]
+    #[cfg_attr(feature = "serde", serde(rename = "sid", alias = "stream_id"))]
     stream_id: Option<u32>,
     /// Class identifier.
     #[deku(cond = "header.class_id_included()")]
+    #[cfg_attr(feature = "serde", serde(rename = "cid", alias = "class_id"))]
     class_id: Option<ClassIdentifier>,
     /// Integer timestamp.
     #[deku(cond = "header.integer_timestamp_included()")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "ts_int", alias = "integer_timestamp")
+    )]
     integer_timestamp: Option<u32>,
     /// Fractional timestamp.
     #[deku(cond = "header.fractional_timestamp_included()")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "ts_frac", alias = "fractional_timestamp")
+    )]
     fractional_timestamp: Option<u64>,
     /// Packet payload. For signal data, this would be raw bytes. For
     /// context, this would be context information, etc..
     #[deku(ctx = "header")]
     payload: Payload,
     /// Data trailer.
-    #[deku(cond = "header.trailer_included()")]
+    #[deku(
+        cond = "header.trailer_included()",
+        reader = "Self::read_trailer(deku::reader, header)"
+    )]
     trailer: Option<Trailer>,
 }
 
+/// Byte-swaps each 32-bit word of a whole-word buffer.
+fn swap_words(bytes: &mut [u8]) {
+    for word in bytes.chunks_exact_mut(4) {
+        word.reverse();
+    }
+}
+
 impl Vrt {
+    /// Reads the trailer, rejecting headers whose packet size leaves no
+    /// room for it so the next packet's bytes aren't taken as a trailer.
+    fn read_trailer<R: deku::no_std_io::Read + deku::no_std_io::Seek>(
+        reader: &mut deku::reader::Reader<R>,
+        header: &PacketHeader,
+    ) -> Result<Option<Trailer>, DekuError> {
+        if (header.packet_size() as usize) < header.prologue_size_words() + 1 {
+            return Err(VitaError::TrailerSizeConflict.into());
+        }
+        Ok(Some(Trailer::from_reader_with_ctx(reader, Endian::Big)?))
+    }
+
     /// Produce a new signal data packet with some sane defaults.
     ///
     /// # Example
@@ -69,6 +109,27 @@ impl Vrt {
         ret
     }
 
+    /// Produce a new signal data packet without a stream ID (packet
+    /// type 0).
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let packet = Vrt::new_signal_data_packet_no_stream_id();
+    /// assert_eq!(packet.stream_id(), None);
+    /// assert_eq!(
+    ///     packet.header().packet_type(),
+    ///     PacketType::SignalDataWithoutStreamId
+    /// );
+    /// assert!(!packet.header().stream_id_included());
+    /// ```
+    pub fn new_signal_data_packet_no_stream_id() -> Vrt {
+        let mut ret = Vrt::new_signal_data_packet();
+        ret.set_stream_id(None);
+        ret.update_packet_size();
+        ret
+    }
+
     /// Produce a new context packet with some sane defaults.
     ///
     /// # Example
@@ -76,7 +137,7 @@ impl Vrt {
     /// use vita49::prelude::*;
     /// let mut packet = Vrt::new_context_packet();
     /// let context: &mut Context = packet.payload_mut().context_mut().unwrap();
-    /// context.set_bandwidth_hz(Some(8e6));
+    /// context.set_bandwidth_hz(Some(8e6)).unwrap();
     /// assert_eq!(context.bandwidth_hz(), Some(8e6));
     /// ```
     pub fn new_context_packet() -> Vrt {
@@ -93,6 +154,76 @@ impl Vrt {
         ret
     }
 
+    /// Produce a context packet announcing the parameters of a signal
+    /// data stream: its bandwidth, RF reference frequency, and sample
+    /// rate. The context field change indicator is left unset.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::OutOfRange`] if any of the values is NaN.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let packet = Vrt::context_for_stream(0x1234, 8e6, 100e6, 10e6)?;
+    /// assert_eq!(packet.stream_id(), Some(0x1234));
+    /// let context = packet.payload().context()?;
+    /// assert_eq!(context.sample_rate_sps(), Some(10e6));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn context_for_stream(
+        stream_id: u32,
+        bandwidth_hz: f64,
+        rf_ref_freq_hz: f64,
+        sample_rate_sps: f64,
+    ) -> Result<Vrt, VitaError> {
+        let mut context = Context::new();
+        context.set_context_changed(false);
+        context.set_bandwidth_hz(Some(bandwidth_hz))?;
+        context.set_rf_ref_freq_hz(Some(rf_ref_freq_hz))?;
+        context.set_sample_rate_sps(Some(sample_rate_sps))?;
+
+        let mut ret = Vrt::new_context_packet();
+        ret.set_stream_id(Some(stream_id));
+        ret.payload = Payload::Context(context);
+        ret.update_packet_size();
+        Ok(ret)
+    }
+
+    /// Produce a new extension context packet with an empty payload.
+    /// The payload format is defined by the class ID, so it's handled
+    /// as opaque bytes.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_extension_context_packet();
+    /// packet.set_class_id(Some(ClassIdentifier::default()));
+    /// packet
+    ///     .payload_mut()
+    ///     .extension_context_mut()?
+    ///     .set_payload([0xDE, 0xAD, 0xBE, 0xEF])?;
+    /// packet.update_packet_size();
+    /// assert_eq!(packet.header().packet_type(), PacketType::ExtensionContext);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_extension_context_packet() -> Vrt {
+        let mut ret = Vrt {
+            header: PacketHeader::new_extension_context_header(),
+            stream_id: Some(0),
+            class_id: None,
+            integer_timestamp: None,
+            fractional_timestamp: None,
+            payload: Payload::ExtensionContext(ExtensionContext::new()),
+            trailer: None,
+        };
+        ret.update_packet_size();
+        ret
+    }
+
     /// Produce a new control packet.
     ///
     /// # Example
@@ -101,7 +232,7 @@ impl Vrt {
     /// let mut packet = Vrt::new_control_packet();
     /// let mut command = packet.payload_mut().command_mut().unwrap();
     /// let mut control = command.payload_mut().control_mut().unwrap();
-    /// control.set_bandwidth_hz(Some(8e6));
+    /// control.set_bandwidth_hz(Some(8e6)).unwrap();
     /// assert_eq!(control.bandwidth_hz(), Some(8e6));
     /// ```
     pub fn new_control_packet() -> Vrt {
@@ -204,7 +335,7 @@ impl Vrt {
     /// let mut packet = Vrt::new_query_ack_packet();
     /// let mut command = packet.payload_mut().command_mut().unwrap();
     /// let mut ack = command.payload_mut().query_ack_mut().unwrap();
-    /// ack.set_bandwidth_hz(Some(100e6));
+    /// ack.set_bandwidth_hz(Some(100e6)).unwrap();
     /// assert!(ack.bandwidth_hz().is_some());
     /// ```
     pub fn new_query_ack_packet() -> Vrt {
@@ -221,315 +352,2331 @@ impl Vrt {
         ret
     }
 
-    /// Gets a reference to the packet header.
-    pub fn header(&self) -> &PacketHeader {
-        &self.header
-    }
-    /// Gets a mutable reference to the packet header.
-    pub fn header_mut(&mut self) -> &mut PacketHeader {
-        &mut self.header
-    }
-
-    /// Get the packet stream ID.
+    /// Produce the ACK packets replying to this control or cancellation
+    /// packet: one for each ACK the command's CAM field requests, in
+    /// validation, execution, query order. The stream ID, message ID, and
+    /// controllee/controller identifiers are copied from the command into
+    /// each reply.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::CommandOnly`] if this isn't a command packet,
+    /// [`VitaError::ControlOnly`] if it's an ACK packet, or
+    /// [`VitaError::NoAckRequested`] if the command doesn't request an ACK.
     ///
     /// # Example
     /// ```
     /// use vita49::prelude::*;
-    /// let mut packet = Vrt::new_signal_data_packet();
-    /// packet.set_stream_id(Some(0xDEADBEEF));
-    /// assert_eq!(packet.stream_id(), Some(0xDEADBEEF));
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_control_packet();
+    /// packet.set_stream_id(Some(0x1234));
+    /// let command = packet.payload_mut().command_mut()?;
+    /// command.set_message_id(42);
+    /// let mut cam = command.cam();
+    /// cam.set_validation();
+    /// cam.set_execution();
+    /// command.set_cam(cam);
+    ///
+    /// let replies = packet.make_ack_replies()?;
+    /// assert_eq!(replies.len(), 2);
+    /// for reply in &replies {
+    ///     assert_eq!(reply.stream_id(), Some(0x1234));
+    ///     assert_eq!(reply.payload().command()?.message_id(), 42);
+    /// }
+    /// assert!(replies[0].payload().command()?.payload().validation_ack().is_ok());
+    /// assert!(replies[1].payload().command()?.payload().exec_ack().is_ok());
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn stream_id(&self) -> Option<u32> {
-        self.stream_id
+    pub fn make_ack_replies(&self) -> Result<Vec<Vrt>, VitaError> {
+        let command = self.request_command()?;
+        let cam = command.cam();
+        let requested = [
+            (
+                cam.validation(),
+                Vrt::new_validation_ack_packet as fn() -> Vrt,
+            ),
+            (cam.execution(), Vrt::new_exec_ack_packet),
+            (cam.state(), Vrt::new_query_ack_packet),
+        ];
+        let replies = requested
+            .into_iter()
+            .filter(|(requested, _)| *requested)
+            .map(|(_, new_ack)| self.echo_ids_into(new_ack()))
+            .collect::<Result<Vec<_>, _>>()?;
+        if replies.is_empty() {
+            return Err(VitaError::NoAckRequested);
+        }
+        Ok(replies)
     }
 
-    /// Sets the packet's stream ID. If `None` is passed, the stream ID
-    /// field will be unset.
+    /// Produce an execution ACK replying to `control_packet`, whatever
+    /// ACKs its CAM field requests. Like [`make_ack_replies()`](Vrt::make_ack_replies()),
+    /// the stream ID, message ID, and controllee/controller identifiers
+    /// are echoed so the controller can match the reply to its request.
     ///
-    /// Note: if the packet type does not match after setting/unsetting,
-    /// the packet type will be updated to reflect the change. For example,
-    /// if you did `packet.set_stream_id(1)` on a `PacketType::SignalDataWithoutStreamId`,
-    /// it would change the packet to a `PacketType:SignalData`.
+    /// # Errors
+    /// Returns [`VitaError::CommandOnly`] if `control_packet` isn't a
+    /// command packet, or [`VitaError::ControlOnly`] if it's an ACK packet.
     ///
     /// # Example
     /// ```
-    /// use vita49::prelude::*;
-    /// let mut packet = Vrt::new_signal_data_packet();
-    /// packet.set_stream_id(Some(0xDEADBEEF));
-    /// assert_eq!(packet.stream_id(), Some(0xDEADBEEF));
-    /// assert!(matches!(packet.header().packet_type(), PacketType::SignalData));
-    /// packet.set_stream_id(None);
-    /// assert!(matches!(packet.header().packet_type(), PacketType::SignalDataWithoutStreamId));
+    /// use vita49::{command_prelude::*, prelude::*};
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_control_packet();
+    /// packet.set_stream_id(Some(0x1234));
+    /// let command = packet.payload_mut().command_mut()?;
+    /// command.set_message_id(42);
+    /// command.set_controllee_id(Some(7))?;
+    ///
+    /// let reply = Vrt::exec_ack_for(&packet)?;
+    /// assert_eq!(reply.stream_id(), Some(0x1234));
+    /// let reply = reply.payload().command()?;
+    /// assert_eq!(reply.message_id(), 42);
+    /// assert_eq!(reply.controllee(), Identity::Id(7));
+    /// assert!(reply.payload().exec_ack().is_ok());
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn set_stream_id(&mut self, stream_id: Option<u32>) {
-        self.stream_id = stream_id;
-        if self.stream_id.is_some() {
-            match self.header.packet_type() {
-                PacketType::SignalDataWithoutStreamId => {
-                    self.header.set_packet_type(PacketType::SignalData);
-                }
-                PacketType::ExtensionDataWithoutStreamId => {
-                    self.header.set_packet_type(PacketType::ExtensionData);
-                }
-                _ => (),
-            }
-        } else {
-            match self.header.packet_type() {
-                PacketType::SignalData => {
-                    self.header
-                        .set_packet_type(PacketType::SignalDataWithoutStreamId);
-                }
-                PacketType::ExtensionData => {
-                    self.header
-                        .set_packet_type(PacketType::ExtensionDataWithoutStreamId);
-                }
-                _ => (),
-            }
-        }
+    pub fn exec_ack_for(control_packet: &Vrt) -> Result<Vrt, VitaError> {
+        control_packet.request_command()?;
+        control_packet.echo_ids_into(Vrt::new_exec_ack_packet())
     }
 
-    /// Gets a reference to the packet class identifier.
-    pub fn class_id(&self) -> Option<&ClassIdentifier> {
-        self.class_id.as_ref()
-    }
-    /// Gets the packet class identifier as a mutable reference.
-    pub fn class_id_mut(&mut self) -> Option<&mut ClassIdentifier> {
-        self.class_id.as_mut()
-    }
-    /// Set the packet class identifier.
-    pub fn set_class_id(&mut self, class_id: Option<ClassIdentifier>) {
-        self.class_id = class_id;
-        self.header.set_class_id_included(class_id.is_some());
+    /// Gets the command of a packet that can be replied to with an ACK.
+    fn request_command(&self) -> Result<&Command, VitaError> {
+        let command = self.payload.command()?;
+        if matches!(
+            command.payload(),
+            CommandPayload::ValidationAck(_)
+                | CommandPayload::ExecAck(_)
+                | CommandPayload::QueryAck(_)
+        ) {
+            return Err(VitaError::ControlOnly);
+        }
+        Ok(command)
     }
 
-    /// Gets the integer timestamp field.
-    pub fn integer_timestamp(&self) -> Option<u32> {
-        self.integer_timestamp
+    /// Copies the stream ID, message ID, and controllee/controller
+    /// identifiers of this command packet into the ACK packet `reply`.
+    fn echo_ids_into(&self, mut reply: Vrt) -> Result<Vrt, VitaError> {
+        let command = self.payload.command()?;
+        reply.set_stream_id(self.stream_id);
+        let ack = reply.payload.command_mut()?;
+        ack.set_message_id(command.message_id());
+        ack.set_controllee_id(command.controllee_id())?;
+        ack.set_controllee_uuid(command.controllee_uuid())?;
+        ack.set_controller_id(command.controller_id())?;
+        ack.set_controller_uuid(command.controller_uuid())?;
+        reply.update_packet_size();
+        Ok(reply)
     }
-    /// Sets the integer timestamp field.
-    ///
-    /// When setting this field, you must also provide a [`Tsi`] mode to indicate what
-    /// kind of timestamp is being represented.
+
+    /// Copies the stream ID, class ID, and integer/fractional timestamps
+    /// (along with their TSI/TSF modes) from `src`, leaving this packet's
+    /// type, payload, and trailer untouched. Fields absent in `src` are
+    /// cleared here too, except the stream ID of packet types that always
+    /// include one (see [`set_stream_id()`](Vrt::set_stream_id())).
+    /// Useful when building a reply to a received packet.
     ///
-    /// # Errors
-    /// If a timestamp and tsi mode are passed that don't work together, this function
-    /// will return an error. For example, if `timestamp = Some(123)` and `tsi = Tsi::Null`.
+    /// The packet size is updated to match.
     ///
     /// # Example
     /// ```
     /// use vita49::prelude::*;
     /// # fn main() -> Result<(), VitaError> {
-    /// let mut packet = Vrt::new_signal_data_packet();
-    /// packet.set_integer_timestamp(Some(12345), Tsi::Utc)?;
-    /// assert_eq!(packet.integer_timestamp(), Some(12345));
-    /// # Ok(())
-    /// # }
-    /// ```
-    /// ```should_panic
-    /// use vita49::prelude::*;
-    /// # fn main() -> Result<(), VitaError> {
-    /// let mut packet = Vrt::new_signal_data_packet();
-    /// // This call will return an error
-    /// packet.set_integer_timestamp(Some(12345), Tsi::Null)?;
+    /// let mut request = Vrt::new_context_packet();
+    /// request.set_stream_id(Some(0x1234));
+    /// request.set_integer_timestamp(Some(1_700_000_000), Tsi::Utc)?;
+    ///
+    /// let mut reply = Vrt::new_context_packet();
+    /// reply.copy_prologue_from(&request);
+    /// assert_eq!(reply.stream_id(), Some(0x1234));
+    /// assert_eq!(reply.integer_timestamp(), Some(1_700_000_000));
+    /// assert_eq!(reply.header().tsi(), Tsi::Utc);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn set_integer_timestamp(
-        &mut self,
-        timestamp: Option<u32>,
-        tsi: Tsi,
-    ) -> Result<(), VitaError> {
-        if (timestamp.is_some() && matches!(tsi, Tsi::Null))
-            || (timestamp.is_none() && !matches!(tsi, Tsi::Null))
-        {
-            return Err(VitaError::TimestampModeMismatch);
-        }
-        self.integer_timestamp = timestamp;
-        self.header.set_tsi(tsi);
-        Ok(())
+    pub fn copy_prologue_from(&mut self, src: &Vrt) {
+        self.set_stream_id(src.stream_id);
+        self.set_class_id(src.class_id);
+        self.integer_timestamp = src.integer_timestamp;
+        self.header.set_tsi(src.header.tsi());
+        self.fractional_timestamp = src.fractional_timestamp;
+        self.header.set_tsf(src.header.tsf());
+        self.update_packet_size();
     }
 
-    /// Gets the fractional timestamp field.
-    pub fn fractional_timestamp(&self) -> Option<u64> {
-        self.fractional_timestamp
-    }
-    /// Sets the fractional timestamp field.
+    /// Parses the packet at the start of `bytes`, returning the packet and
+    /// the number of bytes it occupied. Bytes past the packet size given
+    /// in the header are ignored, so this can be called repeatedly to walk
+    /// a buffer holding several packets.
     ///
-    /// When setting this field, you must also provide a [`Tsf`] mode to indicate what
-    /// kind of timestamp is being represented.
+    /// This never panics, whatever the input.
     ///
     /// # Errors
-    /// If a timestamp and tsi mode are passed that don't work together, this function
-    /// will return an error. For example, if `timestamp = Some(123)` and `tsi = Tsi::Null`.
+    /// Returns an error if `bytes` is shorter than the packet, if the
+    /// header is invalid, if the packet can't be parsed, or if the packet
+    /// contents don't fill exactly the packet size given in the header.
     ///
     /// # Example
     /// ```
     /// use vita49::prelude::*;
     /// # fn main() -> Result<(), VitaError> {
     /// let mut packet = Vrt::new_signal_data_packet();
-    /// packet.set_fractional_timestamp(Some(12345), Tsf::SampleCount)?;
-    /// assert_eq!(packet.fractional_timestamp(), Some(12345));
-    /// # Ok(())
-    /// # }
-    /// ```
-    /// ```should_panic
-    /// use vita49::prelude::*;
-    /// # fn main() -> Result<(), VitaError> {
-    /// let mut packet = Vrt::new_signal_data_packet();
-    /// // This call will return an error
-    /// packet.set_fractional_timestamp(Some(12345), Tsf::Null)?;
+    /// packet.set_signal_payload(&[1, 2, 3, 4])?;
+    /// let mut bytes = packet.to_bytes()?;
+    /// bytes.extend(Vrt::new_context_packet().to_bytes()?);
+    ///
+    /// let (first, len) = Vrt::try_parse(&bytes)?;
+    /// assert_eq!(first, packet);
+    /// let (second, _) = Vrt::try_parse(&bytes[len..])?;
+    /// assert!(second.is_context());
     /// # Ok(())
     /// # }
     /// ```
-    pub fn set_fractional_timestamp(
-        &mut self,
-        timestamp: Option<u64>,
-        tsf: Tsf,
-    ) -> Result<(), VitaError> {
-        if (timestamp.is_some() && matches!(tsf, Tsf::Null))
-            || (timestamp.is_none() && !matches!(tsf, Tsf::Null))
-        {
-            return Err(VitaError::TimestampModeMismatch);
+    pub fn try_parse(bytes: &[u8]) -> Result<(Vrt, usize), VitaError> {
+        let view = VrtView::new(bytes)?;
+        let packet_len = view.header().packet_size_bytes();
+        let (packet, read) = Vrt::read_prefix(&bytes[..packet_len])?;
+        if read != packet_len {
+            return Err(VitaError::PacketSizeMismatch {
+                header: view.header().packet_size(),
+                computed: (read / 4) as u16,
+            });
         }
-        self.fractional_timestamp = timestamp;
-        self.header.set_tsf(tsf);
-        Ok(())
-    }
-
-    /// Gets a reference to the payload enumeration.
-    pub fn payload(&self) -> &Payload {
-        &self.payload
-    }
-
-    /// Consumes the struct and returns the inner payload enumeration.
-    pub fn into_payload(self) -> Payload {
-        self.payload
-    }
-
-    /// Gets a mutable reference to the payload enumeration.
-    pub fn payload_mut(&mut self) -> &mut Payload {
-        &mut self.payload
-    }
-
-    /// Gets a reference to the trailer.
-    pub fn trailer(&self) -> Option<&Trailer> {
-        self.trailer.as_ref()
+        Ok((packet, packet_len))
     }
 
-    /// Gets a mutable reference to the trailer.
-    pub fn trailer_mut(&mut self) -> Option<&mut Trailer> {
-        self.trailer.as_mut()
+    /// Reads a packet from the start of `bytes`, returning it along with
+    /// the number of bytes read. Incomplete reads are reported with the
+    /// offset of the field that ran past the end of `bytes`.
+    fn read_prefix(bytes: &[u8]) -> Result<(Vrt, usize), VitaError> {
+        let mut cursor = deku::no_std_io::Cursor::new(bytes);
+        let mut reader = deku::reader::Reader::new(&mut cursor);
+        match Vrt::from_reader_with_ctx(&mut reader, ()) {
+            Ok(packet) => Ok((packet, reader.bits_read / 8)),
+            Err(e) => Err(VitaError::from_deku_at(e, bytes.len(), reader.bits_read)),
+        }
     }
 
-    /// Get a read-only slice of the packet payload.
+    /// Parses the packet at the start of `bytes` like
+    /// [`Vrt::try_parse()`], returning it along with the bytes left over
+    /// after it, so a stream of packets can be parsed in a loop.
     ///
     /// # Errors
-    /// This function should only be used with a signal data packet type. Use
-    /// of this function on other packet types will return an error.
+    /// Returns the same errors as [`Vrt::try_parse()`].
     ///
     /// # Example
     /// ```
     /// use vita49::prelude::*;
     /// # fn main() -> Result<(), VitaError> {
-    /// let mut packet = Vrt::new_signal_data_packet();
-    /// packet.set_signal_payload(&[1, 2, 3, 4, 5, 6, 7, 8])?;
-    /// assert_eq!(packet.signal_payload()?, &[1, 2, 3, 4, 5, 6, 7, 8]);
+    /// let mut bytes = Vrt::new_signal_data_packet().to_bytes()?;
+    /// bytes.extend(Vrt::new_context_packet().to_bytes()?);
+    ///
+    /// let mut rest = &bytes[..];
+    /// let mut packets = Vec::new();
+    /// while !rest.is_empty() {
+    ///     let (packet, tail) = Vrt::from_prefix(rest)?;
+    ///     packets.push(packet);
+    ///     rest = tail;
+    /// }
+    /// assert_eq!(packets.len(), 2);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn signal_payload(&self) -> Result<&[u8], VitaError> {
-        Ok(self.payload.signal_data()?.payload())
+    pub fn from_prefix(bytes: &[u8]) -> Result<(Vrt, &[u8]), VitaError> {
+        let (packet, len) = Vrt::try_parse(bytes)?;
+        Ok((packet, &bytes[len..]))
     }
 
-    /// Set the packet payload to some raw bytes (signal data only).
-    /// Can be an owned `Vec<u8>` (zero-copy) or a `&[u8]` slice which
-    /// will allocate under the hood.
+    /// Parses a packet like [`Vrt::try_from()`], but first rejects any
+    /// header claiming more than `max_words` 32-bit words or more data
+    /// than `bytes` holds, before anything is allocated for the payload.
     ///
     /// # Errors
-    /// This function should only be used with a signal data packet type. Use
-    /// of this function on other packet types will return an error.
+    /// Returns [`VitaError::PacketExceedsLimit`] if the header's packet
+    /// size is above `max_words`, [`VitaError::BufferTooShort`] if
+    /// `bytes` is shorter than the packet,
+    /// [`VitaError::PacketSizeMismatch`] if `bytes` holds more than the
+    /// packet, or any error from [`Vrt::try_parse()`].
     ///
     /// # Example
     /// ```
-    /// # use std::io;
     /// use vita49::prelude::*;
     /// # fn main() -> Result<(), VitaError> {
     /// let mut packet = Vrt::new_signal_data_packet();
-    /// packet.set_signal_payload(&[1, 2, 3, 4, 5, 6, 7, 8])?;
-    /// assert_eq!(packet.signal_payload()?, &[1, 2, 3, 4, 5, 6, 7, 8]);
+    /// packet.set_signal_payload(&[0; 64])?;
+    /// let bytes = packet.to_bytes()?;
+    /// assert_eq!(Vrt::try_from_with_limit(&bytes, 32)?, packet);
+    /// assert!(matches!(
+    ///     Vrt::try_from_with_limit(&bytes, 16),
+    ///     Err(VitaError::PacketExceedsLimit { words: 18, max: 16 })
+    /// ));
     /// # Ok(())
     /// # }
     /// ```
-    pub fn set_signal_payload(&mut self, payload: impl Into<Vec<u8>>) -> Result<(), VitaError> {
-        let sig_data = self.payload.signal_data_mut()?;
-        sig_data.set_payload(payload);
-        self.update_packet_size();
-        Ok(())
+    pub fn try_from_with_limit(bytes: &[u8], max_words: u16) -> Result<Vrt, VitaError> {
+        let header = PacketHeader::from_u32(crate::vrt_view::read_u32(bytes, 0)?);
+        if header.packet_size() > max_words {
+            return Err(VitaError::PacketExceedsLimit {
+                words: header.packet_size(),
+                max: max_words,
+            });
+        }
+        let (packet, read) = Vrt::try_parse(bytes)?;
+        if read != bytes.len() {
+            // Bytes past the end of the packet count as extra contents.
+            return Err(VitaError::PacketSizeMismatch {
+                header: header.packet_size(),
+                computed: u16::try_from((bytes.len() + 3) / 4).unwrap_or(u16::MAX),
+            });
+        }
+        Ok(packet)
     }
 
-    /// Consume the VRT packet and extract the owned signal data payload.
-    /// This avoids cloning the internal vector.
+    /// Serializes the packet like [`DekuContainerWrite::to_bytes()`], but
+    /// first checks that the packet is self-consistent: the header size
+    /// matches the contents and, for command packets, the payload type
+    /// matches the header indicators and CAM field (see
+    /// [`Command::validate()`]).
     ///
     /// # Errors
-    /// This function should only be used with a signal data packet type. Use
-    /// of this function on other packet types will return an error.
+    /// Returns [`VitaError::PacketSizeMismatch`],
+    /// [`VitaError::AmbiguousAckCam`], or
+    /// [`VitaError::CommandPayloadMismatch`] if the checks fail.
     ///
     /// # Example
     /// ```
-    /// # use std::io;
     /// use vita49::prelude::*;
     /// # fn main() -> Result<(), VitaError> {
-    /// let mut packet = Vrt::new_signal_data_packet();
-    /// packet.set_signal_payload(&[1, 2, 3, 4, 5, 6, 7, 8])?;
-    /// let payload = packet.into_signal_payload()?;
-    /// assert_eq!(payload, &[1, 2, 3, 4, 5, 6, 7, 8]);
+    /// let mut packet = Vrt::new_query_ack_packet();
+    /// assert!(packet.checked_to_bytes().is_ok());
+    ///
+    /// let command = packet.payload_mut().command_mut()?;
+    /// let mut cam = command.cam();
+    /// cam.unset_state();
+    /// cam.set_validation();
+    /// command.set_cam(cam);
+    /// assert!(matches!(
+    ///     packet.checked_to_bytes(),
+    ///     Err(VitaError::CommandPayloadMismatch { .. })
+    /// ));
     /// # Ok(())
     /// # }
     /// ```
-    pub fn into_signal_payload(self) -> Result<Vec<u8>, VitaError> {
-        match self.payload {
-            Payload::SignalData(sig) => Ok(sig.into_payload()),
-            _ => Err(VitaError::SignalDataOnly),
+    pub fn checked_to_bytes(&self) -> Result<Vec<u8>, VitaError> {
+        self.validate_size()?;
+        if let Payload::Command(command) = &self.payload {
+            command.check_payload_type(&self.header)?;
         }
+        Ok(self.to_bytes()?)
     }
 
-    /// Update the VRT packet header size field to reflect the current contents of
-    /// the data structure.
-    ///
-    /// This function should be executed after making any changes to a packet (i.e
-    /// after any functions `set_*()`) to make sure the header size is set correctly
-    /// prior to serialization.
+    /// Serializes the packet onto the end of `buf`, returning the number
+    /// of bytes written. Reusing one buffer across packets avoids the
+    /// per-packet allocation of [`DekuContainerWrite::to_bytes()`].
     ///
     /// # Example
     /// ```
     /// use vita49::prelude::*;
-    /// let mut packet = Vrt::new_context_packet();
-    /// let context = packet.payload_mut().context_mut().unwrap();
-    /// context.set_bandwidth_hz(Some(8e6));
-    /// context.set_sample_rate_sps(Some(8e6));
-    /// packet.update_packet_size();
-    /// // ... write the packet
+    /// # fn main() -> Result<(), VitaError> {
+    /// let packet = Vrt::new_context_packet();
+    /// let mut buf = Vec::with_capacity(1500);
+    /// let len = packet.write_into(&mut buf)?;
+    /// assert_eq!(len, packet.packet_size_bytes());
+    /// assert_eq!(buf, packet.to_bytes()?);
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn update_packet_size(&mut self) {
-        let mut packet_size_words = 1;
+    pub fn write_into(&self, buf: &mut Vec<u8>) -> Result<usize, VitaError> {
+        let start = buf.len();
+        let mut cursor = deku::no_std_io::Cursor::new(&mut *buf);
+        cursor.set_position(start as u64);
+        let mut writer = Writer::new(&mut cursor);
+        self.to_writer(&mut writer, ())?;
+        writer.finalize()?;
+        Ok(buf.len() - start)
+    }
+
+    /// Serializes the packet into any [`std::io::Write`], such as a file
+    /// or socket, without setting up a deku [`Writer`] (which also needs
+    /// the output to be seekable). The whole packet is handed to the
+    /// writer with a single `write_all()` call.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let packet = Vrt::new_context_packet();
+    /// let mut out = Vec::new();
+    /// packet.write_to(&mut out)?;
+    /// assert_eq!(out, packet.to_bytes()?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn write_to<W: std::io::Write>(&self, w: &mut W) -> Result<(), VitaError> {
+        let bytes = self.to_bytes()?;
+        w.write_all(&bytes)?;
+        Ok(())
+    }
+
+    /// Reads every packet from a file of back-to-back VRT packets, as
+    /// written by [`Vrt::write_all_to_file()`], through a buffered
+    /// reader. Each packet's header size tells where the next one starts
+    /// (see [`Vrt::try_parse()`]), so only one packet is held in memory
+    /// at a time besides the result.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::Io`] if the file can't be read, or any error
+    /// from [`Vrt::try_parse()`] if a packet is malformed or the file ends
+    /// partway through a packet.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// for packet in Vrt::read_all_from_file("capture.vrt")? {
+    ///     println!("{:?}", packet.header().packet_type());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn read_all_from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Vec<Vrt>, VitaError> {
+        use std::io::Read;
+        let mut r = std::io::BufReader::new(std::fs::File::open(path)?);
+        let mut packets = Vec::new();
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            (&mut r).take(4).read_to_end(&mut buf)?;
+            if buf.is_empty() {
+                return Ok(packets);
+            }
+            if let Ok(word) = <[u8; 4]>::try_from(&buf[..]) {
+                let packet_len =
+                    PacketHeader::from_u32(u32::from_be_bytes(word)).packet_size_bytes();
+                (&mut r)
+                    .take(packet_len.saturating_sub(4) as u64)
+                    .read_to_end(&mut buf)?;
+            }
+            // A short read leaves a truncated packet for try_parse() to
+            // report.
+            let (packet, _) = Vrt::try_parse(&buf)?;
+            packets.push(packet);
+        }
+    }
+
+    /// Writes `packets` back to back into a new file (replacing any
+    /// existing one) through a buffered writer. The file can be read
+    /// back with [`Vrt::read_all_from_file()`].
+    ///
+    /// # Errors
+    /// Returns [`VitaError::Io`] if the file can't be written, or an
+    /// error if a packet can't be serialized.
+    #[cfg(feature = "std")]
+    pub fn write_all_to_file<P: AsRef<std::path::Path>>(
+        path: P,
+        packets: &[Vrt],
+    ) -> Result<(), VitaError> {
+        use std::io::Write;
+        let mut w = std::io::BufWriter::new(std::fs::File::create(path)?);
+        for packet in packets {
+            packet.write_to(&mut w)?;
+        }
+        w.flush()?;
+        Ok(())
+    }
+
+    /// Parses a packet stored as little-endian 32-bit words.
+    ///
+    /// **This is not standard VITA 49**, which is big-endian on the
+    /// wire. It only exists for tooling that stores packets as they'd
+    /// sit in memory on a little-endian host: every 32-bit word of the
+    /// standard encoding is byte-swapped, whatever fields it holds
+    /// (so a 64-bit timestamp is two swapped words, most significant
+    /// word first). Use [`Vrt::try_from()`] for real packets.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::PartialWord`] if `bytes` isn't a whole
+    /// number of 32-bit words, or any error from parsing the packet.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_stream_id(Some(0x1234));
+    /// let bytes = packet.to_bytes_le()?;
+    /// assert_eq!(&bytes[4..8], &[0x34, 0x12, 0, 0]);
+    /// assert_eq!(Vrt::from_bytes_le(&bytes)?, packet);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_bytes_le(bytes: &[u8]) -> Result<Vrt, VitaError> {
+        if bytes.len() % 4 != 0 {
+            return Err(VitaError::PartialWord { len: bytes.len() });
+        }
+        let mut swapped = bytes.to_vec();
+        swap_words(&mut swapped);
+        let (_, packet) = Vrt::from_bytes((&swapped, 0))?;
+        Ok(packet)
+    }
+
+    /// Serializes the packet as little-endian 32-bit words, i.e. the
+    /// standard encoding with every 32-bit word byte-swapped.
+    ///
+    /// **This is not standard VITA 49**; see [`Vrt::from_bytes_le()`].
+    /// Use [`Vrt::to_bytes()`] for packets going on the wire.
+    pub fn to_bytes_le(&self) -> Result<Vec<u8>, VitaError> {
+        let mut bytes = self.to_bytes()?;
+        swap_words(&mut bytes);
+        Ok(bytes)
+    }
+
+    /// Gets a reference to the packet header.
+    pub fn header(&self) -> &PacketHeader {
+        &self.header
+    }
+    /// Gets a mutable reference to the packet header.
+    pub fn header_mut(&mut self) -> &mut PacketHeader {
+        &mut self.header
+    }
+
+    /// Gets the packet size from the header in bytes. This is the
+    /// number of bytes the packet occupies on the wire.
+    pub fn packet_size_bytes(&self) -> usize {
+        self.header.packet_size_bytes()
+    }
+
+    /// Gets the modulo-16 packet count from the header. See
+    /// [`PacketHeader::packet_count()`].
+    pub fn packet_count(&self) -> u8 {
+        self.header.packet_count()
+    }
+    /// Sets the modulo-16 packet count in the header, masking `count` to
+    /// its lower 4 bits. See [`PacketHeader::set_packet_count()`].
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_packet_count(15);
+    /// packet.inc_packet_count();
+    /// assert_eq!(packet.packet_count(), 0);
+    /// ```
+    pub fn set_packet_count(&mut self, count: u8) {
+        self.header.set_packet_count(count);
+    }
+    /// Increments the packet count in the header by one, wrapping at 16.
+    /// See [`PacketHeader::inc_packet_count()`].
+    pub fn inc_packet_count(&mut self) {
+        self.header.inc_packet_count();
+    }
+
+    /// Get the packet stream ID.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_stream_id(Some(0xDEADBEEF));
+    /// assert_eq!(packet.stream_id(), Some(0xDEADBEEF));
+    /// ```
+    pub fn stream_id(&self) -> Option<u32> {
+        self.stream_id
+    }
+
+    /// Sets the packet's stream ID. Passing `None` removes the stream ID
+    /// from signal and extension data packets; context, extension context
+    /// and command packets always include a stream ID, so for them `None`
+    /// leaves the current stream ID in place.
+    ///
+    /// Note: if the packet type does not match after setting/unsetting,
+    /// the packet type will be updated to reflect the change. For example,
+    /// if you did `packet.set_stream_id(1)` on a `PacketType::SignalDataWithoutStreamId`,
+    /// it would change the packet to a `PacketType:SignalData`.
+    ///
+    /// The packet size is updated to match.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_stream_id(Some(0xDEADBEEF));
+    /// assert_eq!(packet.stream_id(), Some(0xDEADBEEF));
+    /// assert!(matches!(packet.header().packet_type(), PacketType::SignalData));
+    /// packet.set_stream_id(None);
+    /// assert!(matches!(packet.header().packet_type(), PacketType::SignalDataWithoutStreamId));
+    ///
+    /// let mut context = Vrt::new_context_packet();
+    /// context.set_stream_id(Some(7));
+    /// context.set_stream_id(None);
+    /// assert_eq!(context.stream_id(), Some(7));
+    /// ```
+    pub fn set_stream_id(&mut self, stream_id: Option<u32>) {
+        let packet_type = match (self.header.packet_type(), stream_id.is_some()) {
+            (PacketType::SignalDataWithoutStreamId, true) => PacketType::SignalData,
+            (PacketType::ExtensionDataWithoutStreamId, true) => PacketType::ExtensionData,
+            (PacketType::SignalData, false) => PacketType::SignalDataWithoutStreamId,
+            (PacketType::ExtensionData, false) => PacketType::ExtensionDataWithoutStreamId,
+            (packet_type, _) => packet_type,
+        };
+        self.header.set_packet_type(packet_type);
+        if stream_id.is_none() && self.header.stream_id_included() {
+            return;
+        }
+        self.stream_id = stream_id;
+        self.update_packet_size();
+    }
+
+    /// Gets a reference to the packet class identifier.
+    pub fn class_id(&self) -> Option<&ClassIdentifier> {
+        self.class_id.as_ref()
+    }
+    /// Gets the packet class identifier as a mutable reference.
+    pub fn class_id_mut(&mut self) -> Option<&mut ClassIdentifier> {
+        self.class_id.as_mut()
+    }
+    /// Set the packet class identifier. This also sets the class ID
+    /// included bit in the header and updates the packet size to match.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_class_id(Some(ClassIdentifier::new(0xFF5654, 0x1, 0x2)));
+    /// assert!(packet.header().class_id_included());
+    /// assert_eq!(packet.header().packet_size(), 4);
+    /// ```
+    pub fn set_class_id(&mut self, class_id: Option<ClassIdentifier>) {
+        self.class_id = class_id;
+        self.header.set_class_id_included(class_id.is_some());
+        self.update_packet_size();
+    }
+
+    /// Sets or clears the "Not a V49.0 Packet" header indicator of a
+    /// signal or extension data packet. When set, receivers know the
+    /// packet follows VITA 49.2 rules (e.g. for interpreting the trailer)
+    /// rather than VITA 49.0.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::SignalDataOnly`] if the packet isn't a data
+    /// packet.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::{prelude::*, Indicators};
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_not_a_vita490(true)?;
+    /// let Indicators::SignalData(i) = packet.header().indicators() else {
+    ///     unreachable!()
+    /// };
+    /// assert!(i.not_a_vita490_packet);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_not_a_vita490(&mut self, not_a_vita490: bool) -> Result<(), VitaError> {
+        let Indicators::SignalData(mut indicators) = self.header.indicators() else {
+            return Err(VitaError::SignalDataOnly);
+        };
+        indicators.not_a_vita490_packet = not_a_vita490;
+        self.header
+            .set_indicators(Indicators::SignalData(indicators));
+        Ok(())
+    }
+
+    /// Gets the integer timestamp field.
+    pub fn integer_timestamp(&self) -> Option<u32> {
+        self.integer_timestamp
+    }
+    /// Sets the integer timestamp field.
+    ///
+    /// When setting this field, you must also provide a [`Tsi`] mode to indicate what
+    /// kind of timestamp is being represented. The packet size is updated
+    /// to match.
+    ///
+    /// # Errors
+    /// If a timestamp and tsi mode are passed that don't work together, this function
+    /// will return an error. For example, if `timestamp = Some(123)` and `tsi = Tsi::Null`.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_integer_timestamp(Some(12345), Tsi::Utc)?;
+    /// assert_eq!(packet.integer_timestamp(), Some(12345));
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ```should_panic
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// // This call will return an error
+    /// packet.set_integer_timestamp(Some(12345), Tsi::Null)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_integer_timestamp(
+        &mut self,
+        timestamp: Option<u32>,
+        tsi: Tsi,
+    ) -> Result<(), VitaError> {
+        if (timestamp.is_some() && matches!(tsi, Tsi::Null))
+            || (timestamp.is_none() && !matches!(tsi, Tsi::Null))
+        {
+            return Err(VitaError::TimestampModeMismatch);
+        }
+        self.integer_timestamp = timestamp;
+        self.header.set_tsi(tsi);
+        self.update_packet_size();
+        Ok(())
+    }
+
+    /// Gets the integer timestamp as UTC seconds since the Unix epoch,
+    /// interpreting it with the header's TSI mode. GPS timestamps are
+    /// converted with [`gps_to_utc()`](crate::gps_to_utc) using the given
+    /// GPS-UTC `leap_seconds` offset (usually
+    /// [`GPS_LEAP_SECONDS`](crate::GPS_LEAP_SECONDS)); it's ignored for
+    /// UTC timestamps.
+    ///
+    /// Returns `None` if there's no integer timestamp or the TSI mode is
+    /// [`Tsi::Other`].
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::{prelude::*, GPS_LEAP_SECONDS};
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// // 2017-01-01T00:00:00Z in GPS seconds.
+    /// packet.set_integer_timestamp(Some(1_167_264_018), Tsi::Gps)?;
+    /// assert_eq!(
+    ///     packet.integer_timestamp_unix_secs(GPS_LEAP_SECONDS),
+    ///     Some(1_483_228_800)
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn integer_timestamp_unix_secs(&self, leap_seconds: i64) -> Option<i64> {
+        crate::gps_time::tsi_to_unix_secs(self.integer_timestamp?, self.header.tsi(), leap_seconds)
+    }
+
+    /// Sets the integer timestamp from UTC seconds since the Unix epoch,
+    /// encoded for the given `tsi` mode. GPS timestamps are converted
+    /// with [`utc_to_gps()`](crate::utc_to_gps) using the given GPS-UTC
+    /// `leap_seconds` offset; it's ignored for UTC timestamps.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::TimestampModeMismatch`] if `tsi` isn't
+    /// [`Tsi::Utc`] or [`Tsi::Gps`], or [`VitaError::OutOfRange`] if the
+    /// time can't be represented from that epoch.
+    pub fn set_integer_timestamp_unix_secs(
+        &mut self,
+        unix_secs: i64,
+        tsi: Tsi,
+        leap_seconds: i64,
+    ) -> Result<(), VitaError> {
+        let secs = crate::gps_time::unix_secs_to_tsi(unix_secs, tsi, leap_seconds)?;
+        self.set_integer_timestamp(Some(secs), tsi)
+    }
+
+    /// Gets the integer timestamp as a wall-clock time. See
+    /// [`integer_timestamp_unix_secs()`](Vrt::integer_timestamp_unix_secs()).
+    #[cfg(feature = "jiff")]
+    pub fn integer_timestamp_jiff(&self, leap_seconds: i64) -> Option<jiff::Timestamp> {
+        jiff::Timestamp::from_second(self.integer_timestamp_unix_secs(leap_seconds)?).ok()
+    }
+
+    /// Sets the integer timestamp from a wall-clock time, dropping
+    /// subsecond precision. See
+    /// [`set_integer_timestamp_unix_secs()`](Vrt::set_integer_timestamp_unix_secs()).
+    #[cfg(feature = "jiff")]
+    pub fn set_integer_timestamp_jiff(
+        &mut self,
+        time: jiff::Timestamp,
+        tsi: Tsi,
+        leap_seconds: i64,
+    ) -> Result<(), VitaError> {
+        self.set_integer_timestamp_unix_secs(time.as_second(), tsi, leap_seconds)
+    }
+
+    /// Sets the TSI mode without changing the integer timestamp value.
+    ///
+    /// Setting [`Tsi::Null`] clears the integer timestamp field. Setting any
+    /// other mode on a packet without an integer timestamp adds one with
+    /// a value of 0 so the packet stays serializable. The packet size is
+    /// updated to match.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_tsi(Tsi::Gps);
+    /// assert_eq!(packet.header().tsi(), Tsi::Gps);
+    /// assert_eq!(packet.integer_timestamp(), Some(0));
+    /// packet.set_tsi(Tsi::Null);
+    /// assert_eq!(packet.integer_timestamp(), None);
+    /// ```
+    pub fn set_tsi(&mut self, tsi: Tsi) {
+        match tsi {
+            Tsi::Null => self.integer_timestamp = None,
+            _ => self.integer_timestamp = Some(self.integer_timestamp.unwrap_or(0)),
+        }
+        self.header.set_tsi(tsi);
+        self.update_packet_size();
+    }
+
+    /// Gets the fractional timestamp field.
+    pub fn fractional_timestamp(&self) -> Option<u64> {
+        self.fractional_timestamp
+    }
+    /// Sets the fractional timestamp field.
+    ///
+    /// When setting this field, you must also provide a [`Tsf`] mode to indicate what
+    /// kind of timestamp is being represented. The packet size is updated
+    /// to match.
+    ///
+    /// # Errors
+    /// If a timestamp and tsi mode are passed that don't work together, this function
+    /// will return an error. For example, if `timestamp = Some(123)` and `tsi = Tsi::Null`.
+    /// In [`Tsf::RealTimePs`] mode, values of one second (10^12 ps) or more
+    /// return [`VitaError::FractionalTimestampOutOfRange`].
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_fractional_timestamp(Some(12345), Tsf::SampleCount)?;
+    /// assert_eq!(packet.fractional_timestamp(), Some(12345));
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ```should_panic
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// // This call will return an error
+    /// packet.set_fractional_timestamp(Some(12345), Tsf::Null)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_fractional_timestamp(
+        &mut self,
+        timestamp: Option<u64>,
+        tsf: Tsf,
+    ) -> Result<(), VitaError> {
+        if (timestamp.is_some() && matches!(tsf, Tsf::Null))
+            || (timestamp.is_none() && !matches!(tsf, Tsf::Null))
+        {
+            return Err(VitaError::TimestampModeMismatch);
+        }
+        if let (Some(ps), Tsf::RealTimePs) = (timestamp, tsf) {
+            if ps >= PS_PER_SEC {
+                return Err(VitaError::FractionalTimestampOutOfRange(ps));
+            }
+        }
+        self.fractional_timestamp = timestamp;
+        self.header.set_tsf(tsf);
+        self.update_packet_size();
+        Ok(())
+    }
+
+    /// Sets the TSF mode without changing the fractional timestamp value.
+    ///
+    /// Setting [`Tsf::Null`] clears the fractional timestamp field. Setting
+    /// any other mode on a packet without a fractional timestamp adds one
+    /// with a value of 0 so the packet stays serializable. Switching to
+    /// [`Tsf::RealTimePs`] also resets a value of one second (10^12 ps) or
+    /// more to 0, as it isn't a valid picosecond count. The packet size is
+    /// updated to match.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_tsf(Tsf::RealTimePs);
+    /// assert_eq!(packet.header().tsf(), Tsf::RealTimePs);
+    /// assert_eq!(packet.fractional_timestamp(), Some(0));
+    ///
+    /// packet.set_fractional_timestamp(Some(2_000_000_000_000), Tsf::SampleCount)?;
+    /// packet.set_tsf(Tsf::RealTimePs);
+    /// assert_eq!(packet.fractional_timestamp(), Some(0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_tsf(&mut self, tsf: Tsf) {
+        match tsf {
+            Tsf::Null => self.fractional_timestamp = None,
+            Tsf::RealTimePs => {
+                self.fractional_timestamp = Some(
+                    self.fractional_timestamp
+                        .filter(|&ps| ps < PS_PER_SEC)
+                        .unwrap_or(0),
+                )
+            }
+            _ => self.fractional_timestamp = Some(self.fractional_timestamp.unwrap_or(0)),
+        }
+        self.header.set_tsf(tsf);
+        self.update_packet_size();
+    }
+
+    /// Gets a reference to the payload enumeration.
+    pub fn payload(&self) -> &Payload {
+        &self.payload
+    }
+
+    /// Consumes the struct and returns the inner payload enumeration.
+    pub fn into_payload(self) -> Payload {
+        self.payload
+    }
+
+    /// Gets a mutable reference to the payload enumeration.
+    pub fn payload_mut(&mut self) -> &mut Payload {
+        &mut self.payload
+    }
+
+    /// Returns true if this is a signal data packet.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let packet = Vrt::new_signal_data_packet();
+    /// assert!(packet.is_signal_data());
+    /// assert!(!packet.is_context());
+    /// ```
+    pub fn is_signal_data(&self) -> bool {
+        matches!(self.payload, Payload::SignalData(_))
+    }
+
+    /// Returns true if this is a context packet.
+    pub fn is_context(&self) -> bool {
+        matches!(self.payload, Payload::Context(_))
+    }
+
+    /// Returns true if this is an extension context packet.
+    pub fn is_extension_context(&self) -> bool {
+        matches!(self.payload, Payload::ExtensionContext(_))
+    }
+
+    /// Returns true if this is a command packet (control, cancellation,
+    /// or any type of ACK).
+    pub fn is_command(&self) -> bool {
+        matches!(self.payload, Payload::Command(_))
+    }
+
+    /// Returns true if this is a control packet.
+    pub fn is_control(&self) -> bool {
+        matches!(
+            self.payload,
+            Payload::Command(ref c) if matches!(c.payload(), CommandPayload::Control(_))
+        )
+    }
+
+    /// Returns true if this is a cancellation packet.
+    pub fn is_cancellation(&self) -> bool {
+        matches!(
+            self.payload,
+            Payload::Command(ref c) if matches!(c.payload(), CommandPayload::Cancellation(_))
+        )
+    }
+
+    /// Returns true if this is a validation, execution, or query ACK packet.
+    ///
+    /// As when parsing, the CAM field must select exactly one of the
+    /// validation, execution, or state ACK types.
+    pub fn is_ack(&self) -> bool {
+        let Payload::Command(command) = &self.payload else {
+            return false;
+        };
+        let cam = command.cam();
+        let exclusive = [cam.validation(), cam.execution(), cam.state()]
+            .iter()
+            .filter(|&x| *x)
+            .count()
+            == 1;
+        exclusive
+            && matches!(
+                command.payload(),
+                CommandPayload::ValidationAck(_)
+                    | CommandPayload::ExecAck(_)
+                    | CommandPayload::QueryAck(_)
+            )
+    }
+
+    /// Gets a reference to the trailer.
+    pub fn trailer(&self) -> Option<&Trailer> {
+        self.trailer.as_ref()
+    }
+
+    /// Gets a mutable reference to the trailer.
+    pub fn trailer_mut(&mut self) -> Option<&mut Trailer> {
+        self.trailer.as_mut()
+    }
+
+    /// Sets or removes the trailer of a signal or extension data packet.
+    /// This also sets or clears the trailer-included header indicator and
+    /// updates the packet size to match.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::SignalDataOnly`] if the packet isn't a data
+    /// packet.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::{prelude::*, Trailer};
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_trailer(Some(Trailer::default()))?;
+    /// assert!(packet.header().trailer_included());
+    /// assert_eq!(packet.header().packet_size(), 3);
+    /// packet.set_trailer(None)?;
+    /// assert!(!packet.header().trailer_included());
+    /// assert_eq!(packet.header().packet_size(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_trailer(&mut self, trailer: Option<Trailer>) -> Result<(), VitaError> {
+        let Indicators::SignalData(mut indicators) = self.header.indicators() else {
+            return Err(VitaError::SignalDataOnly);
+        };
+        indicators.trailer_included = trailer.is_some();
+        self.header
+            .set_indicators(Indicators::SignalData(indicators));
+        self.trailer = trailer;
+        self.update_packet_size();
+        Ok(())
+    }
+
+    /// Get a read-only slice of the packet payload.
+    ///
+    /// # Errors
+    /// This function should only be used with a signal data packet type. Use
+    /// of this function on other packet types will return an error.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_signal_payload(&[1, 2, 3, 4, 5, 6, 7, 8])?;
+    /// assert_eq!(packet.signal_payload()?, &[1, 2, 3, 4, 5, 6, 7, 8]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn signal_payload(&self) -> Result<&[u8], VitaError> {
+        Ok(self.payload.signal_data()?.payload())
+    }
+
+    /// Set the packet payload to some raw bytes (signal data only).
+    /// Can be an owned `Vec<u8>` (zero-copy) or a `&[u8]` slice which
+    /// will allocate under the hood.
+    ///
+    /// # Errors
+    /// This function should only be used with a signal data packet type. Use
+    /// of this function on other packet types will return an error.
+    ///
+    /// Returns [`VitaError::PayloadTooLarge`] (leaving the packet unchanged)
+    /// if the packet would exceed the maximum packet size of 65535 words.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::io;
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_signal_payload(&[1, 2, 3, 4, 5, 6, 7, 8])?;
+    /// assert_eq!(packet.signal_payload()?, &[1, 2, 3, 4, 5, 6, 7, 8]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_signal_payload(&mut self, payload: impl Into<Vec<u8>>) -> Result<(), VitaError> {
+        let payload = payload.into();
+        let words = self.prologue_words() + (payload.len() + 3) / 4;
+        let sig_data = self.payload.signal_data_mut()?;
+        if words > u16::MAX as usize {
+            return Err(VitaError::PayloadTooLarge { words });
+        }
+        sig_data.set_payload(payload)?;
+        self.update_packet_size();
+        Ok(())
+    }
+
+    /// Consume the VRT packet and extract the owned signal data payload.
+    /// This avoids cloning the internal vector.
+    ///
+    /// # Errors
+    /// This function should only be used with a signal data packet type. Use
+    /// of this function on other packet types will return an error.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::io;
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_signal_payload(&[1, 2, 3, 4, 5, 6, 7, 8])?;
+    /// let payload = packet.into_signal_payload()?;
+    /// assert_eq!(payload, &[1, 2, 3, 4, 5, 6, 7, 8]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_signal_payload(self) -> Result<Vec<u8>, VitaError> {
+        match self.payload {
+            Payload::SignalData(sig) => Ok(sig.into_payload()),
+            _ => Err(VitaError::SignalDataOnly),
+        }
+    }
+
+    /// Update the VRT packet header size field to reflect the current contents of
+    /// the data structure.
+    ///
+    /// This function should be executed after making any changes to a packet (i.e
+    /// after any functions `set_*()`) to make sure the header size is set correctly
+    /// prior to serialization.
+    ///
+    /// Only the size field is changed. If the CIF fields were edited
+    /// directly (rather than through their setters), run
+    /// [`Vrt::sync_indicators()`] first.
+    ///
+    /// If the packet has grown past the 65535-word maximum, the size field
+    /// is left as it was, so [`Vrt::validate_size()`] and
+    /// [`Vrt::checked_to_bytes()`] report the problem. Use
+    /// [`Vrt::try_update_packet_size()`] to get the error directly.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut packet = Vrt::new_context_packet();
+    /// let context = packet.payload_mut().context_mut().unwrap();
+    /// context.set_bandwidth_hz(Some(8e6)).unwrap();
+    /// context.set_sample_rate_sps(Some(8e6)).unwrap();
+    /// packet.update_packet_size();
+    /// // ... write the packet
+    /// ```
+    pub fn update_packet_size(&mut self) {
+        let _ = self.try_update_packet_size();
+    }
+
+    /// Like [`Vrt::update_packet_size()`], but fails if the packet is
+    /// too large for the packet size field.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::PayloadTooLarge`] (leaving the size field
+    /// unchanged) if the packet is more than 65535 words long.
+    pub fn try_update_packet_size(&mut self) -> Result<(), VitaError> {
+        let packet_size_words = self.computed_size_words()?;
+        self.header.set_packet_size(packet_size_words);
+        Ok(())
+    }
+
+    /// Brings the CIF indicator bits of context, control, and query ACK
+    /// payloads in line with the data fields actually present, so fields
+    /// edited directly can't leave a stale bit set (or a set field
+    /// unflagged). Call [`Vrt::update_packet_size()`] afterwards.
+    ///
+    /// Control packets in Action Mode 0 ([`ActionMode::NoAction`](crate::ActionMode::NoAction))
+    /// carry only the indicator words, so their indicator bits are left
+    /// alone.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// let mut packet = Vrt::new_context_packet();
+    /// let context = packet.payload_mut().context_mut().unwrap();
+    /// context.set_bandwidth_hz(Some(8e6)).unwrap();
+    /// context.cif0_fields_mut().bandwidth = None;
+    /// assert_eq!(context.present_fields(), ["bandwidth"]);
+    /// packet.sync_indicators();
+    /// packet.update_packet_size();
+    /// let context = packet.payload().context().unwrap();
+    /// assert!(context.present_fields().is_empty());
+    /// ```
+    pub fn sync_indicators(&mut self) {
+        self.payload.sync_indicators();
+    }
+
+    /// Computes the packet size (in 32-bit words) from the packet's
+    /// current contents without modifying the header.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::PayloadTooLarge`] if the packet is more than
+    /// 65535 words long, i.e. too large for the packet size field.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_context_packet();
+    /// let context = packet.payload_mut().context_mut().unwrap();
+    /// context.set_bandwidth_hz(Some(8e6)).unwrap();
+    /// assert_ne!(packet.computed_size_words()?, packet.header().packet_size());
+    /// packet.update_packet_size();
+    /// assert_eq!(packet.computed_size_words()?, packet.header().packet_size());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn computed_size_words(&self) -> Result<u16, VitaError> {
+        let words = self.size_words();
+        u16::try_from(words).map_err(|_| VitaError::PayloadTooLarge { words })
+    }
+
+    /// Computes the packet size in 32-bit words without limiting it to
+    /// what the packet size field can hold.
+    fn size_words(&self) -> usize {
+        self.prologue_words() + self.payload.size_words()
+    }
+
+    /// Gets the number of 32-bit words taken by everything but the
+    /// payload: the header, stream ID, class ID, timestamps and trailer.
+    fn prologue_words(&self) -> usize {
+        let mut words = self.header.prologue_size_words();
+        if self.header.trailer_included() {
+            words += 1;
+        }
+        words
+    }
+
+    /// Gets the serialized length of the packet in bytes, computed from
+    /// its contents like [`Vrt::computed_size_words()`] rather than read
+    /// from the (possibly stale) header packet size field. Useful for
+    /// sizing buffers without serializing.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_signal_payload(&[0; 64])?;
+    /// assert_eq!(packet.len_bytes(), packet.to_bytes()?.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn len_bytes(&self) -> usize {
+        self.size_words() * 4
+    }
+
+    /// Checks that the packet size field in the header matches the
+    /// size computed from the packet's contents.
+    ///
+    /// # Errors
+    /// Returns [`VitaError::PacketSizeMismatch`] if the sizes differ, or
+    /// [`VitaError::PayloadTooLarge`] if the packet is too large for the
+    /// packet size field.
+    pub fn validate_size(&self) -> Result<(), VitaError> {
+        let computed = self.computed_size_words()?;
+        let header = self.header.packet_size();
+        if header != computed {
+            return Err(VitaError::PacketSizeMismatch { header, computed });
+        }
+        Ok(())
+    }
+
+    /// Gives a short human-readable summary of the packet: its type,
+    /// stream ID, payload size and, if included, the trailer indicators.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_stream_id(Some(0x1234));
+    /// packet.set_signal_payload(vec![1, 2, 3, 4])?;
+    /// let description = packet.describe();
+    /// assert!(description.contains("Stream ID: 0x00001234"));
+    /// assert!(description.contains("Payload: 4 bytes"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn describe(&self) -> String {
+        use core::fmt::Write;
+
+        let mut ret = String::new();
+        writeln!(ret, "Packet type: {:?}", self.header.packet_type()).unwrap();
+        if let Some(sid) = self.stream_id {
+            writeln!(ret, "Stream ID: {sid:#010x}").unwrap();
+        }
+        writeln!(ret, "Payload: {} bytes", self.payload.payload_size_bytes()).unwrap();
+        if let Some(trailer) = &self.trailer {
+            write!(ret, "{trailer}").unwrap();
+        }
+        ret
+    }
+
+    /// Serializes the packet and formats it as a hex dump, one
+    /// section per packet field with byte offsets on each line.
+    ///
+    /// # Example
+    /// ```
+    /// use vita49::prelude::*;
+    /// # fn main() -> Result<(), VitaError> {
+    /// let mut packet = Vrt::new_signal_data_packet();
+    /// packet.set_signal_payload(vec![1, 2, 3, 4])?;
+    /// assert_eq!(
+    ///     packet.hexdump()?,
+    ///     "header\n  0000: 10 00 00 03\n\
+    ///      stream ID\n  0004: 00 00 00 00\n\
+    ///      payload\n  0008: 01 02 03 04\n"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn hexdump(&self) -> Result<String, VitaError> {
+        use core::fmt::Write;
+
+        let bytes = self.to_bytes()?;
+        let mut sections = vec![("header", 4)];
         if self.header.stream_id_included() {
-            packet_size_words += 1;
+            sections.push(("stream ID", 4));
         }
         if self.header.class_id_included() {
-            packet_size_words += 2;
+            sections.push(("class ID", 8));
         }
         if self.header.integer_timestamp_included() {
-            packet_size_words += 1;
+            sections.push(("integer timestamp", 4));
         }
         if self.header.fractional_timestamp_included() {
-            packet_size_words += 2;
+            sections.push(("fractional timestamp", 8));
         }
-        if self.header.trailer_included() {
-            packet_size_words += 1;
+        let trailer_len = if self.header.trailer_included() { 4 } else { 0 };
+        let prologue_len: usize = sections.iter().map(|(_, len)| len).sum();
+        sections.push((
+            "payload",
+            bytes.len().saturating_sub(prologue_len + trailer_len),
+        ));
+        if trailer_len > 0 {
+            sections.push(("trailer", trailer_len));
         }
 
-        packet_size_words += self.payload.size_words();
+        let mut ret = String::new();
+        let mut offset = 0;
+        for (name, len) in sections {
+            if len == 0 {
+                continue;
+            }
+            let end = (offset + len).min(bytes.len());
+            writeln!(ret, "{name}").unwrap();
+            for (i, line) in bytes[offset..end].chunks(16).enumerate() {
+                write!(ret, "  {:04x}:", offset + i * 16).unwrap();
+                for (j, byte) in line.iter().enumerate() {
+                    if j > 0 && j % 4 == 0 {
+                        ret.push(' ');
+                    }
+                    write!(ret, " {byte:02x}").unwrap();
+                }
+                ret.push('\n');
+            }
+            offset = end;
+        }
+        Ok(ret)
+    }
+}
 
-        self.header.set_packet_size(packet_size_words);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packet_predicates() {
+        // (packet, signal data, context, command, control, cancellation, ack)
+        let cases = [
+            (
+                Vrt::new_signal_data_packet(),
+                [true, false, false, false, false, false],
+            ),
+            (
+                Vrt::new_context_packet(),
+                [false, true, false, false, false, false],
+            ),
+            (
+                Vrt::new_control_packet(),
+                [false, false, true, true, false, false],
+            ),
+            (
+                Vrt::new_cancellation_packet(),
+                [false, false, true, false, true, false],
+            ),
+            (
+                Vrt::new_validation_ack_packet(),
+                [false, false, true, false, false, true],
+            ),
+            (
+                Vrt::new_exec_ack_packet(),
+                [false, false, true, false, false, true],
+            ),
+            (
+                Vrt::new_query_ack_packet(),
+                [false, false, true, false, false, true],
+            ),
+        ];
+        for (packet, expected) in cases {
+            let actual = [
+                packet.is_signal_data(),
+                packet.is_context(),
+                packet.is_command(),
+                packet.is_control(),
+                packet.is_cancellation(),
+                packet.is_ack(),
+            ];
+            assert_eq!(actual, expected, "{:?}", packet.header().packet_type());
+        }
+    }
+
+    #[test]
+    fn ack_requires_exclusive_cam() {
+        let mut packet = Vrt::new_validation_ack_packet();
+        let command = packet.payload_mut().command_mut().unwrap();
+        let mut cam = command.cam();
+        cam.set_execution();
+        command.set_cam(cam);
+        assert!(!packet.is_ack());
+    }
+
+    #[test]
+    fn ack_replies_match_command() {
+        let mut packet = Vrt::new_control_packet();
+        packet.set_stream_id(Some(0xABCD));
+        let command = packet.payload_mut().command_mut().unwrap();
+        command.set_message_id(0x5555);
+        command.set_controllee_id(Some(7)).unwrap();
+        command.set_controller_uuid(Some(0x1234_5678)).unwrap();
+        assert!(matches!(
+            packet.make_ack_replies(),
+            Err(VitaError::NoAckRequested)
+        ));
+
+        type CamSetter = fn(&mut ControlAckMode);
+        type AckCheck = fn(&CommandPayload) -> bool;
+        let cases: [(CamSetter, AckCheck); 3] = [
+            (ControlAckMode::set_validation, |p| {
+                p.validation_ack().is_ok()
+            }),
+            (ControlAckMode::set_execution, |p| p.exec_ack().is_ok()),
+            (ControlAckMode::set_state, |p| p.query_ack().is_ok()),
+        ];
+        let check_reply = |reply: &Vrt, is_expected_ack: AckCheck| {
+            assert!(reply.is_ack());
+            assert_eq!(reply.stream_id(), Some(0xABCD));
+            assert!(reply.validate_size().is_ok());
+            let reply = reply.payload().command().unwrap();
+            assert!(is_expected_ack(reply.payload()));
+            assert_eq!(reply.message_id(), 0x5555);
+            assert_eq!(reply.controllee_id(), Some(7));
+            assert_eq!(reply.controller_uuid(), Some(0x1234_5678));
+        };
+        for (set_ack, is_expected_ack) in cases {
+            let mut packet = packet.clone();
+            let command = packet.payload_mut().command_mut().unwrap();
+            let mut cam = command.cam();
+            set_ack(&mut cam);
+            command.set_cam(cam);
+
+            let replies = packet.make_ack_replies().unwrap();
+            assert_eq!(replies.len(), 1);
+            check_reply(&replies[0], is_expected_ack);
+        }
+
+        // A CAM requesting every ACK gets every reply.
+        let command = packet.payload_mut().command_mut().unwrap();
+        let mut cam = command.cam();
+        for (set_ack, _) in cases {
+            set_ack(&mut cam);
+        }
+        command.set_cam(cam);
+        let replies = packet.make_ack_replies().unwrap();
+        assert_eq!(replies.len(), 3);
+        for (reply, (_, is_expected_ack)) in replies.iter().zip(cases) {
+            check_reply(reply, is_expected_ack);
+        }
+
+        let ack = Vrt::new_exec_ack_packet();
+        assert!(matches!(
+            ack.make_ack_replies(),
+            Err(VitaError::ControlOnly)
+        ));
+        assert!(matches!(
+            Vrt::new_context_packet().make_ack_replies(),
+            Err(VitaError::CommandOnly)
+        ));
+    }
+
+    #[test]
+    fn size_bytes() {
+        let mut signal_data = Vrt::new_signal_data_packet();
+        signal_data.set_signal_payload([0; 12]).unwrap();
+        let mut context = Vrt::new_context_packet();
+        context
+            .payload_mut()
+            .context_mut()
+            .unwrap()
+            .set_bandwidth_hz(Some(8e6))
+            .unwrap();
+        context.update_packet_size();
+        for packet in [
+            signal_data,
+            context,
+            Vrt::new_control_packet(),
+            Vrt::new_query_ack_packet(),
+        ] {
+            let bytes = packet.to_bytes().unwrap();
+            assert_eq!(packet.packet_size_bytes(), bytes.len());
+            assert_eq!(
+                packet.packet_size_bytes(),
+                packet.header().packet_size() as usize * 4
+            );
+            assert_eq!(
+                packet.header().payload_size_bytes(),
+                packet.payload().payload_size_bytes()
+            );
+        }
+
+        let mut packet = Vrt::new_signal_data_packet();
+        packet.set_signal_payload([0; 12]).unwrap();
+        let signal_data = packet.payload().signal_data().unwrap();
+        assert_eq!(signal_data.payload_size_bytes(), 12);
+        assert_eq!(packet.header().payload_size_bytes(), 12);
+
+        // A partial final word only counts as padding in the payload.
+        packet.set_signal_payload([0; 13]).unwrap();
+        let signal_data = packet.payload().signal_data().unwrap();
+        assert_eq!(signal_data.payload_size_bytes(), 13);
+        assert_eq!(packet.payload().payload_size_bytes(), 16);
+        assert_eq!(packet.header().payload_size_bytes(), 16);
+    }
+
+    #[test]
+    fn trailer_size_conflict() {
+        let mut packet = Vrt::new_signal_data_packet();
+        packet.set_stream_id(Some(0x1234));
+        packet.set_signal_payload(vec![0; 8]).unwrap();
+        let bytes = packet.to_bytes().unwrap();
+
+        // Trailer bit set, but the size only covers the header and stream
+        // ID. The following packet's bytes mustn't be read as a trailer.
+        let mut conflict = bytes[..8].to_vec();
+        conflict[0] |= 0x04;
+        conflict[3] = 2;
+        conflict.extend(&bytes);
+        assert!(matches!(
+            Vrt::try_parse(&conflict),
+            Err(VitaError::TrailerSizeConflict)
+        ));
+        assert!(matches!(
+            Vrt::try_from_with_limit(&conflict, 1024),
+            Err(VitaError::TrailerSizeConflict)
+        ));
+        assert!(matches!(
+            PacketHeader::peek(&conflict),
+            Err(VitaError::TrailerSizeConflict)
+        ));
+        // The plain deku parse path checks it too, and converting its
+        // error back gives the typed variant.
+        assert!(matches!(
+            VitaError::from(Vrt::try_from(&conflict[..12]).unwrap_err()),
+            VitaError::TrailerSizeConflict
+        ));
+        // An ACK whose CAM selects both a validation and an execution ACK
+        // (validation is bit 20 of the CAM word after the prologue).
+        let packet = Vrt::new_exec_ack_packet();
+        let mut ack = packet.to_bytes().unwrap();
+        ack[packet.header().prologue_size_words() * 4 + 1] |= 0x10;
+        assert!(matches!(
+            VitaError::from(Vrt::try_from(&ack[..]).unwrap_err()),
+            VitaError::AmbiguousAckCam
+        ));
+        assert!(matches!(
+            Vrt::try_parse(&ack),
+            Err(VitaError::AmbiguousAckCam)
+        ));
+
+        // Trailer bit set with room for the trailer but no payload is fine.
+        let mut bytes = bytes[..8].to_vec();
+        bytes[0] |= 0x04;
+        bytes[3] = 3;
+        bytes.extend(0x4000_0000u32.to_be_bytes());
+        let (parsed, len) = Vrt::try_parse(&bytes).unwrap();
+        assert_eq!(len, 12);
+        assert!(parsed.payload().signal_data().unwrap().payload().is_empty());
+        assert!(parsed.trailer().is_some());
+
+        // With the trailer bit clear, the same word is simply payload.
+        bytes[0] &= !0x04;
+        let (parsed, _) = Vrt::try_parse(&bytes).unwrap();
+        assert!(parsed.trailer().is_none());
+        assert_eq!(
+            parsed.payload().signal_data().unwrap().payload_size_bytes(),
+            4
+        );
+    }
+
+    #[test]
+    fn from_prefix() {
+        let mut data = Vrt::new_signal_data_packet();
+        data.set_signal_payload(vec![1, 2, 3, 4, 5, 6, 7, 8])
+            .unwrap();
+        let mut context = Vrt::new_context_packet();
+        context
+            .payload_mut()
+            .context_mut()
+            .unwrap()
+            .set_bandwidth_hz(Some(8e6))
+            .unwrap();
+        context.update_packet_size();
+        let data_bytes = data.to_bytes().unwrap();
+        let context_bytes = context.to_bytes().unwrap();
+        let mut bytes = data_bytes.clone();
+        bytes.extend(&context_bytes);
+        // Trailing partial packet.
+        bytes.extend(&data_bytes[..6]);
+
+        let (first, rest) = Vrt::from_prefix(&bytes).unwrap();
+        assert_eq!(first, data);
+        assert_eq!(rest, &bytes[data_bytes.len()..]);
+        let (second, rest) = Vrt::from_prefix(rest).unwrap();
+        assert_eq!(second, context);
+        assert_eq!(rest, &data_bytes[..6]);
+        assert!(matches!(
+            Vrt::from_prefix(rest),
+            Err(VitaError::BufferTooShort { .. })
+        ));
+
+        let (_, rest) = Vrt::from_prefix(&context_bytes).unwrap();
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn try_parse_regressions() {
+        // Packet size smaller than the prologue (used to underflow).
+        let bytes = [
+            0x07, 0xd0, 0x00, 0x04, 0xab, 0xf4, 0x63, 0x23, 0x07, 0xcf, 0xe2, 0xe1, 0xa6, 0x41,
+            0x27, 0x93,
+        ];
+        assert!(Vrt::try_parse(&bytes).is_err());
+        assert!(Vrt::try_from(&bytes[..]).is_err());
+
+        // GPS ASCII word count far larger than any packet (used to abort
+        // on allocation).
+        let bytes = [
+            0x40, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0xff, 0xff, 0xff, 0xff,
+        ];
+        assert!(Vrt::try_parse(&bytes).is_err());
+
+        // Reserved CAM action mode (used to panic when displayed).
+        let mut bytes = Vrt::new_control_packet().to_bytes().unwrap();
+        bytes[9] |= 0x80;
+        bytes[8] |= 0x01;
+        let (packet, len) = Vrt::try_parse(&bytes).unwrap();
+        assert_eq!(len, bytes.len());
+        let command = packet.payload().command().unwrap();
+        assert_eq!(command.cam().action_mode(), ActionMode::Reserved);
+        assert!(!format!("{command}").is_empty());
+
+        // Reserved CAM timing control mode.
+        let mut bytes = Vrt::new_control_packet().to_bytes().unwrap();
+        bytes[10] |= 0x70;
+        assert!(matches!(
+            Vrt::try_parse(&bytes),
+            Err(VitaError::InvalidValue(_))
+        ));
+
+        // Reserved packet type and unsupported CIF1 fields.
+        assert!(matches!(
+            Vrt::try_parse(&[0xf0, 0x00, 0x00, 0x01]),
+            Err(VitaError::InvalidPacketType(0xf))
+        ));
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_spectrum(Some(crate::Spectrum::default()));
+        packet.update_packet_size();
+        // Set after sizing, which would clear the bit of an absent field.
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.cif1_mut().as_mut().unwrap().set_array_of_cifs();
+        let bytes = packet.to_bytes().unwrap();
+        assert!(matches!(
+            Vrt::try_parse(&bytes),
+            Err(VitaError::InvalidValue(_))
+        ));
+
+        // Header size larger than the contents.
+        let mut bytes = Vrt::new_context_packet().to_bytes().unwrap();
+        bytes[3] += 1;
+        bytes.extend([0; 4]);
+        assert!(matches!(
+            Vrt::try_parse(&bytes),
+            Err(VitaError::PacketSizeMismatch { .. })
+        ));
+        assert!(matches!(
+            Vrt::try_parse(&bytes[..2]),
+            Err(VitaError::BufferTooShort { .. })
+        ));
+    }
+
+    #[test]
+    fn validate_packet_size() {
+        let mut packet = Vrt::new_signal_data_packet();
+        packet.set_signal_payload([1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        assert!(packet.validate_size().is_ok());
+
+        let mut context = Vrt::new_context_packet();
+        context
+            .payload_mut()
+            .context_mut()
+            .unwrap()
+            .set_bandwidth_hz(Some(8e6))
+            .unwrap();
+        let computed = context.computed_size_words().unwrap();
+        let header = context.header().packet_size();
+        assert!(matches!(
+            context.validate_size(),
+            Err(VitaError::PacketSizeMismatch { header: h, computed: c }) if h == header && c == computed
+        ));
+        context.update_packet_size();
+        assert!(context.validate_size().is_ok());
+
+        let mut bytes = context.to_bytes().unwrap();
+        bytes[2..4].copy_from_slice(&(computed + 3).to_be_bytes());
+        let parsed = Vrt::try_from(&bytes[..]).unwrap();
+        assert!(matches!(
+            parsed.validate_size(),
+            Err(VitaError::PacketSizeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn set_timestamp_modes() {
+        let mut packet = Vrt::new_signal_data_packet();
+        packet.set_tsi(Tsi::Utc);
+        packet.set_tsf(Tsf::SampleCount);
+        assert_eq!(packet.header().tsi(), Tsi::Utc);
+        assert_eq!(packet.header().tsf(), Tsf::SampleCount);
+        assert!(packet.header().integer_timestamp_included());
+        assert!(packet.header().fractional_timestamp_included());
+        assert!(packet.validate_size().is_ok());
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        assert_eq!(parsed, packet);
+
+        // Changing the mode keeps the value.
+        packet.set_integer_timestamp(Some(42), Tsi::Utc).unwrap();
+        packet.set_tsi(Tsi::Gps);
+        assert_eq!(packet.integer_timestamp(), Some(42));
+
+        packet.set_tsi(Tsi::Null);
+        packet.set_tsf(Tsf::Null);
+        assert_eq!(packet.integer_timestamp(), None);
+        assert_eq!(packet.fractional_timestamp(), None);
+        assert!(packet.validate_size().is_ok());
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        assert_eq!(parsed, packet);
+    }
+
+    #[test]
+    fn little_endian_round_trip() {
+        let mut context = Vrt::new_context_packet();
+        context.set_stream_id(Some(0x0102_0304));
+        context
+            .set_integer_timestamp(Some(0x0A0B_0C0D), Tsi::Utc)
+            .unwrap();
+        let c = context.payload_mut().context_mut().unwrap();
+        c.set_bandwidth_hz(Some(8e6)).unwrap();
+        c.set_sample_rate_sps(Some(16e6)).unwrap();
+        context.update_packet_size();
+
+        let mut signal = Vrt::new_signal_data_packet();
+        signal.set_signal_payload([1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+
+        let mut control = Vrt::new_control_packet();
+        control.set_stream_id(Some(7));
+        control.update_packet_size();
+
+        for packet in [context, signal, control] {
+            let be = packet.to_bytes().unwrap();
+            let le = packet.to_bytes_le().unwrap();
+            assert_eq!(be.len(), le.len());
+            // Every 32-bit word is swapped, the header included.
+            for (be, le) in be.chunks_exact(4).zip(le.chunks_exact(4)) {
+                assert_eq!(be, [le[3], le[2], le[1], le[0]]);
+            }
+            assert_eq!(Vrt::try_from(&be[..]).unwrap(), packet);
+            assert_eq!(Vrt::from_bytes_le(&le).unwrap(), packet);
+        }
+    }
+
+    #[test]
+    fn little_endian_capture() {
+        // Signal data packet with a stream ID, UTC + real-time timestamps
+        // and a 6-byte payload (padded to two words), as captured from a
+        // little-endian host that stores the packet as 32-bit words.
+        let capture: [u8; 28] = [
+            0x07, 0x00, 0x60, 0x10, // header: 7 words, TSI=UTC, TSF=ps
+            0xEF, 0xBE, 0xAD, 0xDE, // stream ID 0xDEADBEEF
+            0x04, 0x03, 0x02, 0x01, // integer timestamp 0x01020304
+            0x0A, 0x0B, 0x0C, 0x00, // fractional timestamp, upper word
+            0x0E, 0x0F, 0x10, 0x11, // fractional timestamp, lower word
+            0x04, 0x03, 0x02, 0x01, // payload 01 02 03 04
+            0x00, 0x00, 0x06, 0x05, // payload 05 06 + padding
+        ];
+        let packet = Vrt::from_bytes_le(&capture).unwrap();
+        assert_eq!(packet.header().packet_size(), 7);
+        assert_eq!(packet.stream_id(), Some(0xDEAD_BEEF));
+        assert_eq!(packet.header().tsi(), Tsi::Utc);
+        assert_eq!(packet.integer_timestamp(), Some(0x0102_0304));
+        assert_eq!(packet.header().tsf(), Tsf::RealTimePs);
+        assert_eq!(packet.fractional_timestamp(), Some(0x000C_0B0A_1110_0F0E));
+        assert_eq!(packet.signal_payload().unwrap(), &[1, 2, 3, 4, 5, 6, 0, 0]);
+        assert_eq!(packet.to_bytes_le().unwrap(), capture);
+
+        assert!(matches!(
+            Vrt::from_bytes_le(&capture[..27]),
+            Err(VitaError::PartialWord { len: 27 })
+        ));
+    }
+
+    #[test]
+    fn packet_count() {
+        let mut packet = Vrt::new_context_packet();
+        for count in 0..=15 {
+            packet.set_packet_count(count);
+            assert_eq!(packet.packet_count(), count);
+            assert_eq!(packet.header().packet_count(), count);
+            assert_eq!(packet.to_bytes().unwrap()[1] & 0x0F, count);
+        }
+        packet.set_packet_count(0x1A);
+        assert_eq!(packet.packet_count(), 0xA);
+        packet.set_packet_count(15);
+        packet.inc_packet_count();
+        assert_eq!(packet.packet_count(), 0);
+    }
+
+    #[test]
+    fn write_into_appends() {
+        let mut signal = Vrt::new_signal_data_packet();
+        signal.set_signal_payload([1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        let context = Vrt::new_context_packet();
+
+        let mut buf = vec![0xAA];
+        let first = signal.write_into(&mut buf).unwrap();
+        let second = context.write_into(&mut buf).unwrap();
+        assert_eq!(first, signal.packet_size_bytes());
+        assert_eq!(second, context.packet_size_bytes());
+        assert_eq!(buf.len(), 1 + first + second);
+        assert_eq!(buf[0], 0xAA);
+
+        let (parsed, len) = Vrt::try_parse(&buf[1..]).unwrap();
+        assert_eq!((parsed, len), (signal, first));
+        let (parsed, _) = Vrt::try_parse(&buf[1 + first..]).unwrap();
+        assert_eq!(parsed, context);
+
+        // Reusing a cleared buffer doesn't reallocate.
+        let capacity = buf.capacity();
+        buf.clear();
+        context.write_into(&mut buf).unwrap();
+        assert_eq!(buf.capacity(), capacity);
+    }
+
+    #[test]
+    fn signal_data_without_stream_id() {
+        let mut packet = Vrt::new_signal_data_packet_no_stream_id();
+        packet.set_signal_payload([1, 2, 3, 4]).unwrap();
+        packet.update_packet_size();
+        let bytes = packet.to_bytes().unwrap();
+        // Packet type 0 in the top nibble; header + payload only.
+        assert_eq!(bytes[0] >> 4, 0);
+        assert_eq!(bytes, [0x00, 0x00, 0x00, 0x02, 1, 2, 3, 4]);
+
+        let parsed = Vrt::try_from(&bytes[..]).unwrap();
+        assert_eq!(
+            parsed.header().packet_type(),
+            PacketType::SignalDataWithoutStreamId
+        );
+        assert!(!parsed.header().stream_id_included());
+        assert_eq!(parsed.stream_id(), None);
+        assert_eq!(parsed.signal_payload().unwrap(), &[1, 2, 3, 4]);
+        assert_eq!(parsed, packet);
+
+        // Adding a stream ID switches to packet type 1.
+        packet.set_stream_id(Some(5));
+        assert_eq!(packet.header().packet_type(), PacketType::SignalData);
+    }
+
+    #[test]
+    fn payload_too_large() {
+        let mut packet = Vrt::new_signal_data_packet();
+        // Header and stream ID take two words.
+        let max_payload = (u16::MAX as usize - 2) * 4;
+        packet.set_signal_payload(vec![0; max_payload]).unwrap();
+        assert_eq!(packet.header().packet_size(), u16::MAX);
+        assert!(packet.validate_size().is_ok());
+
+        packet.set_signal_payload([1, 2, 3, 4]).unwrap();
+        assert!(matches!(
+            packet.set_signal_payload(vec![0; max_payload + 4]),
+            Err(VitaError::PayloadTooLarge { words: 65536 })
+        ));
+        // A partial word still needs a whole word.
+        assert!(matches!(
+            packet.set_signal_payload(vec![0; max_payload + 1]),
+            Err(VitaError::PayloadTooLarge { words: 65536 })
+        ));
+        assert_eq!(packet.signal_payload().unwrap(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn hexdump_sections() {
+        let mut packet = Vrt::new_signal_data_packet();
+        packet.set_stream_id(Some(0xDEADBEEF));
+        packet.set_signal_payload(vec![0xAB; 20]).unwrap();
+        let bytes = packet.to_bytes().unwrap();
+        let dump = packet.hexdump().unwrap();
+        let mut lines = dump.lines();
+        assert_eq!(lines.next(), Some("header"));
+        assert_eq!(
+            lines.next().unwrap(),
+            format!(
+                "  0000: {:02x} {:02x} {:02x} {:02x}",
+                bytes[0], bytes[1], bytes[2], bytes[3]
+            )
+        );
+        assert_eq!(lines.next(), Some("stream ID"));
+        assert_eq!(lines.next(), Some("  0004: de ad be ef"));
+        assert_eq!(lines.next(), Some("payload"));
+        assert_eq!(
+            lines.next(),
+            Some("  0008: ab ab ab ab  ab ab ab ab  ab ab ab ab  ab ab ab ab")
+        );
+        assert_eq!(lines.next(), Some("  0018: ab ab ab ab"));
+        assert_eq!(lines.next(), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn write_to_vec() {
+        let mut packet = Vrt::new_signal_data_packet();
+        packet.set_stream_id(Some(0x1234));
+        packet
+            .set_signal_payload(vec![1, 2, 3, 4, 5, 6, 7, 8])
+            .unwrap();
+        let mut out = Vec::new();
+        packet.write_to(&mut out).unwrap();
+        // Appends like any other writer.
+        Vrt::new_context_packet().write_to(&mut out).unwrap();
+        let expected = [
+            packet.to_bytes().unwrap(),
+            Vrt::new_context_packet().to_bytes().unwrap(),
+        ]
+        .concat();
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn describe_trailer_indicators() {
+        let mut packet = Vrt::new_signal_data_packet();
+        packet.set_stream_id(Some(0xDEADBEEF));
+        packet.set_signal_payload(vec![0; 8]).unwrap();
+        let mut bytes = packet.to_bytes().unwrap();
+        // Add a trailer with spectral inversion and over-range set.
+        bytes[0] |= 0x04;
+        bytes[3] += 1;
+        bytes.extend(((0b11u32 << 25) | (0b11 << 13)).to_be_bytes());
+        let packet = Vrt::try_from(&bytes[..]).unwrap();
+        let description = packet.describe();
+        assert!(description.contains("Packet type: SignalData"));
+        assert!(description.contains("Stream ID: 0xdeadbeef"));
+        assert!(description.contains("Payload: 8 bytes"));
+        assert!(description.contains("Spectral inversion: true"));
+        assert!(description.contains("Over-range: true"));
+        assert!(!description.contains("Sample loss"));
+    }
+
+    #[test]
+    fn copy_prologue_from() {
+        let mut request = Vrt::new_signal_data_packet();
+        request.set_stream_id(Some(0xDEADBEEF));
+        request.set_class_id(Some(ClassIdentifier::new(0xFF5654, 0x1, 0x2)));
+        request
+            .set_integer_timestamp(Some(1_700_000_000), Tsi::Utc)
+            .unwrap();
+        request
+            .set_fractional_timestamp(Some(500), Tsf::RealTimePs)
+            .unwrap();
+        request.set_signal_payload(vec![0; 8]).unwrap();
+
+        let mut reply = Vrt::new_context_packet();
+        reply
+            .payload_mut()
+            .context_mut()
+            .unwrap()
+            .set_bandwidth_hz(Some(8e6))
+            .unwrap();
+        reply.copy_prologue_from(&request);
+        let parsed = Vrt::try_from(&reply.to_bytes().unwrap()[..]).unwrap();
+        assert_eq!(parsed.header().packet_type(), PacketType::Context);
+        assert_eq!(parsed.stream_id(), request.stream_id());
+        assert_eq!(parsed.class_id(), request.class_id());
+        assert!(parsed.header().class_id_included());
+        assert_eq!(parsed.integer_timestamp(), request.integer_timestamp());
+        assert_eq!(
+            parsed.fractional_timestamp(),
+            request.fractional_timestamp()
+        );
+        assert_eq!(parsed.header().tsi(), Tsi::Utc);
+        assert_eq!(parsed.header().tsf(), Tsf::RealTimePs);
+        assert_eq!(
+            parsed.payload().context().unwrap().bandwidth_hz(),
+            Some(8e6)
+        );
+
+        // Copying from a bare packet clears everything again.
+        reply.copy_prologue_from(&Vrt::new_context_packet());
+        assert_eq!(reply.class_id(), None);
+        assert_eq!(reply.integer_timestamp(), None);
+        assert_eq!(reply.header().tsf(), Tsf::Null);
+        assert!(Vrt::try_from(&reply.to_bytes().unwrap()[..]).is_ok());
+    }
+
+    #[test]
+    fn integer_timestamp_leap_seconds() {
+        let mut packet = Vrt::new_signal_data_packet();
+        assert_eq!(packet.integer_timestamp_unix_secs(18), None);
+        packet
+            .set_integer_timestamp(Some(1_167_264_018), Tsi::Gps)
+            .unwrap();
+        assert_eq!(packet.integer_timestamp_unix_secs(18), Some(1_483_228_800));
+        assert_eq!(packet.integer_timestamp_unix_secs(17), Some(1_483_228_801));
+        packet
+            .set_integer_timestamp(Some(1_483_228_800), Tsi::Utc)
+            .unwrap();
+        assert_eq!(packet.integer_timestamp_unix_secs(17), Some(1_483_228_800));
+        packet.set_integer_timestamp(Some(5), Tsi::Other).unwrap();
+        assert_eq!(packet.integer_timestamp_unix_secs(18), None);
+
+        packet
+            .set_integer_timestamp_unix_secs(1_483_228_800, Tsi::Gps, 18)
+            .unwrap();
+        assert_eq!(packet.integer_timestamp(), Some(1_167_264_018));
+        assert!(matches!(
+            packet.set_integer_timestamp_unix_secs(0, Tsi::Gps, 18),
+            Err(VitaError::OutOfRange)
+        ));
+    }
+
+    #[cfg(feature = "jiff")]
+    #[test]
+    fn integer_timestamp_jiff() {
+        let time: jiff::Timestamp = "2017-01-01T00:00:00Z".parse().unwrap();
+        let mut packet = Vrt::new_signal_data_packet();
+        packet
+            .set_integer_timestamp_jiff(time, Tsi::Gps, 18)
+            .unwrap();
+        assert_eq!(packet.integer_timestamp(), Some(1_167_264_018));
+        assert!(matches!(packet.header().tsi(), Tsi::Gps));
+        assert_eq!(packet.integer_timestamp_jiff(18), Some(time));
+        assert!(matches!(
+            packet.set_integer_timestamp_jiff(time, Tsi::Null, 18),
+            Err(VitaError::TimestampModeMismatch)
+        ));
+    }
+
+    #[test]
+    fn set_stream_id_tracks_header() {
+        let mut extension_data = Vrt::new_signal_data_packet();
+        extension_data
+            .header_mut()
+            .set_packet_type(PacketType::ExtensionData);
+        let packets = [
+            Vrt::new_signal_data_packet(),
+            Vrt::new_signal_data_packet_no_stream_id(),
+            extension_data,
+            Vrt::new_context_packet(),
+            Vrt::new_extension_context_packet(),
+            Vrt::new_control_packet(),
+            Vrt::new_cancellation_packet(),
+            Vrt::new_validation_ack_packet(),
+            Vrt::new_exec_ack_packet(),
+            Vrt::new_query_ack_packet(),
+        ];
+        for mut packet in packets {
+            let optional = packet.is_signal_data();
+
+            packet.set_stream_id(Some(0x1234));
+            assert!(packet.header().stream_id_included());
+            assert_eq!(packet.stream_id(), Some(0x1234));
+            let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+            assert_eq!(parsed.stream_id(), Some(0x1234));
+            let with_sid = packet.header().packet_size();
+
+            packet.set_stream_id(None);
+            assert_eq!(packet.header().stream_id_included(), !optional);
+            let expected_sid = if optional { None } else { Some(0x1234) };
+            assert_eq!(packet.stream_id(), expected_sid);
+            let expected_size = if optional { with_sid - 1 } else { with_sid };
+            assert_eq!(packet.header().packet_size(), expected_size);
+            let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+            assert_eq!(parsed, packet);
+        }
+    }
+
+    #[test]
+    fn prologue_setters_update_packet_size() {
+        let mut packet = Vrt::new_signal_data_packet();
+        packet.set_signal_payload(vec![0; 8]).unwrap();
+        assert_eq!(packet.header().packet_size(), 4);
+        packet.set_class_id(Some(ClassIdentifier::default()));
+        assert_eq!(packet.header().packet_size(), 6);
+        packet.set_integer_timestamp(Some(1), Tsi::Utc).unwrap();
+        assert_eq!(packet.header().packet_size(), 7);
+        packet
+            .set_fractional_timestamp(Some(1), Tsf::SampleCount)
+            .unwrap();
+        assert_eq!(packet.header().packet_size(), 9);
+        assert!(packet.validate_size().is_ok());
+
+        // Copying from a packet without a stream ID keeps the mandatory
+        // stream ID of a context packet, and sizes the packet to match.
+        let mut context = Vrt::new_context_packet();
+        context.set_stream_id(Some(7));
+        context.copy_prologue_from(&Vrt::new_signal_data_packet_no_stream_id());
+        assert_eq!(context.stream_id(), Some(7));
+        assert!(context.validate_size().is_ok());
+        context.copy_prologue_from(&packet);
+        assert_eq!(context.stream_id(), Some(0));
+        assert_eq!(context.class_id(), packet.class_id());
+        assert!(context.validate_size().is_ok());
+    }
+
+    #[test]
+    fn set_trailer() {
+        let mut packet = Vrt::new_signal_data_packet();
+        packet.set_signal_payload(vec![0; 8]).unwrap();
+        let size = packet.header().packet_size();
+
+        let mut trailer = Trailer::default();
+        trailer.set_over_range_indicator(Some(true));
+        packet.set_trailer(Some(trailer)).unwrap();
+        assert!(packet.header().trailer_included());
+        assert_eq!(packet.header().packet_size(), size + 1);
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        assert_eq!(parsed.trailer(), Some(&trailer));
+        assert_eq!(parsed.signal_payload().unwrap(), &[0; 8]);
+
+        // Replacing an existing trailer doesn't grow the packet again.
+        packet.set_trailer(Some(Trailer::default())).unwrap();
+        assert_eq!(packet.header().packet_size(), size + 1);
+
+        packet.set_trailer(None).unwrap();
+        assert!(!packet.header().trailer_included());
+        assert_eq!(packet.header().packet_size(), size);
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        assert_eq!(parsed.trailer(), None);
+        assert_eq!(parsed.signal_payload().unwrap(), &[0; 8]);
+
+        assert!(matches!(
+            Vrt::new_context_packet().set_trailer(None),
+            Err(VitaError::SignalDataOnly)
+        ));
+    }
+
+    #[test]
+    fn set_not_a_vita490() {
+        let mut packet = Vrt::new_signal_data_packet();
+        packet.set_signal_payload(vec![0; 4]).unwrap();
+        packet.set_not_a_vita490(true).unwrap();
+        let bytes = packet.to_bytes().unwrap();
+        assert_eq!(bytes[0] & 0x02, 0x02);
+        let parsed = Vrt::try_from(&bytes[..]).unwrap();
+        match parsed.header().indicators() {
+            Indicators::SignalData(i) => {
+                assert!(i.not_a_vita490_packet);
+                assert!(!i.trailer_included);
+                assert!(!i.signal_spectral_data);
+            }
+            _ => panic!("unexpected indicators"),
+        }
+        assert!(parsed.check_v490().is_err());
+
+        packet.set_not_a_vita490(false).unwrap();
+        assert_eq!(packet.to_bytes().unwrap()[0] & 0x02, 0);
+        assert!(packet.check_v490().is_ok());
+
+        assert!(matches!(
+            Vrt::new_context_packet().set_not_a_vita490(true),
+            Err(VitaError::SignalDataOnly)
+        ));
+    }
+
+    #[test]
+    fn exec_ack_for() {
+        let mut packet = Vrt::new_control_packet();
+        packet.set_stream_id(Some(0xDEADBEEF));
+        let command = packet.payload_mut().command_mut().unwrap();
+        command.set_message_id(0x1234);
+        command.set_controllee_id(Some(0x42)).unwrap();
+        command
+            .set_controller_uuid(Some(0x0011_2233_4455_6677_8899_AABB_CCDD_EEFF))
+            .unwrap();
+        // Only a validation ACK is requested; an exec ACK is built anyway.
+        let mut cam = command.cam();
+        cam.set_validation();
+        command.set_cam(cam);
+        packet.update_packet_size();
+
+        let reply = Vrt::exec_ack_for(&packet).unwrap();
+        let parsed = Vrt::try_from(&reply.to_bytes().unwrap()[..]).unwrap();
+        assert_eq!(parsed.stream_id(), Some(0xDEADBEEF));
+        let ack = parsed.payload().command().unwrap();
+        assert_eq!(ack.message_id(), 0x1234);
+        assert_eq!(ack.controllee(), Identity::Id(0x42));
+        assert_eq!(
+            ack.controller(),
+            Identity::Uuid(0x0011_2233_4455_6677_8899_AABB_CCDD_EEFF)
+        );
+        assert!(ack.payload().exec_ack().is_ok());
+        assert!(ack.cam().execution());
+
+        assert!(matches!(
+            Vrt::exec_ack_for(&reply),
+            Err(VitaError::ControlOnly)
+        ));
+        assert!(matches!(
+            Vrt::exec_ack_for(&Vrt::new_context_packet()),
+            Err(VitaError::CommandOnly)
+        ));
+    }
+
+    #[test]
+    fn oversized_header_is_rejected() {
+        // Header claims 65535 words, but only a few bytes follow.
+        let bytes = [0x10, 0x00, 0xFF, 0xFF, 0, 0, 0, 1, 0xAA, 0xBB];
+        assert!(matches!(
+            Vrt::try_from_with_limit(&bytes, 1024),
+            Err(VitaError::PacketExceedsLimit {
+                words: 65535,
+                max: 1024
+            })
+        ));
+        assert!(matches!(
+            Vrt::try_from_with_limit(&bytes, u16::MAX),
+            Err(VitaError::BufferTooShort {
+                needed: 262140,
+                available: 10
+            })
+        ));
+        assert!(matches!(
+            Vrt::try_from(&bytes[..]),
+            Err(DekuError::Incomplete(_))
+        ));
+    }
+
+    #[test]
+    fn trailing_bytes_are_a_size_mismatch() {
+        let mut bytes = Vrt::new_signal_data_packet().to_bytes().unwrap();
+        bytes.extend([0; 4]);
+        assert!(matches!(
+            Vrt::try_from_with_limit(&bytes, 1024),
+            Err(VitaError::PacketSizeMismatch {
+                header: 2,
+                computed: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn incomplete_reports_offset_and_shortfall() {
+        // A context packet sized at 4 words whose CIF0 announces an
+        // 8-byte bandwidth field, leaving only 4 of its bytes.
+        let bytes = [
+            0x40, 0x00, 0x00, 0x04, 0, 0, 0, 1, 0x20, 0, 0, 0, 0, 0, 0, 0,
+        ];
+        assert!(matches!(
+            Vrt::try_parse(&bytes),
+            Err(VitaError::Incomplete {
+                offset: 12,
+                needed: 4
+            })
+        ));
+        assert!(matches!(
+            Vrt::try_from_with_limit(&bytes, u16::MAX),
+            Err(VitaError::Incomplete {
+                offset: 12,
+                needed: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn real_time_fractional_timestamp_range() {
+        let mut packet = Vrt::new_signal_data_packet();
+        packet
+            .set_fractional_timestamp(Some(999_999_999_999), Tsf::RealTimePs)
+            .unwrap();
+        assert!(matches!(
+            packet.set_fractional_timestamp(Some(1_000_000_000_000), Tsf::RealTimePs),
+            Err(VitaError::FractionalTimestampOutOfRange(1_000_000_000_000))
+        ));
+        assert_eq!(packet.fractional_timestamp(), Some(999_999_999_999));
+        // Other modes aren't limited to one second.
+        packet
+            .set_fractional_timestamp(Some(1_000_000_000_000), Tsf::SampleCount)
+            .unwrap();
+        // Switching the mode can't sneak an out-of-range value through.
+        packet.set_tsf(Tsf::RealTimePs);
+        assert_eq!(packet.fractional_timestamp(), Some(0));
+        packet
+            .set_fractional_timestamp(Some(123), Tsf::SampleCount)
+            .unwrap();
+        packet.set_tsf(Tsf::RealTimePs);
+        assert_eq!(packet.fractional_timestamp(), Some(123));
+        assert!(Vrt::try_from(&packet.to_bytes().unwrap()[..]).is_ok());
+    }
+
+    #[test]
+    fn len_bytes_matches_serialized() {
+        let mut data = Vrt::new_signal_data_packet();
+        data.set_signal_payload(vec![0; 12]).unwrap();
+        data.set_class_id(Some(ClassIdentifier::new(0xFF5654, 1, 2)));
+        data.set_integer_timestamp(Some(1), Tsi::Utc).unwrap();
+        data.set_fractional_timestamp(Some(2), Tsf::RealTimePs)
+            .unwrap();
+        let mut context = Vrt::new_context_packet();
+        let c = context.payload_mut().context_mut().unwrap();
+        c.set_bandwidth_hz(Some(8e6)).unwrap();
+        c.set_range_m(Some(1.0)).unwrap();
+        let packets = [
+            data,
+            Vrt::new_signal_data_packet_no_stream_id(),
+            context,
+            Vrt::new_extension_context_packet(),
+            Vrt::new_control_packet(),
+            Vrt::new_cancellation_packet(),
+            Vrt::new_validation_ack_packet(),
+            Vrt::new_exec_ack_packet(),
+            Vrt::new_query_ack_packet(),
+        ];
+        for packet in packets {
+            // Not calling update_packet_size(): the header may be stale.
+            assert_eq!(packet.len_bytes(), packet.to_bytes().unwrap().len());
+        }
+    }
+
+    #[test]
+    fn context_for_stream() {
+        let packet = Vrt::context_for_stream(0xABCD, 8e6, 100e6, 10e6).unwrap();
+        assert!(packet.validate_size().is_ok());
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        assert_eq!(parsed.stream_id(), Some(0xABCD));
+        let context = parsed.payload().context().unwrap();
+        assert!(!context.context_changed());
+        assert_eq!(context.bandwidth_hz(), Some(8e6));
+        assert_eq!(context.rf_ref_freq_hz(), Some(100e6));
+        assert_eq!(context.sample_rate_sps(), Some(10e6));
+        assert_eq!(
+            context.present_fields(),
+            ["bandwidth", "rf_ref_freq", "sample_rate"]
+        );
     }
 }
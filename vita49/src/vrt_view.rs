@@ -0,0 +1,263 @@
+// SPDX-FileCopyrightText: 2025 The vita49-rs Authors
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Zero-copy, borrowed view over a serialized VRT packet. Useful for
+high-rate receivers that only need to inspect the prologue or
+forward the raw payload bytes without allocating.
+*/
+
+use crate::prelude::*;
+use crate::Trailer;
+use deku::DekuError;
+
+/// A borrowed view over a single serialized VRT packet.
+///
+/// Constructing a view only validates the packet header against the
+/// input length; all other fields are decoded lazily from the borrowed
+/// bytes when requested. No heap allocation is performed.
+///
+/// # Example
+/// ```
+/// use vita49::prelude::*;
+/// # fn main() -> Result<(), VitaError> {
+/// let mut packet = Vrt::new_signal_data_packet();
+/// packet.set_stream_id(Some(0xDEADBEEF));
+/// packet.set_signal_payload(&[1, 2, 3, 4, 5, 6, 7, 8])?;
+/// let bytes = packet.to_bytes().unwrap();
+///
+/// let view = VrtView::new(&bytes)?;
+/// assert_eq!(view.stream_id(), Some(0xDEADBEEF));
+/// assert_eq!(view.payload(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+/// assert_eq!(view.to_vrt().unwrap(), packet);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct VrtView<'a> {
+    header: PacketHeader,
+    bytes: &'a [u8],
+}
+
+impl<'a> VrtView<'a> {
+    /// Creates a view over the packet at the start of `bytes`.
+    ///
+    /// Any bytes past the packet size given in the header are ignored.
+    ///
+    /// # Errors
+    /// Returns an error if `bytes` is shorter than the packet size given
+    /// in the header, if the header has a reserved packet type, or if the
+    /// packet size is too small for the fields the header says are present.
+    /// A data packet whose trailer-included bit is set but whose size only
+    /// covers the prologue gives [`VitaError::TrailerSizeConflict`].
+    pub fn new(bytes: &'a [u8]) -> Result<VrtView<'a>, VitaError> {
+        let header = PacketHeader::from_u32(read_u32(bytes, 0)?);
+        let packet_type = (header.as_u32() >> 28) as u8;
+        PacketType::try_from(packet_type).map_err(|_| VitaError::InvalidPacketType(packet_type))?;
+
+        let packet_len = header.packet_size_bytes();
+        if bytes.len() < packet_len {
+            return Err(VitaError::BufferTooShort {
+                needed: packet_len,
+                available: bytes.len(),
+            });
+        }
+        let view = VrtView {
+            header,
+            bytes: &bytes[..packet_len],
+        };
+        if view.payload_offset() > packet_len {
+            return Err(VitaError::InvalidPacketSize);
+        }
+        if view.payload_offset() + view.trailer_len() > packet_len {
+            return Err(VitaError::TrailerSizeConflict);
+        }
+        Ok(view)
+    }
+
+    /// Gets a reference to the packet header.
+    pub fn header(&self) -> &PacketHeader {
+        &self.header
+    }
+
+    /// Gets the packet stream ID.
+    pub fn stream_id(&self) -> Option<u32> {
+        self.header
+            .stream_id_included()
+            .then(|| self.word(self.stream_id_offset()))
+    }
+
+    /// Gets the packet class identifier.
+    pub fn class_id(&self) -> Option<ClassIdentifier> {
+        self.header.class_id_included().then(|| {
+            let offset = self.class_id_offset();
+            ClassIdentifier::from_words(self.word(offset), self.word(offset + 4))
+        })
+    }
+
+    /// Gets the integer timestamp field.
+    pub fn integer_timestamp(&self) -> Option<u32> {
+        self.header
+            .integer_timestamp_included()
+            .then(|| self.word(self.integer_timestamp_offset()))
+    }
+
+    /// Gets the fractional timestamp field.
+    pub fn fractional_timestamp(&self) -> Option<u64> {
+        self.header.fractional_timestamp_included().then(|| {
+            let offset = self.fractional_timestamp_offset();
+            ((self.word(offset) as u64) << 32) | self.word(offset + 4) as u64
+        })
+    }
+
+    /// Gets the raw payload bytes.
+    ///
+    /// For signal data packets, these are the sample bytes exactly as
+    /// they appear on the wire. For context and command packets, this
+    /// is the still-encoded payload.
+    pub fn payload(&self) -> &'a [u8] {
+        &self.bytes[self.payload_offset()..self.bytes.len() - self.trailer_len()]
+    }
+
+    /// Gets the packet trailer.
+    pub fn trailer(&self) -> Option<Trailer> {
+        self.header
+            .trailer_included()
+            .then(|| Trailer::from_u32(self.word(self.bytes.len() - 4)))
+    }
+
+    /// Gets the bytes backing this packet.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Decodes the viewed bytes into an owned [`Vrt`].
+    ///
+    /// # Errors
+    /// Returns an error if the payload can't be decoded.
+    pub fn to_vrt(&self) -> Result<Vrt, DekuError> {
+        Vrt::try_from(self.bytes)
+    }
+
+    fn word(&self, offset: usize) -> u32 {
+        // Offsets are validated against the packet size in `new()`.
+        read_u32(self.bytes, offset).unwrap()
+    }
+
+    fn stream_id_offset(&self) -> usize {
+        4
+    }
+
+    fn class_id_offset(&self) -> usize {
+        self.stream_id_offset() + 4 * self.header.stream_id_included() as usize
+    }
+
+    fn integer_timestamp_offset(&self) -> usize {
+        self.class_id_offset() + 8 * self.header.class_id_included() as usize
+    }
+
+    fn fractional_timestamp_offset(&self) -> usize {
+        self.integer_timestamp_offset() + 4 * self.header.integer_timestamp_included() as usize
+    }
+
+    fn payload_offset(&self) -> usize {
+        self.fractional_timestamp_offset()
+            + 8 * self.header.fractional_timestamp_included() as usize
+    }
+
+    fn trailer_len(&self) -> usize {
+        4 * self.header.trailer_included() as usize
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for VrtView<'a> {
+    type Error = VitaError;
+
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+        VrtView::new(bytes)
+    }
+}
+
+pub(crate) fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, VitaError> {
+    match bytes.get(offset..offset + 4) {
+        Some(b) => Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]])),
+        None => Err(VitaError::BufferTooShort {
+            needed: offset + 4,
+            available: bytes.len(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Tsf, Tsi};
+
+    #[test]
+    fn view_matches_full_parse() {
+        let mut packet = Vrt::new_signal_data_packet();
+        packet.set_stream_id(Some(0xDEADBEEF));
+        let mut class_id = ClassIdentifier::default();
+        class_id.set_oui(0x12_3456);
+        class_id.set_packet_class_code(0xABCD);
+        packet.set_class_id(Some(class_id));
+        packet.set_integer_timestamp(Some(1234), Tsi::Utc).unwrap();
+        packet
+            .set_fractional_timestamp(Some(0x1_0000_0002), Tsf::SampleCount)
+            .unwrap();
+        packet.set_signal_payload([1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        let bytes = packet.to_bytes().unwrap();
+
+        let view = VrtView::new(&bytes).unwrap();
+        assert_eq!(view.header(), packet.header());
+        assert_eq!(view.stream_id(), packet.stream_id());
+        assert_eq!(view.class_id().as_ref(), packet.class_id());
+        assert_eq!(view.integer_timestamp(), packet.integer_timestamp());
+        assert_eq!(view.fractional_timestamp(), packet.fractional_timestamp());
+        assert_eq!(view.payload(), packet.signal_payload().unwrap());
+        assert_eq!(view.trailer(), None);
+        assert_eq!(view.to_vrt().unwrap(), packet);
+    }
+
+    #[test]
+    fn view_ignores_trailing_bytes() {
+        let mut packet = Vrt::new_context_packet();
+        packet
+            .payload_mut()
+            .context_mut()
+            .unwrap()
+            .set_bandwidth_hz(Some(8e6))
+            .unwrap();
+        packet.update_packet_size();
+        let mut bytes = packet.to_bytes().unwrap();
+        let packet_len = bytes.len();
+        bytes.extend_from_slice(&[0xFF; 8]);
+
+        let view = VrtView::new(&bytes).unwrap();
+        assert_eq!(view.as_bytes().len(), packet_len);
+        assert_eq!(view.to_vrt().unwrap(), packet);
+    }
+
+    #[test]
+    fn view_rejects_malformed_input() {
+        let packet = Vrt::new_signal_data_packet();
+        let bytes = packet.to_bytes().unwrap();
+        assert!(matches!(
+            VrtView::new(&bytes[..2]),
+            Err(VitaError::BufferTooShort { .. })
+        ));
+
+        // Header claims a stream ID but the packet size is one word.
+        let bytes = [0x10, 0x00, 0x00, 0x01];
+        assert!(matches!(
+            VrtView::new(&bytes),
+            Err(VitaError::InvalidPacketSize)
+        ));
+
+        let bytes = [0xF0, 0x00, 0x00, 0x01];
+        assert!(matches!(
+            VrtView::new(&bytes),
+            Err(VitaError::InvalidPacketType(0xF))
+        ));
+    }
+}
@@ -9,9 +9,12 @@ use semver_sort::semver::semver_compare;
 use subprocess::Exec;
 use tempfile::NamedTempFile;
 use vita49::{prelude::*, ActionMode, ControlAckMode};
-use vita49::{CommandPayload, Spectrum};
+use vita49::{
+    CommandPayload, DataItemFormat, DeviceId, EcefEphemeris, PayloadFormat, RealComplexType,
+    Spectrum, WindowTimeDelta, WindowTimeDeltaInterpretation,
+};
 #[cfg(feature = "serde")]
-use vita49::{Indicators, SignalDataIndicators, Tsf, Tsi};
+use vita49::{Identity, Indicators, SignalDataIndicators, TimingControlMode, Tsf, Tsi};
 
 fn log_init() {
     let _ = env_logger::builder().is_test(true).try_init();
@@ -72,8 +75,10 @@ fn wireshark_parse(packet: &Vrt, check_strs: &[&str]) -> Result<(), Error> {
     // Write packet to a file, then wrap it in a dummy
     // pcap file and run it through tshark. It should
     // never say the packet is malformed.
-    let tmp = NamedTempFile::new()?;
-    packet.to_writer(&mut Writer::new(&tmp), ())?;
+    let mut tmp = NamedTempFile::new()?;
+    packet
+        .write_to(&mut tmp)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
     let tmp_path = tmp.into_temp_path();
 
     // Take the raw binary of the VRT packet, wrap it in
@@ -100,7 +105,7 @@ fn wireshark_parse(packet: &Vrt, check_strs: &[&str]) -> Result<(), Error> {
         log::error!("Wireshark couldn't parse this packet!");
         log::error!("STDERR:\n{}", tshark_out.stderr_str());
         log::error!("STDOUT:\n{}", tshark_out.stdout_str());
-        return Err(Error::other("failed to parse packet"));
+        return Err(Error::new(ErrorKind::Other, "failed to parse packet"));
     }
 
     // Check for specific strings in the output
@@ -110,7 +115,7 @@ fn wireshark_parse(packet: &Vrt, check_strs: &[&str]) -> Result<(), Error> {
             log::error!("STDERR:\n{}", tshark_out.stderr_str());
             log::error!("STDOUT:\n{}", tshark_out.stdout_str());
             log::error!("{err}");
-            return Err(Error::other(err));
+            return Err(Error::new(ErrorKind::Other, err));
         }
     }
 
@@ -152,6 +157,61 @@ fn read_spectral_data() {
     assert!(packet.trailer().is_some());
 }
 
+#[cfg(feature = "serde")]
+#[test]
+fn construct_trailer_context_packet_count() {
+    log_init();
+    let json = include_str!("spectral_data_packet.json5");
+    let mut packet: Vrt = serde_json5::from_str(json).unwrap();
+    packet
+        .trailer_mut()
+        .unwrap()
+        .set_associated_context_packet_count(Some(5))
+        .unwrap();
+    let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+    assert_eq!(
+        parsed.trailer().unwrap().associated_context_packet_count(),
+        Some(5)
+    );
+    assert!(wireshark_parse(&packet, &["Associated context packet count: 5"]).is_ok());
+}
+
+#[test]
+fn file_round_trip() {
+    log_init();
+    let mut data = Vrt::new_signal_data_packet();
+    data.set_stream_id(Some(1));
+    data.set_signal_payload([1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+    let mut context = Vrt::new_context_packet();
+    context.set_stream_id(Some(1));
+    let c = context.payload_mut().context_mut().unwrap();
+    c.set_bandwidth_hz(Some(8e6)).unwrap();
+    c.set_sample_rate_sps(Some(10e6)).unwrap();
+    context.update_packet_size();
+    let mut control = Vrt::new_control_packet();
+    control
+        .payload_mut()
+        .command_mut()
+        .unwrap()
+        .set_message_id(7);
+    control.update_packet_size();
+    let packets = [data, context, control];
+
+    let path = NamedTempFile::new().unwrap().into_temp_path();
+    Vrt::write_all_to_file(&path, &packets).unwrap();
+    let expected_len: usize = packets.iter().map(|p| p.packet_size_bytes()).sum();
+    assert_eq!(
+        std::fs::metadata(&path).unwrap().len() as usize,
+        expected_len
+    );
+    assert_eq!(Vrt::read_all_from_file(&path).unwrap(), packets);
+
+    // A file cut off partway through a packet is an error.
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::write(&path, &bytes[..bytes.len() - 4]).unwrap();
+    assert!(Vrt::read_all_from_file(&path).is_err());
+}
+
 #[cfg(feature = "serde")]
 #[test]
 fn read_context() {
@@ -188,7 +248,7 @@ fn modify_context() {
     assert_eq!(packet.header().packet_type(), PacketType::Context);
     let context = packet.payload_mut().context_mut().unwrap();
     assert_eq!(context.bandwidth_hz(), Some(6e6));
-    context.set_bandwidth_hz(Some(8e6));
+    context.set_bandwidth_hz(Some(8e6)).unwrap();
     assert_eq!(context.bandwidth_hz(), Some(8e6));
 }
 
@@ -208,9 +268,7 @@ fn construct_signal_data_packet() {
     log_init();
     let mut packet = Vrt::new_signal_data_packet();
     packet.set_stream_id(Some(0xDEADBEEF));
-    packet
-        .set_signal_payload(&[1, 2, 3, 4, 5, 6, 7, 8])
-        .unwrap();
+    packet.set_signal_payload([1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
     packet.update_packet_size();
     assert!(wireshark_parse(
         &packet,
@@ -223,34 +281,241 @@ fn construct_signal_data_packet() {
     log::info!("\nConstructed signal data packet:\n{packet:#?}");
 }
 
+#[test]
+fn construct_signal_data_packet_no_stream_id() {
+    log_init();
+    let mut packet = Vrt::new_signal_data_packet_no_stream_id();
+    packet.set_signal_payload([1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+    packet.update_packet_size();
+    let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+    assert_eq!(parsed.stream_id(), None);
+    assert!(wireshark_parse(
+        &packet,
+        &[
+            "Packet type: IF data packet without stream ID (0)",
+            "Data: 0102030405060708"
+        ]
+    )
+    .is_ok());
+}
+
+#[test]
+fn construct_context_for_stream() {
+    log_init();
+    let packet = Vrt::context_for_stream(0xDEADBEEF, 8e6, 100e6, 10e6).unwrap();
+    assert!(wireshark_parse(
+        &packet,
+        &[
+            "Packet type: IF context packet (4)",
+            "Bandwidth: 8.000000 MHz",
+        ],
+    )
+    .is_ok());
+}
+
+#[test]
+fn construct_context_packet_signed_offsets() {
+    log_init();
+    for (offset_hz, offset_str) in [(-2.5e9, "-2.500000 GHz"), (2.5e9, "2.500000 GHz")] {
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_if_band_offset_hz(Some(offset_hz)).unwrap();
+        context.set_rf_ref_freq_offset_hz(Some(offset_hz)).unwrap();
+        packet.update_packet_size();
+
+        let parsed = Vrt::try_from(packet.to_bytes().unwrap().as_slice()).unwrap();
+        let context = parsed.payload().context().unwrap();
+        assert_eq!(context.if_band_offset_hz(), Some(offset_hz));
+        assert_eq!(context.rf_ref_freq_offset_hz(), Some(offset_hz));
+        assert!(wireshark_parse(
+            &packet,
+            &[
+                &format!("IF band offset: {offset_str}"),
+                &format!("RF reference frequency offset: {offset_str}"),
+            ],
+        )
+        .is_ok());
+    }
+}
+
+#[test]
+fn construct_context_changed() {
+    log_init();
+    let mut packet = Vrt::new_context_packet();
+    let context = packet.payload_mut().context_mut().unwrap();
+    context.set_context_changed(true);
+    context.set_bandwidth_hz(Some(8e6)).unwrap();
+    packet.update_packet_size();
+    let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+    assert!(parsed.payload().context().unwrap().context_changed());
+    assert!(wireshark_parse(&packet, &["Context field change indicator: True"]).is_ok());
+}
+
+#[test]
+fn construct_context_timestamp_mode() {
+    log_init();
+    let mut packet = Vrt::new_context_packet();
+    packet
+        .header_mut()
+        .set_timestamp_mode(TimestampMode::PreciseTiming)
+        .unwrap();
+    let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+    assert_eq!(
+        parsed.header().timestamp_mode().unwrap(),
+        TimestampMode::PreciseTiming
+    );
+    assert!(wireshark_parse(&packet, &["Precise timing"]).is_ok());
+}
+
 #[test]
 fn construct_context_packet() {
     log_init();
     let mut packet = Vrt::new_context_packet();
     let context = packet.payload_mut().context_mut().unwrap();
-    context.set_bandwidth_hz(Some(8e6));
+    context.set_bandwidth_hz(Some(8e6)).unwrap();
     let mut spectrum = Spectrum::default();
     spectrum.set_num_transform_points(1280);
     spectrum.set_num_window_points(1280);
-    spectrum.set_resolution_hz(6.25e3);
-    spectrum.set_span_hz(8e6);
+    spectrum.set_resolution_hz(6.25e3).unwrap();
+    spectrum.set_span_hz(8e6).unwrap();
     spectrum.set_f1_index(-1280);
     spectrum.set_f2_index(1279);
+    spectrum.set_num_averages(16);
+    spectrum.set_weighting_factor(2);
+    spectrum
+        .set_window_time_delta_interpretation(WindowTimeDeltaInterpretation::Samples)
+        .unwrap();
+    spectrum.set_window_time_delta(WindowTimeDelta::from_samples(640));
     context.set_spectrum(Some(spectrum));
     packet.set_stream_id(Some(0xDEADBEEF));
     packet.update_packet_size();
+    let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+    let parsed = parsed.payload().context().unwrap().spectrum().unwrap();
+    assert_eq!(parsed.num_averages(), 16);
+    assert_eq!(parsed.weighting_factor(), 2);
+    assert_eq!(parsed.window_time_delta().as_samples(), 640);
     assert!(wireshark_parse(
         &packet,
         &[
             "Packet type: IF context packet (4)",
             "F1 index: -1280",
-            "Resolution: 6.250000 kHz"
+            "F2 index: 1279",
+            "Resolution: 6.250000 kHz",
+            "Span: 8.000000 MHz",
+            "Number of averages: 16",
+            "Weighting factor: 2",
+            "Window time delta: 640",
         ],
     )
     .is_ok());
     log::info!("\nConstructed context packet:\n{packet:#?}");
 }
 
+#[test]
+fn construct_ecef_ephemeris_packet() {
+    log_init();
+    let mut packet = Vrt::new_context_packet();
+    let context = packet.payload_mut().context_mut().unwrap();
+    let mut ephemeris = EcefEphemeris::default();
+    ephemeris.set_position_x_m(Some(1000.0)).unwrap();
+    ephemeris.set_position_y_m(Some(2000.0)).unwrap();
+    ephemeris.set_position_z_m(Some(-500.0)).unwrap();
+    context.set_ecef_ephemeris(Some(ephemeris));
+    packet.update_packet_size();
+    let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+    let parsed = parsed
+        .payload()
+        .context()
+        .unwrap()
+        .ecef_ephemeris()
+        .unwrap();
+    assert_eq!(parsed.position_x_m(), Some(1000.0));
+    assert_eq!(parsed.position_y_m(), Some(2000.0));
+    assert_eq!(parsed.position_z_m(), Some(-500.0));
+    assert!(wireshark_parse(&packet, &["ECEF ephemeris: True"]).is_ok());
+}
+
+#[test]
+fn construct_reference_level_extremes() {
+    log_init();
+    let max = f32::from(i16::MAX) / 128.0;
+    for (level, expected, tshark) in [
+        (1000.0, max, "Reference level: 255.99"),
+        (-1000.0, -256.0, "Reference level: -256"),
+    ] {
+        let mut packet = Vrt::new_context_packet();
+        let context = packet.payload_mut().context_mut().unwrap();
+        context.set_reference_level_db(Some(level)).unwrap();
+        packet.update_packet_size();
+        let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+        assert_eq!(
+            parsed.payload().context().unwrap().reference_level_db(),
+            Some(expected)
+        );
+        assert!(wireshark_parse(&packet, &[tshark]).is_ok());
+    }
+}
+
+#[test]
+fn construct_temperature_packet() {
+    log_init();
+    let mut packet = Vrt::new_context_packet();
+    let context = packet.payload_mut().context_mut().unwrap();
+    context.set_temperature_c(Some(25.5)).unwrap();
+    packet.update_packet_size();
+    let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+    assert_eq!(
+        parsed.payload().context().unwrap().temperature_c(),
+        Some(25.5)
+    );
+    assert!(wireshark_parse(&packet, &["Temperature: 25.5"]).is_ok());
+}
+
+#[test]
+fn construct_payload_format_packet() {
+    log_init();
+    let mut packet = Vrt::new_context_packet();
+    let context = packet.payload_mut().context_mut().unwrap();
+    let mut format = PayloadFormat::default();
+    format
+        .set_real_complex_type(RealComplexType::ComplexCartesian)
+        .unwrap();
+    format
+        .set_data_item_format(DataItemFormat::SignedFixedPoint)
+        .unwrap();
+    format.set_item_packing_field_size(16).unwrap();
+    format.set_data_item_size(16).unwrap();
+    context.set_signal_data_payload_format(Some(format));
+    packet.update_packet_size();
+    let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+    assert_eq!(
+        parsed
+            .payload()
+            .context()
+            .unwrap()
+            .signal_data_payload_format(),
+        Some(&format)
+    );
+    // Complex Cartesian, signed fixed point, 16-bit items in 16-bit
+    // packing fields (sizes are sent minus one).
+    assert_eq!(format.as_u64(), 0x2000_03CF_0000_0000);
+    assert!(wireshark_parse(&packet, &["Signal data format: True", "0x200003cf00000000"]).is_ok());
+}
+
+#[test]
+fn construct_device_id_packet() {
+    log_init();
+    let mut packet = Vrt::new_context_packet();
+    let context = packet.payload_mut().context_mut().unwrap();
+    context.set_device_id(Some(DeviceId::new(0x12_3456, 0xABCD).unwrap()));
+    packet.update_packet_size();
+    let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+    let device_id = parsed.payload().context().unwrap().device_id().unwrap();
+    assert_eq!(device_id.oui(), 0x12_3456);
+    assert_eq!(device_id.device_code(), 0xABCD);
+    assert!(wireshark_parse(&packet, &["Device identifier", "0x123456"]).is_ok());
+}
+
 #[test]
 fn construct_control_packet() {
     log_init();
@@ -260,9 +525,9 @@ fn construct_control_packet() {
     let control = command.payload_mut().control_mut().unwrap();
     control.set_controllee_id(Some(0));
     control.set_controller_uuid(Some(0));
-    control.set_rf_ref_freq_hz(Some(100e6));
-    control.set_sample_rate_sps(Some(128e6));
-    control.set_bandwidth_hz(Some(100e6));
+    control.set_rf_ref_freq_hz(Some(100e6)).unwrap();
+    control.set_sample_rate_sps(Some(128e6)).unwrap();
+    control.set_bandwidth_hz(Some(100e6)).unwrap();
     let mut cam = ControlAckMode::default();
     cam.set_action_mode(ActionMode::Execute);
     cam.set_partial_packet_impl_permitted();
@@ -341,6 +606,74 @@ fn query_ack_parsing() {
     ));
 }
 
+#[test]
+fn ambiguous_ack_cam_parsing() {
+    log_init();
+    let packet = Vrt::new_validation_ack_packet();
+    let bytes = packet.to_bytes().unwrap();
+    // The CAM field follows the header and stream ID. Bits 20, 19,
+    // and 18 select validation, execution, and query ACKs.
+    let cam = u32::from_be_bytes(bytes[8..12].try_into().unwrap()) & !(0b111 << 18);
+    for ack_bits in [0b000, 0b110, 0b111] {
+        let mut bytes = bytes.clone();
+        bytes[8..12].copy_from_slice(&(cam | (ack_bits << 18)).to_be_bytes());
+        assert!(Vrt::try_from(bytes.as_ref()).is_err());
+    }
+}
+
+#[test]
+fn garbage_bytes_parsing() {
+    log_init();
+    // A signal data header claiming 16 words, followed by only a
+    // partial stream ID.
+    let truncated = [0x10, 0x00, 0x00, 0x10, 0xde, 0xad];
+    assert!(matches!(
+        Vrt::try_parse(&truncated),
+        Err(VitaError::BufferTooShort {
+            needed: 64,
+            available: 6
+        })
+    ));
+    assert!(matches!(
+        VitaError::from(Vrt::try_from(&truncated[..]).unwrap_err()),
+        VitaError::Parse(deku::DekuError::Incomplete(_))
+    ));
+
+    // A context packet whose size field cuts its 8-byte bandwidth
+    // field in half.
+    let truncated = [
+        0x40, 0x00, 0x00, 0x04, 0, 0, 0, 1, 0x20, 0, 0, 0, 0, 0, 0, 0,
+    ];
+    let err = Vrt::try_parse(&truncated).unwrap_err();
+    assert!(matches!(
+        err,
+        VitaError::Incomplete {
+            offset: 12,
+            needed: 4
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "incomplete packet: field at byte offset 12 needs 4 more bytes"
+    );
+
+    // Pseudo-random input must produce errors, not panics.
+    let mut state = 0x1234_5678_u32;
+    for len in 0..256 {
+        let garbage: Vec<u8> = (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+        if let Err(e) = Vrt::try_from(&garbage[..]) {
+            assert!(!VitaError::from(e).to_string().is_empty());
+        }
+    }
+}
+
 #[cfg(feature = "serde")]
 #[test]
 fn parse_ack_packet() {
@@ -361,10 +694,14 @@ fn construct_cif7_packet() {
     cif7.set_average();
     cif7.set_median();
     context.cif7 = Some(cif7);
-    context.set_bandwidth_hz(Some(8e6));
-    context.set_bandwidth_hz_attributes(Some(vec![8.0, 7.0]));
-    context.set_sample_rate_sps(Some(10e6));
-    context.set_sample_rate_sps_attributes(Some(vec![11.0, 9.0]));
+    context.set_bandwidth_hz(Some(8e6)).unwrap();
+    context
+        .set_bandwidth_hz_attributes(Some(vec![8.0, 7.0]))
+        .unwrap();
+    context.set_sample_rate_sps(Some(10e6)).unwrap();
+    context
+        .set_sample_rate_sps_attributes(Some(vec![11.0, 9.0]))
+        .unwrap();
     packet.update_packet_size();
     assert_eq!(packet.header().packet_size(), 16);
     assert!(wireshark_parse(
@@ -374,6 +711,57 @@ fn construct_cif7_packet() {
     .is_ok());
 }
 
+#[test]
+#[cfg(feature = "cif7")]
+fn read_cif7_attribute_names() {
+    log_init();
+    let mut packet = Vrt::new_context_packet();
+    let context = packet.payload_mut().context_mut().unwrap();
+    let mut cif7 = Cif7::default();
+    cif7.set_current();
+    cif7.set_average();
+    cif7.set_median();
+    context.cif7 = Some(cif7);
+    context.set_bandwidth_hz(Some(8e6)).unwrap();
+    context
+        .set_bandwidth_hz_attributes(Some(vec![8.0, 7.0]))
+        .unwrap();
+    context.set_sample_rate_sps(Some(10e6)).unwrap();
+    context
+        .set_sample_rate_sps_attributes(Some(vec![11.0, 9.0]))
+        .unwrap();
+    packet.update_packet_size();
+
+    let parsed = Vrt::try_from(&packet.to_bytes().unwrap()[..]).unwrap();
+    let context = parsed.payload().context().unwrap();
+    // The current value is the main field; the attributes hold the rest.
+    assert_eq!(context.bandwidth_hz(), Some(8e6));
+    assert_eq!(
+        context.label_attribute_values(context.bandwidth_hz_attributes()),
+        [("average", 8.0), ("median", 7.0)]
+    );
+    assert_eq!(
+        context.label_attribute_values(context.sample_rate_sps_attributes()),
+        [("average", 11.0), ("median", 9.0)]
+    );
+    // Every present field gets its attributes labeled.
+    let labeled = context.label_attributes();
+    assert_eq!(
+        labeled.iter().map(|(field, _)| *field).collect::<Vec<_>>(),
+        ["bandwidth", "sample_rate"]
+    );
+    for (_, attributes) in &labeled {
+        assert_eq!(
+            attributes.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+            ["average", "median"]
+        );
+    }
+    assert_eq!(
+        labeled[0].1[1].1,
+        context.cif0_fields().bandwidth_attributes[1].to_be_bytes()
+    );
+}
+
 #[cfg(feature = "serde")]
 #[test]
 fn serde_json() {
@@ -382,3 +770,98 @@ fn serde_json() {
     let packet: Vrt = serde_json5::from_str(json).unwrap();
     println!("{}", serde_json::to_string_pretty(&packet).unwrap())
 }
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    log_init();
+    let mut context_packet = Vrt::new_context_packet();
+    let context = context_packet.payload_mut().context_mut().unwrap();
+    context.set_bandwidth_hz(Some(8e6)).unwrap();
+    context.set_rf_ref_freq_hz(Some(100e6)).unwrap();
+    context.set_sample_rate_sps(Some(8e6)).unwrap();
+    context_packet.update_packet_size();
+
+    let mut data_packet = Vrt::new_signal_data_packet();
+    data_packet.set_signal_payload([1, 2, 3, 4]).unwrap();
+
+    for packet in [
+        context_packet.clone(),
+        data_packet,
+        Vrt::new_control_packet(),
+        Vrt::new_exec_ack_packet(),
+        Vrt::new_query_ack_packet(),
+    ] {
+        let json = serde_json::to_string(&packet).unwrap();
+        let parsed: Vrt = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, packet);
+    }
+
+    let json = serde_json::to_value(&context_packet).unwrap();
+    assert!(json.get("sid").is_some());
+    assert!(json["hdr"].get("len").is_some());
+    assert!(json.get("stream_id").is_none());
+    assert!(json.get("header").is_none());
+    let fields = &json["payload"]["Context"]["cif0_fields"];
+    for key in ["bw", "rffreq", "samplerate"] {
+        assert!(fields.get(key).is_some(), "missing key {key}");
+    }
+    assert!(fields.get("bandwidth").is_none());
+
+    let mut packet = Vrt::new_signal_data_packet();
+    packet.set_class_id(Some(ClassIdentifier::new(0xFF5654, 0, 1)));
+    let json = serde_json::to_value(&packet).unwrap();
+    assert_eq!(json["cid"]["icc"], 0);
+    assert_eq!(json["cid"]["pcc"], 1);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_accepts_1_0_names() {
+    log_init();
+    // The same packet as context_packet.json5, written with the 1.0.0 names.
+    let old: Vrt = serde_json5::from_str(include_str!("context_packet_1_0.json5")).unwrap();
+    let new: Vrt = serde_json5::from_str(include_str!("context_packet.json5")).unwrap();
+    assert_eq!(old, new);
+
+    let json = r#"{"word_1": 16733780, "information_class_code": 0, "packet_class_code": 1}"#;
+    let class_id: ClassIdentifier = serde_json::from_str(json).unwrap();
+    assert_eq!(class_id, ClassIdentifier::new(0xFF5654, 0, 1));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_readable_cam() {
+    let mut packet = Vrt::new_control_packet();
+    let command = packet.payload_mut().command_mut().unwrap();
+    let mut cam = command.cam();
+    cam.set_action_mode(ActionMode::Execute);
+    cam.set_timing_control(TimingControlMode::LateAndSpecifiedExecution);
+    command.set_cam(cam);
+    command.set_controllee(Identity::Uuid(7));
+
+    let json = serde_json::to_string_pretty(&packet).unwrap();
+    assert!(json.contains(r#""action_mode": "Execute""#));
+    assert!(json.contains(r#""controllee_id_format": "Uuid128bit""#));
+    assert!(json.contains(r#""timing_control": "LateAndSpecifiedExecution""#));
+    let parsed: Vrt = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, packet);
+
+    // Partial readable CAMs default the rest, and raw words still parse.
+    for (cam_json, action_mode) in [
+        (r#"{"action_mode": "DryRun"}"#, ActionMode::DryRun),
+        ("8388608", ActionMode::DryRun),
+    ] {
+        let cam: ControlAckMode = serde_json5::from_str(cam_json).unwrap();
+        assert_eq!(cam.action_mode(), action_mode);
+        assert!(!cam.controllee_enabled());
+    }
+    assert!(serde_json::from_str::<ControlAckMode>("28672").is_err());
+
+    // Reserved bits survive the readable form.
+    let cam = ControlAckMode::from_u32(0x8020_8001).unwrap();
+    let json = serde_json::to_string(&cam).unwrap();
+    assert!(json.contains(r#""reserved":2129921"#));
+    let parsed: ControlAckMode = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.as_u32(), 0x8020_8001);
+}
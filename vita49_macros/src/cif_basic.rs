@@ -5,9 +5,10 @@
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 use syn::parse::{Parse, ParseStream};
-use syn::{parse2, Ident, Token, Type};
+use syn::{parse2, Attribute, Ident, Token, Type};
 
 struct CifBasicArgs {
+    attrs: Vec<Attribute>,
     cif_name: Ident,
     _comma0: Token![,],
     cif_field: Ident,
@@ -19,6 +20,7 @@ struct CifBasicArgs {
 
 impl Parse for CifBasicArgs {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
         let cif_name = input.parse()?;
         let _comma0: Token![,] = input.parse()?;
         let cif_field = input.parse()?;
@@ -27,6 +29,7 @@ impl Parse for CifBasicArgs {
         let _comma2: Token![,] = input.parse()?;
         let cif_type = input.parse()?;
         Ok(CifBasicArgs {
+            attrs,
             cif_name,
             _comma0,
             cif_field,
@@ -40,6 +43,7 @@ impl Parse for CifBasicArgs {
 
 pub fn cif_basic(input: TokenStream) -> TokenStream {
     let CifBasicArgs {
+        attrs,
         cif_name,
         cif_field,
         cif_field_w_unit,
@@ -90,18 +94,31 @@ pub fn cif_basic(input: TokenStream) -> TokenStream {
         [`update_packet_size()`](Vrt::update_packet_size()) should be executed after running this method."
     );
 
+    // Any attributes given ahead of the CIF name (i.e. extra doc comments)
+    // are appended to the getter and setter.
+    let extra_docs = if attrs.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            #[doc = ""]
+            #(#attrs)*
+        }
+    };
+
     if cif == "cif0" {
         quote! {
             #[doc = #get_fn_doc]
+            #extra_docs
             fn #get_fn(&self) -> Option<&#friendly_type> {
                 self.#cif_fields().#cif_field.as_ref()
             }
             #[doc = #get_attr_fn_doc]
             #[cfg(feature = "cif7")]
-            fn #get_attr_fn(&self) -> &Vec<#friendly_type> {
+            fn #get_attr_fn(&self) -> &::alloc::vec::Vec<#friendly_type> {
                 self.#cif_fields().#cif_attr_field.as_ref()
             }
             #[doc = #set_fn_doc]
+            #extra_docs
             fn #set_fn(&mut self, #cif_field_w_unit: Option<#friendly_type>) {
                 self.#cif_fields_mut().#cif_field = #cif_field_w_unit;
                 if self.#cif_fields().#cif_field.is_some() {
@@ -112,7 +129,7 @@ pub fn cif_basic(input: TokenStream) -> TokenStream {
             }
             #[doc = #set_attr_fn_doc]
             #[cfg(feature = "cif7")]
-            fn #set_attr_fn(&mut self, #cif_attr_field_w_unit: Option<Vec<#friendly_type>>) {
+            fn #set_attr_fn(&mut self, #cif_attr_field_w_unit: Option<::alloc::vec::Vec<#friendly_type>>) {
                 if let Some(vec) = #cif_attr_field_w_unit {
                     self.cif0_mut().#set_cif7_field_fn();
                     self.#cif_fields_mut().#cif_attr_field = vec;
@@ -125,6 +142,7 @@ pub fn cif_basic(input: TokenStream) -> TokenStream {
     } else {
         quote! {
             #[doc = #get_fn_doc]
+            #extra_docs
             fn #get_fn(&self) -> Option<&#friendly_type> {
                 self.#cif_fields()?
                     .#cif_field
@@ -132,7 +150,7 @@ pub fn cif_basic(input: TokenStream) -> TokenStream {
             }
             #[doc = #get_attr_fn_doc]
             #[cfg(feature = "cif7")]
-            fn #get_attr_fn(&self) -> Option<&Vec<#friendly_type>> {
+            fn #get_attr_fn(&self) -> Option<&::alloc::vec::Vec<#friendly_type>> {
                 if let Some(cif_fields) = self.#cif_fields() {
                     Some(cif_fields
                         .#cif_attr_field
@@ -142,6 +160,7 @@ pub fn cif_basic(input: TokenStream) -> TokenStream {
                 }
             }
             #[doc = #set_fn_doc]
+            #extra_docs
             fn #set_fn(&mut self, #cif_field_w_unit: Option<#friendly_type>) {
                 if let Some(v) = #cif_field_w_unit {
                     if self.#cif().is_none() {
@@ -178,7 +197,7 @@ pub fn cif_basic(input: TokenStream) -> TokenStream {
             }
             #[doc = #set_attr_fn_doc]
             #[cfg(feature = "cif7")]
-            fn #set_attr_fn(&mut self, #cif_attr_field_w_unit: Option<Vec<#friendly_type>>) {
+            fn #set_attr_fn(&mut self, #cif_attr_field_w_unit: Option<::alloc::vec::Vec<#friendly_type>>) {
                 if let Some(vec) = #cif_attr_field_w_unit {
                     self.cif0_mut().#set_cif7_field_fn();
                     if self.#cif().is_none() {
@@ -4,7 +4,8 @@
 
 use proc_macro::TokenStream;
 use quote::{format_ident, quote, ToTokens};
-use syn::{parse_macro_input, Ident, ItemStruct};
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, Expr, ExprLit, Ident, ItemStruct, Lit, Meta, Token};
 
 static PRIMITIVES: &[&str] = &[
     "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32",
@@ -12,44 +13,95 @@ static PRIMITIVES: &[&str] = &[
 ];
 
 pub fn cif_fields(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let cif_name = parse_macro_input!(attr as Ident);
+    // Arguments are the CIF name, optionally followed by `ack` for the
+    // ACK response structures.
+    let args = parse_macro_input!(attr with Punctuated::<Ident, Token![,]>::parse_terminated);
+    let mut args = args.into_iter();
+    let Some(cif_name) = args.next() else {
+        return syn::Error::new(proc_macro2::Span::call_site(), "expected a CIF name")
+            .to_compile_error()
+            .into();
+    };
+    let ack = match args.next() {
+        None => false,
+        Some(arg) if arg == "ack" && args.len() == 0 => true,
+        Some(arg) => {
+            return syn::Error::new(arg.span(), "expected `ack` as the only extra argument")
+                .to_compile_error()
+                .into();
+        }
+    };
     let input = parse_macro_input!(item as ItemStruct);
     let struct_name = input.ident;
     let mut expanded_fields = Vec::new();
     let mut expanded_size_lines = Vec::new();
     let mut empty_check_lines = Vec::new();
+    let mut response_lines = Vec::new();
+    let mut diff_lines = Vec::new();
+    let mut clear_attr_lines = Vec::new();
+    let mut overlay_lines = Vec::new();
+    let mut set_response_lines = Vec::new();
+    let mut sync_lines = Vec::new();
+    let mut field_bytes_lines = Vec::new();
+    let mut attribute_bytes_lines = Vec::new();
 
     for field in input.fields {
         let cif_field = field.clone().ident.unwrap();
         let cif_type = field.clone().ty;
 
+        // An optional `#[serde_name = "..."]` attribute renames the field
+        // (and its CIF7 attributes) when serialized. The Rust name is
+        // still accepted when deserializing.
+        let serde_name = field.attrs.iter().find_map(|attr| match &attr.meta {
+            Meta::NameValue(nv) if nv.path.is_ident("serde_name") => match &nv.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(s), ..
+                }) => Some(s.value()),
+                _ => panic!("serde_name must be a string literal"),
+            },
+            _ => None,
+        });
+        let (serde_rename, serde_attr_rename) = match serde_name {
+            Some(name) => {
+                let attr_name = format!("{name}_attributes");
+                let alias = cif_field.to_string();
+                let attr_alias = format!("{alias}_attributes");
+                (
+                    quote! { #[cfg_attr(feature = "serde", serde(rename = #name, alias = #alias))] },
+                    quote! { #[cfg_attr(feature = "serde", serde(rename = #attr_name, alias = #attr_alias))] },
+                )
+            }
+            None => (quote! {}, quote! {}),
+        };
+
         let field_doc = format!("{cif_field} data field");
         let attr_doc = format!("{cif_field} data attributes field (only used if CIF7 is enabled)");
 
         let attr_field = format_ident!("{}_attributes", cif_field);
         // CIF fields other than cif0 are optional, so we have to add an unwrap()
-        let (main_cond, attr_cond) =
-            if cif_name == "cif0" && !format!("{struct_name}").contains("Ack") {
-                (
-                    format!("{cif_name}.{cif_field}() && cif7_opts.current_val"),
-                    format!("{cif_name}.{cif_field}() && cif7_opts.num_extra_attrs > 0"),
-                )
-            } else {
-                (
-                    format!("{cif_name}.unwrap().{cif_field}() && cif7_opts.current_val"),
-                    format!("{cif_name}.unwrap().{cif_field}() && cif7_opts.num_extra_attrs > 0"),
-                )
-            };
+        let (main_cond, attr_cond) = if cif_name == "cif0" && !ack {
+            (
+                format!("{cif_name}.{cif_field}() && cif7_opts.current_val"),
+                format!("{cif_name}.{cif_field}() && cif7_opts.num_extra_attrs > 0"),
+            )
+        } else {
+            (
+                format!("{cif_name}.unwrap().{cif_field}() && cif7_opts.current_val"),
+                format!("{cif_name}.unwrap().{cif_field}() && cif7_opts.num_extra_attrs > 0"),
+            )
+        };
 
         let expanded = quote! {
             #[doc = #field_doc]
             #[deku(cond = #main_cond)]
+            #serde_rename
             pub #cif_field: Option<#cif_type>,
 
             #[doc = #attr_doc]
             #[cfg(feature = "cif7")]
             #[deku(cond = #attr_cond, count = "cif7_opts.num_extra_attrs")]
-            pub #attr_field: Vec<#cif_type>,
+            #serde_attr_rename
+            pub #attr_field: ::alloc::vec::Vec<#cif_type>,
         };
         expanded_fields.push(expanded);
 
@@ -58,11 +110,11 @@ pub fn cif_fields(attr: TokenStream, item: TokenStream) -> TokenStream {
         let expanded = if PRIMITIVES.contains(&cif_type_string.as_str()) {
             quote! {
                 if let Some(v) = &self.#cif_field {
-                    acc += (std::mem::size_of_val(v) / std::mem::size_of::<u32>()) as u16;
+                    acc += core::mem::size_of_val(v) / core::mem::size_of::<u32>();
                 }
                 #[cfg(feature = "cif7")]
                 if let Some(v) = self.#attr_field.first() {
-                    acc += ((std::mem::size_of_val(v) * self.#attr_field.len()) / std::mem::size_of::<u32>()) as u16;
+                    acc += (core::mem::size_of_val(v) * self.#attr_field.len()) / core::mem::size_of::<u32>();
                 }
             }
         } else {
@@ -72,7 +124,7 @@ pub fn cif_fields(attr: TokenStream, item: TokenStream) -> TokenStream {
                 }
                 #[cfg(feature = "cif7")]
                 if let Some(v) = self.#attr_field.first() {
-                    acc += v.size_words() * (self.#attr_field.len() as u16);
+                    acc += v.size_words() * self.#attr_field.len();
                 }
             }
         };
@@ -90,6 +142,78 @@ pub fn cif_fields(attr: TokenStream, item: TokenStream) -> TokenStream {
             }
         };
         empty_check_lines.push(expanded);
+
+        let cif_field_str = cif_field.to_string();
+        let expanded = quote! {
+            if let Some(r) = self.#cif_field {
+                ret.push((#cif_field_str, r));
+            }
+        };
+        response_lines.push(expanded);
+
+        let attr_field_str = attr_field.to_string();
+        let expanded = quote! {
+            if self.#cif_field != other.#cif_field {
+                ret.push(crate::FieldDiff {
+                    field: #cif_field_str,
+                    left: ::alloc::format!("{:?}", self.#cif_field),
+                    right: ::alloc::format!("{:?}", other.#cif_field),
+                });
+            }
+            #[cfg(feature = "cif7")]
+            if self.#attr_field != other.#attr_field {
+                ret.push(crate::FieldDiff {
+                    field: #attr_field_str,
+                    left: ::alloc::format!("{:?}", self.#attr_field),
+                    right: ::alloc::format!("{:?}", other.#attr_field),
+                });
+            }
+        };
+        diff_lines.push(expanded);
+
+        clear_attr_lines.push(quote! {
+            self.#attr_field.clear();
+        });
+
+        field_bytes_lines.push(quote! {
+            #cif_field_str => self.#cif_field.as_ref().and_then(crate::cif0::field_to_bytes),
+        });
+
+        attribute_bytes_lines.push(quote! {
+            #cif_field_str => self.#attr_field.iter().map(crate::cif0::field_to_bytes).collect(),
+        });
+
+        let set_bit = format_ident!("set_{}", cif_field);
+        let unset_bit = format_ident!("unset_{}", cif_field);
+        set_response_lines.push(quote! {
+            #cif_field_str => {
+                cif.#set_bit();
+                self.#cif_field = Some(response);
+            }
+        });
+        sync_lines.push(quote! {
+            #[cfg(feature = "cif7")]
+            let present = self.#cif_field.is_some() || !self.#attr_field.is_empty();
+            #[cfg(not(feature = "cif7"))]
+            let present = self.#cif_field.is_some();
+            if present {
+                cif.#set_bit();
+            } else {
+                cif.#unset_bit();
+            }
+        });
+
+        overlay_lines.push(quote! {
+            #[cfg(feature = "cif7")]
+            if newer.#cif_field.is_some() || !newer.#attr_field.is_empty() {
+                self.#cif_field = newer.#cif_field.clone();
+                self.#attr_field = newer.#attr_field.clone();
+            }
+            #[cfg(not(feature = "cif7"))]
+            if newer.#cif_field.is_some() {
+                self.#cif_field = newer.#cif_field.clone();
+            }
+        });
     }
 
     let cif_name_str = cif_name.to_string();
@@ -98,8 +222,9 @@ pub fn cif_fields(attr: TokenStream, item: TokenStream) -> TokenStream {
         None => String::new(),
         Some(f) => f.to_uppercase().collect::<String>() + cif_name_chars.as_str(),
     };
+    let cif_ident = format_ident!("{cif_type_name}");
     cif_type_name = format!("&{cif_type_name}");
-    if cif_name != "cif0" || format!("{struct_name}").contains("Ack") {
+    if cif_name != "cif0" || ack {
         cif_type_name = format!("Option<{cif_type_name}>");
     }
     let deku_ctx =
@@ -107,6 +232,89 @@ pub fn cif_fields(attr: TokenStream, item: TokenStream) -> TokenStream {
     let struct_doc = format!("Structure for all {cif_name} data fields (not indicators)");
     let size_doc = format!("Gets the size of all {cif_name} data fields in 32-bit words");
     let empty_doc = format!("Returns true if all {cif_name} data fields are empty, false if not");
+    let clear_attr_doc = format!("Removes the CIF7 attributes of all {cif_name} data fields");
+
+    // ACK structures get an extra helper to list the set response fields
+    let responses_fn = if ack {
+        let responses_doc = format!(
+            "Gets the name and ACK response of every {cif_name} field that is set, in packet order"
+        );
+        let set_response_doc = format!(
+            "Sets the ACK response of the {cif_name} field called `name` and its indicator bit in `cif`, \
+            returning false if there's no such field"
+        );
+        quote! {
+            #[doc = #responses_doc]
+            pub fn responses(&self) -> ::alloc::vec::Vec<(&'static str, crate::AckResponse)> {
+                let mut ret = ::alloc::vec::Vec::new();
+                #(#response_lines)*
+                ret
+            }
+
+            #[doc = #set_response_doc]
+            pub(crate) fn set_response(
+                &mut self,
+                cif: &mut #cif_ident,
+                name: &str,
+                response: crate::AckResponse,
+            ) -> bool {
+                match name {
+                    #(#set_response_lines)*
+                    _ => return false,
+                }
+                true
+            }
+        }
+    } else {
+        let diff_doc =
+            format!("Lists the {cif_name} data fields that differ from `other`, in packet order");
+        let overlay_doc = format!(
+            "Copies every {cif_name} data field that is set in `newer` into `self`, leaving the others untouched"
+        );
+        let field_bytes_doc =
+            format!("Gets the on-wire bytes of the {cif_name} field called `name`, if it's set");
+        let attribute_bytes_doc = format!(
+            "Gets the on-wire bytes of each CIF7 attribute of the {cif_name} field called `name`"
+        );
+        let sync_doc = format!(
+            "Sets or clears each {cif_name} field indicator bit in `cif` to match whether the field is present"
+        );
+        quote! {
+            #[doc = #diff_doc]
+            pub fn diff(&self, other: &Self) -> ::alloc::vec::Vec<crate::FieldDiff> {
+                let mut ret = ::alloc::vec::Vec::new();
+                #(#diff_lines)*
+                ret
+            }
+
+            #[doc = #overlay_doc]
+            pub fn overlay(&mut self, newer: &Self) {
+                #(#overlay_lines)*
+            }
+
+            #[doc = #field_bytes_doc]
+            pub(crate) fn field_bytes(&self, name: &str) -> Option<::alloc::vec::Vec<u8>> {
+                match name {
+                    #(#field_bytes_lines)*
+                    _ => None,
+                }
+            }
+
+            #[doc = #attribute_bytes_doc]
+            #[cfg(feature = "cif7")]
+            pub(crate) fn attribute_bytes(&self, name: &str) -> Option<::alloc::vec::Vec<::alloc::vec::Vec<u8>>> {
+                match name {
+                    #(#attribute_bytes_lines)*
+                    _ => None,
+                }
+            }
+
+            #[doc = #sync_doc]
+            pub(crate) fn sync_indicators(&self, cif: &mut #cif_ident) {
+                #(#sync_lines)*
+            }
+        }
+    };
 
     let expanded = quote! {
         #[doc = #struct_doc]
@@ -122,7 +330,7 @@ pub fn cif_fields(attr: TokenStream, item: TokenStream) -> TokenStream {
 
         impl #struct_name {
             #[doc = #size_doc]
-            pub fn size_words(&self) -> u16 {
+            pub fn size_words(&self) -> usize {
                 let mut acc = 0;
                 #(#expanded_size_lines)*
                 acc
@@ -133,6 +341,14 @@ pub fn cif_fields(attr: TokenStream, item: TokenStream) -> TokenStream {
                 #(#empty_check_lines)*
                 true
             }
+
+            #[doc = #clear_attr_doc]
+            #[cfg(feature = "cif7")]
+            pub fn clear_attributes(&mut self) {
+                #(#clear_attr_lines)*
+            }
+
+            #responses_fn
         }
     };
 
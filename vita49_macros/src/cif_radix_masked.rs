@@ -101,14 +101,21 @@ pub fn cif_radix_masked(input: TokenStream) -> TokenStream {
         format!("Get the current {cif_field_w_unit}. If `None` is returned, the field is unset.");
     let set_fn_doc = format!(
         "Set the {cif_field_w_unit}. If `None` is passed, the field will be unset.\n\n\
-        [`update_packet_size()`](Vrt::update_packet_size()) should be executed after running this method."
+        Values outside the range of the fixed-point field are clamped to its \
+        minimum or maximum rather than wrapping.\n\n\
+        [`update_packet_size()`](Vrt::update_packet_size()) should be executed after running this method.\n\n\
+        # Errors\n\
+        Returns [`VitaError::OutOfRange`](crate::VitaError::OutOfRange) for NaN, leaving the field unchanged."
     );
     let get_attr_fn_doc = format!(
         "Get the current {cif_attr_field_w_unit} (CIF7 attributes). If `None` is returned, the field is unset."
     );
     let set_attr_fn_doc = format!(
         "Set the {cif_attr_field_w_unit} (CIF7 attributes). If `None` is passed, the field will be unset.\n\n\
-        [`update_packet_size()`](Vrt::update_packet_size()) should be executed after running this method."
+        Values are clamped like those passed to the main setter.\n\n\
+        [`update_packet_size()`](Vrt::update_packet_size()) should be executed after running this method.\n\n\
+        # Errors\n\
+        Returns [`VitaError::OutOfRange`](crate::VitaError::OutOfRange) if any value is NaN, leaving the field unchanged."
     );
 
     let masked_base_type_str = masked_base_type.to_token_stream().to_string();
@@ -132,35 +139,40 @@ pub fn cif_radix_masked(input: TokenStream) -> TokenStream {
             }
             #[doc = #get_attr_fn_doc]
             #[cfg(feature = "cif7")]
-            fn #get_attr_fn(&self) -> Vec<#friendly_type> {
+            fn #get_attr_fn(&self) -> ::alloc::vec::Vec<#friendly_type> {
                 self.#cif_fields().#cif_attr_field.iter().map(|v| {
                     let v_masked = (v & (#mask as #base_type)) as #masked_base_type;
                     #fixed_type::from_bits(v_masked).to_num()
                 }).collect()
             }
             #[doc = #set_fn_doc]
-            fn #set_fn(&mut self, #cif_field_w_unit: Option<#friendly_type>) {
+            fn #set_fn(&mut self, #cif_field_w_unit: Option<#friendly_type>) -> Result<(), crate::VitaError> {
                 if let Some(v) = #cif_field_w_unit {
-                    self.#cif_fields_mut().#cif_field = Some(#fixed_type::from_num(v).to_bits() as #base_type);
+                    let bits = (crate::fixed_point::saturating_from_f64::<#fixed_type>(v.into())?.to_bits() as #base_type) & (#mask as #base_type);
+                    self.#cif_fields_mut().#cif_field = Some(bits);
                     self.#cif_mut().#set_cif_field_fn();
                 } else {
                     self.#cif_fields_mut().#cif_field = None;
                     self.#cif_mut().#unset_cif_field_fn();
                 }
+                Ok(())
             }
             #[doc = #set_attr_fn_doc]
             #[cfg(feature = "cif7")]
-            fn #set_attr_fn(&mut self, #cif_attr_field_w_unit: Option<Vec<#friendly_type>>) {
+            fn #set_attr_fn(&mut self, #cif_attr_field_w_unit: Option<::alloc::vec::Vec<#friendly_type>>) -> Result<(), crate::VitaError> {
                 if let Some(vec) = #cif_attr_field_w_unit {
+                    let bits = vec
+                        .iter()
+                        .map(|v| Ok((crate::fixed_point::saturating_from_f64::<#fixed_type>((*v).into())?.to_bits() as #base_type) & (#mask as #base_type)))
+                        .collect::<Result<::alloc::vec::Vec<_>, crate::VitaError>>()?;
                     self.cif0_mut().#set_cif7_field_fn();
-                    self.#cif_fields_mut().#cif_attr_field = vec.iter()
-                        .map(|v| #fixed_type::from_num(*v).to_bits() as #base_type)
-                        .collect();
+                    self.#cif_fields_mut().#cif_attr_field = bits;
                     self.#cif_mut().#set_cif_field_fn();
                 } else {
                     #[cfg(feature = "cif7")]
                     self.#cif_fields_mut().#cif_attr_field.clear();
                 }
+                Ok(())
             }
         }
     } else {
@@ -176,7 +188,7 @@ pub fn cif_radix_masked(input: TokenStream) -> TokenStream {
             }
             #[doc = #get_attr_fn_doc]
             #[cfg(feature = "cif7")]
-            fn #get_attr_fn(&self) -> Vec<#friendly_type> {
+            fn #get_attr_fn(&self) -> ::alloc::vec::Vec<#friendly_type> {
                 if let Some(cif_fields) = self.#cif_fields() {
                     cif_fields
                         .#cif_attr_field
@@ -187,12 +199,13 @@ pub fn cif_radix_masked(input: TokenStream) -> TokenStream {
                         })
                         .collect()
                 } else {
-                    Vec::new()
+                    ::alloc::vec::Vec::new()
                 }
             }
             #[doc = #set_fn_doc]
-            fn #set_fn(&mut self, #cif_field_w_unit: Option<#friendly_type>) {
+            fn #set_fn(&mut self, #cif_field_w_unit: Option<#friendly_type>) -> Result<(), crate::VitaError> {
                 if let Some(v) = #cif_field_w_unit {
+                    let bits = (crate::fixed_point::saturating_from_f64::<#fixed_type>(v.into())?.to_bits() as #base_type) & (#mask as #base_type);
                     if self.#cif().is_none() {
                         self.cif0_mut().#enable_cif_fn();
                         *self.#cif_mut() = Some(#cif_type_name::default())
@@ -203,7 +216,7 @@ pub fn cif_radix_masked(input: TokenStream) -> TokenStream {
                         *self.#cif_fields_mut() = Some(#cif_fields_type_name::default());
                     }
                     self.#cif_fields_mut().as_mut().unwrap().#cif_field = Some(
-                        #fixed_type::from_num(v).to_bits() as #base_type
+                        bits
                     );
                 } else {
                     let mut clear_cif = false;
@@ -226,11 +239,16 @@ pub fn cif_radix_masked(input: TokenStream) -> TokenStream {
                         *self.#cif_fields_mut() = None;
                     }
                 }
+                Ok(())
             }
             #[doc = #set_attr_fn_doc]
             #[cfg(feature = "cif7")]
-            fn #set_attr_fn(&mut self, #cif_attr_field_w_unit: Option<Vec<#friendly_type>>) {
+            fn #set_attr_fn(&mut self, #cif_attr_field_w_unit: Option<::alloc::vec::Vec<#friendly_type>>) -> Result<(), crate::VitaError> {
                 if let Some(vec) = #cif_attr_field_w_unit {
+                    let bits = vec
+                        .iter()
+                        .map(|v| Ok((crate::fixed_point::saturating_from_f64::<#fixed_type>((*v).into())?.to_bits() as #base_type) & (#mask as #base_type)))
+                        .collect::<Result<::alloc::vec::Vec<_>, crate::VitaError>>()?;
                     self.cif0_mut().#set_cif7_field_fn();
                     if self.#cif().is_none() {
                         self.cif0_mut().#enable_cif_fn();
@@ -241,15 +259,13 @@ pub fn cif_radix_masked(input: TokenStream) -> TokenStream {
                     if self.#cif_fields().is_none() {
                         *self.#cif_fields_mut() = Some(#cif_fields_type_name::default());
                     }
-                    self.#cif_fields_mut().as_mut().unwrap().#cif_attr_field = vec
-                        .iter()
-                        .map(|v| #fixed_type::from_num(*v).to_bits() as #base_type)
-                        .collect();
+                    self.#cif_fields_mut().as_mut().unwrap().#cif_attr_field = bits;
                 } else {
                     if let Some(f) = self.#cif_fields_mut() {
                         f.#cif_attr_field.clear();
                     }
                 }
+                Ok(())
             }
         }
     }
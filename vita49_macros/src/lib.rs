@@ -34,6 +34,10 @@ pub fn cif_field(input: TokenStream) -> TokenStream {
 /// CIF7 attributes members associated with each CIF field (e.g.
 /// bandwidth_attributes) and generates a size_words() method
 /// based on each field's type.
+///
+/// Takes the CIF name (e.g. `#[cif_fields(cif0)]`), followed by `ack`
+/// for the structures holding ACK responses instead of field values
+/// (e.g. `#[cif_fields(cif0, ack)]`).
 #[proc_macro_attribute]
 pub fn cif_fields(attr: TokenStream, item: TokenStream) -> TokenStream {
     cif_fields::cif_fields(attr, item)
@@ -51,6 +55,9 @@ pub fn todo_cif_field(input: TokenStream) -> TokenStream {
 /// field that is represented internally as a simple type.
 /// The same type is used for the internal representation and
 /// the user-facing value.
+///
+/// Doc comments placed before the CIF name are appended to the
+/// generated getter and setter docs.
 #[proc_macro]
 pub fn cif_basic(input: TokenStream) -> TokenStream {
     cif_basic::cif_basic(input.into()).into()